@@ -0,0 +1,79 @@
+// Derive of webapi ObjectType, type name is name of struct and schema of type
+// is submitted to inventory, so /schema endpoint gets it without manual edit.
+//
+// #[object_type(crate = "...")] sets path of webapi module, crate::webapi by default,
+// downstream services set path of linked library, e.g. my_rust_hyper::webapi.
+// #[object_type(path = ...)] submits built-in path of type to routes::path.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Expr, LitStr, Path};
+
+#[proc_macro_derive(ObjectType, attributes(object_type))]
+pub fn derive_object_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "ObjectType is not supported for generics",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let mut krate: Path = syn::parse_quote!(crate::webapi);
+    let mut path: Option<Expr> = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("object_type"))
+    {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse::<LitStr>()?.parse()?;
+                Ok(())
+            } else if meta.path.is_ident("path") {
+                path = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate` or `path`"))
+            }
+        });
+        if let Err(e) = result {
+            return e.to_compile_error().into();
+        }
+    }
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let object_path = path.map(|p| {
+        quote! {
+            inventory::submit! {
+                #krate::routes::path::ObjectPath {
+                    type_name: #type_name,
+                    path: #p,
+                }
+            }
+        }
+    });
+    let expanded = quote! {
+        impl #krate::traits::ObjectType for #name {
+            fn get_type_name() -> &'static str {
+                #type_name
+            }
+        }
+
+        const _: () = {
+            fn schema() -> schemars::schema::RootSchema {
+                schemars::schema_for!(#name)
+            }
+            inventory::submit! {
+                #krate::schema::ObjectSchema {
+                    type_name: #type_name,
+                    schema: schema,
+                }
+            }
+            #object_path
+        };
+    };
+    expanded.into()
+}
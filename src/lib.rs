@@ -0,0 +1,12 @@
+// Library of webapi service, downstream services link it to register own
+// commands and events in webapi::registry::HandlerRegistry and run same workers.
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate strum_macros;
+#[macro_use]
+extern crate my_rust_hyper_derive;
+
+extern crate chrono;
+
+pub mod webapi;
@@ -1,11 +1,5 @@
 #[macro_use]
 extern crate log;
-#[macro_use]
-extern crate strum_macros;
-
-extern crate chrono;
-
-mod webapi;
 
 use dotenv::dotenv;
 
@@ -14,8 +8,7 @@ use dove::container::*;
 #[cfg(feature = "amqp")]
 use dove::url;
 use hyper::service::{make_service_fn, service_fn};
-use hyper::{Body, Error, Server};
-use std::collections::HashMap;
+use hyper::{Error, Server};
 use std::env;
 use std::fs;
 use std::net::SocketAddr;
@@ -23,15 +16,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
-use webapi::{access, connectors, executors, publishers, router, routes, settings, workers};
-
-pub fn sync_command_handler(
-    _body: Body,
-    _param: HashMap<String, String>,
-) -> (Body, HashMap<String, String>) {
-    info!("command handler");
-    (Body::empty(), HashMap::<String, String>::new())
-}
+use my_rust_hyper::webapi::{
+    access, connectors, executors, handlers, providers, publishers, registry, router, routes,
+    settings, workers,
+};
 
 #[tokio::main]
 async fn main() {
@@ -49,13 +37,21 @@ async fn main() {
     const DEFAULT_LOG_SETTINGS: &str = "log4rs.yml";
     const ENV_LOG_SETTINGS: &str = "MY_LOG_SETTINGS";
 
+    const DEFAULT_SERVICE_NAME: &str = "webapi";
+    const ENV_SERVICE_NAME: &str = "MY_SERVICE_NAME";
+
     const ENV_DATABASE_URL: &str = "DATABASE_URL";
+    #[cfg(feature = "amqp")]
     const ENV_MQ_BROKER: &str = "MQ_BROKER";
+    const ENV_DEV_SEED: &str = "MY_DEV_SEED"; //any value seeds development users
 
+    //only one database feature is enabled, see connectors
     #[cfg(feature = "postgres")]
-    const DB_PG: &str = "pg";
+    const DB_NAME: &str = "pg";
     #[cfg(feature = "mysql")]
-    const DB_MYSQL: &str = "mysql";
+    const DB_NAME: &str = "mysql";
+    #[cfg(feature = "sqlite")]
+    const DB_NAME: &str = "sqlite";
 
     #[cfg(feature = "amqp")]
     const MQ_AMQP: &str = "ampq";
@@ -90,64 +86,130 @@ async fn main() {
     let app_setting_file: String =
         env::var(ENV_APP_SETTINGS).unwrap_or(String::from(DEFAULT_APP_SETTINGS));
 
-    let app_settings: settings::AppSettings =
+    let mut app_settings: settings::AppSettings =
         serde_json::from_str(&fs::read_to_string(app_setting_file).unwrap()).unwrap();
 
-    #[cfg(feature = "postgres")]
-    let db_connection_string =
-        env::var(ENV_DATABASE_URL).unwrap_or(app_settings.database.get(DB_PG).unwrap().to_string());
-    #[cfg(feature = "mysql")]
-    let db_connection_string = env::var(ENV_DATABASE_URL)
-        .unwrap_or(app_settings.database.get(DB_MYSQL).unwrap().to_string());
-
-    let data_connector = connectors::DataConnector::new(app_settings.error, &db_connection_string)
-        .await
-        .expect("error while data connector initialize");
+    let data_connector = match app_settings.storage.unwrap_or(settings::Storage::Sql) {
+        settings::Storage::Sql => {
+            let db_connection_string = env::var(ENV_DATABASE_URL)
+                .unwrap_or(app_settings.database.get(DB_NAME).unwrap().to_string());
+            connectors::DataConnector::new(
+                app_settings.get_error_catalogue(),
+                &db_connection_string,
+                env::var(ENV_DEV_SEED).is_ok(),
+            )
+            .await
+        }
+        settings::Storage::Memory => {
+            info!("memory storage, data is lost on exit");
+            connectors::DataConnector::new_memory(app_settings.get_error_catalogue()).await
+        }
+    }
+    .expect("error while data connector initialize");
+    let (access_checker_control_sender, access_checker_control_receiver) =
+        mpsc::channel::<workers::SignalCode>(5);
     let access_checker = access::AccessChecker::from_data_connector(
         &data_connector,
         &app_settings.access.authentication,
+        access_checker_control_sender.clone(),
     )
     .await
     .expect("error while access checker initialize");
 
     #[cfg(feature = "amqp")]
-    let mq_connection_string =
-        env::var(ENV_MQ_BROKER).unwrap_or(app_settings.mq_broker.get(MQ_AMQP).unwrap().to_string());
-
-    let url = url::Url::parse(&mq_connection_string).expect("error parsing url");
-    let opts = ConnectionOptions {
-        username: url.username.map(|s| s.to_string()),
-        password: url.password.map(|s| s.to_string()),
-        sasl_mechanism: url.username.map_or(Some(SaslMechanism::Anonymous), |_| {
-            Some(SaslMechanism::Plain)
-        }),
-        idle_timeout: Some(Duration::from_secs(5)),
-    };
+    let (connection, mq_session, broker) = {
+        let mq_connection_string = env::var(ENV_MQ_BROKER)
+            .unwrap_or(app_settings.mq_broker.get(MQ_AMQP).unwrap().to_string());
+
+        let url = url::Url::parse(&mq_connection_string).expect("error parsing url");
+        let opts = ConnectionOptions {
+            username: url.username.map(|s| s.to_string()),
+            password: url.password.map(|s| s.to_string()),
+            sasl_mechanism: url.username.map_or(Some(SaslMechanism::Anonymous), |_| {
+                Some(SaslMechanism::Plain)
+            }),
+            idle_timeout: Some(Duration::from_secs(5)),
+            buffer_size: None,
+            tcp_nodelay: None,
+        };
+
+        let container = Container::new()
+            .expect("error while create mq container")
+            .start();
+
+        let broker = format!("{}:{}", url.hostname, url.port);
+
+        let connection = container
+            .connect(broker.clone(), opts)
+            .await
+            .expect("error while ceate mq connection");
 
-    let container = Container::new()
-        .expect("error while create mq container")
-        .start();
+        let session = connection
+            .new_session(None)
+            .await
+            .expect("error while create mq session");
 
-    let broker = format!("{}:{}", url.hostname, url.port);
+        container.start();
 
-    let connection = container
-        .connect(broker.clone(), opts)
-        .await
-        .expect("error while ceate mq connection");
+        (
+            connection,
+            Some(Arc::new(providers::MqSession::new(session))),
+            broker,
+        )
+    };
+    //without amqp mq provider has no session, commands and events go by http
+    #[cfg(not(feature = "amqp"))]
+    let (mq_session, broker): (Option<Arc<providers::MqSession>>, String) = (None, String::new());
+
+    let mq_service_path = match app_settings.path.get(&providers::Proto::Mq.to_string()) {
+        Some(p) if mq_session.is_some() => {
+            let mut sp = p.clone();
+            router::Router::update_host_mask(&host, &broker, &mut sp);
+            Some(sp)
+        }
+        _ => None,
+    };
 
-    let _session = connection
-        .new_session(None)
+    let mut mq_addresses = Vec::<String>::new();
+    if let Some(sp) = &mq_service_path {
+        mq_addresses.push(sp.request.as_ref().unwrap().to_string());
+        mq_addresses.push(sp.event.as_ref().unwrap().to_string());
+    }
+
+    let sync_reply_wait =
+        Duration::from_secs(app_settings.max_sync_command_reply_wait_in_seconds as u64);
+    let async_reply_wait_in_hours = app_settings.max_async_command_reply_wait_in_hours;
+    let access_reload_interval_in_seconds = app_settings.access.reload_interval_in_seconds;
+
+    let http_provider = providers::HttpProvider::new(sync_reply_wait)
         .await
-        .expect("error while create mq session");
+        .expect("error while http provider initialize");
 
-    container.start();
+    let mq_provider = providers::MqProvider::new(
+        mq_session,
+        mq_service_path.map(|sp| sp.reply_to),
+        sync_reply_wait,
+    )
+    .await
+    .expect("error while mq provider initialize");
 
     let data_connector_arc = Arc::new(data_connector);
     let access_checker_arc = Arc::new(access_checker);
 
-    let router = router::Router::new(
+    //commands and subscriptions of service are registered here, before route is added to router
+    let mut handler_registry = registry::HandlerRegistry::new();
+    handlers::register(&mut handler_registry, data_connector_arc.clone());
+    let service_name: String =
+        env::var(ENV_SERVICE_NAME).unwrap_or(String::from(DEFAULT_SERVICE_NAME));
+    if let Some(r) = app_settings.service.get_mut(&service_name) {
+        handler_registry.update_route(r);
+    }
+
+    let mut router = router::Router::new(
         data_connector_arc.clone(),
         access_checker_arc.clone(),
+        http_provider.clone(),
+        mq_provider.clone(),
         app_settings.router,
         app_settings.path,
         app_settings.service,
@@ -156,6 +218,7 @@ async fn main() {
     )
     .await
     .expect("error while remote router initialize");
+    handler_registry.update_schema(&mut router.schema);
 
     let router_arc = Arc::new(router);
 
@@ -173,20 +236,31 @@ async fn main() {
         event_publisher_control_sender.clone(),
         mq_sender_control_sender.clone(),
         mq_receiver_control_sender.clone(),
+        access_checker_control_sender.clone(),
     ];
 
     let command_executor = executors::CommandExecutor::new(
         data_connector_arc.clone(),
         access_checker_arc.clone(),
         router_arc.clone(),
+        http_provider.clone(),
+        mq_provider.clone(),
         command_executor_control_sender.clone(),
+        app_settings.max_async_command_queue_length,
+        &service_name,
     )
     .await
     .expect("error while command executor initialize");
     let event_publisher = publishers::EventPublisher::new(
+        data_connector_arc.clone(),
         access_checker_arc.clone(),
         router_arc.clone(),
+        http_provider.clone(),
+        mq_provider.clone(),
         event_publisher_control_sender.clone(),
+        app_settings.max_event_delivery_concurrency,
+        app_settings.max_event_delivery_wait_in_seconds,
+        app_settings.event_proto_preference.clone(),
     )
     .await
     .expect("error while event publisher initialize");
@@ -196,15 +270,37 @@ async fn main() {
 
     let local_rt_arc = router_arc.clone();
 
-    let mut hhm = HashMap::<&str, routes::service::Handler>::new();
-    hhm.insert("s1", sync_command_handler);
-    let handler_arc = Arc::new(hhm);
+    //built-in commands and events need router and publisher, commands of service replace them
+    let mut service_registry = registry::HandlerRegistry::new();
+    handlers::register_service(
+        &mut service_registry,
+        data_connector_arc.clone(),
+        access_checker_arc.clone(),
+        router_arc.clone(),
+        event_publisher_arc.clone(),
+    );
+    service_registry.merge(handler_registry);
+
+    let handler_arc = Arc::new(service_registry);
+
+    let mq_dc = data_connector_arc.clone();
+    let mq_ac = access_checker_arc.clone();
+    let mq_ce = command_executor_arc.clone();
+    let mq_rt = router_arc.clone();
+    let mq_hr = handler_arc.clone();
+    let ce_dc = data_connector_arc.clone();
+    let ce_ac = access_checker_arc.clone();
+    let ce_ce = command_executor_arc.clone();
+    let ce_rt = router_arc.clone();
+    let ce_hr = handler_arc.clone();
+    let ep_ep = event_publisher_arc.clone();
+    let ac_dc = data_connector_arc.clone();
+    let ac_ac = access_checker_arc.clone();
 
     let make_svc = make_service_fn(move |_| {
         let dc = data_connector_arc.clone();
         let ac = access_checker_arc.clone();
         let ce = command_executor_arc.clone();
-        let ep = event_publisher_arc.clone();
         let rt = router_arc.clone();
         let hr = handler_arc.clone();
 
@@ -215,7 +311,6 @@ async fn main() {
                     dc.clone(),
                     ac.clone(),
                     ce.clone(),
-                    ep.clone(),
                     rt.clone(),
                     hr.clone(),
                 )
@@ -232,6 +327,7 @@ async fn main() {
     let event_publisher_cancel_flag = cancel_flag.clone();
     let mq_sender_cancel_flag = cancel_flag.clone();
     let mq_receiver_cancel_flag = cancel_flag.clone();
+    let access_checker_cancel_flag = cancel_flag.clone();
 
     let graceful = server
         .with_graceful_shutdown(async { shutdown_signal(cancel_flag, control_senders).await });
@@ -242,6 +338,12 @@ async fn main() {
             if let Err(e) = workers::command_executor_worker(
                 command_executer_cancel_flag,
                 command_executor_control_receiver,
+                ce_dc,
+                ce_ac,
+                ce_ce,
+                ce_rt,
+                ce_hr,
+                async_reply_wait_in_hours,
             )
             .await
             {
@@ -254,6 +356,7 @@ async fn main() {
             if let Err(e) = workers::event_publisher_worker(
                 event_publisher_cancel_flag,
                 event_publisher_control_receiver,
+                ep_ep,
             )
             .await
             {
@@ -272,20 +375,45 @@ async fn main() {
             "ok"
         }),
         tokio::spawn(async move {
-            if let Err(e) =
-                workers::mq_receiver_worker(mq_receiver_cancel_flag, mq_receiver_control_receiver)
-                    .await
+            if let Err(e) = workers::mq_receiver_worker(
+                mq_receiver_cancel_flag,
+                mq_receiver_control_receiver,
+                mq_dc,
+                mq_ac,
+                mq_ce,
+                mq_rt,
+                mq_hr,
+                mq_provider,
+                mq_addresses,
+            )
+            .await
             {
                 error!("mq receiver: {}", e);
                 return "error";
             }
             "ok"
         }),
+        tokio::spawn(async move {
+            if let Err(e) = workers::access_checker_worker(
+                access_checker_cancel_flag,
+                access_checker_control_receiver,
+                ac_dc,
+                ac_ac,
+                access_reload_interval_in_seconds,
+            )
+            .await
+            {
+                error!("access checker: {}", e);
+                return "error";
+            }
+            "ok"
+        }),
     );
     debug!("stop command executor with result: {}", (res.1.unwrap()));
     debug!("stop event publisher with result: {}", (res.2.unwrap()));
     debug!("stop mq sender with result: {}", (res.3.unwrap()));
     debug!("stop mq receiver with result: {}", (res.4.unwrap()));
+    debug!("stop access checker with result: {}", (res.5.unwrap()));
     if let Err(e) = res.0 {
         error!("hyper server: {}", e);
         debug!("stop hyper server with result: error");
@@ -296,6 +424,7 @@ async fn main() {
             .await
             .expect("error while router shutdown");
     }
+    #[cfg(feature = "amqp")]
     connection.close(None).unwrap();
     info!("shutdown");
 }
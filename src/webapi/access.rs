@@ -1,21 +1,104 @@
-use super::{connectors, errors, settings};
+use super::{connectors, errors, settings, workers};
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use base64;
-use std::collections::HashMap;
+use password_hash::{rand_core::OsRng, SaltString};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{OnceLock, RwLock};
+use tokio::sync::mpsc;
+use tokio::task;
 
+const BASIC_PREFIX: &str = "Basic ";
+const MAX_VERIFIED_HEADERS: usize = 10000; //cache is cleared when full
+
+// Server users are reloaded from database on interval, on signal after usr change
+// and by reload command, so credentials are rotated without restart.
 pub struct AccessChecker {
-    sa: HashMap<String, String>,
-    cba: HashMap<String, String>,
+    sa: RwLock<ServerAccess>,
+    cba: HashMap<String, String>, // service name/token
     cba_common: Option<String>,
+    _cs: Option<mpsc::Sender<workers::SignalCode>>,
+}
+
+#[derive(Default)]
+struct ServerAccess {
+    password: HashMap<String, String>, // usr name/password hash
+    admin: HashSet<String>,            // names of usrs allowed to call admin paths
+    verified: HashSet<Vec<u8>>, // digest of verified authorization headers, hash verify is slow
+}
+
+// argon2 hash in PHC string format with random salt
+pub fn hash_password(password: &str) -> connectors::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(h) => Argon2::default()
+            .verify_password(password.as_bytes(), &h)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+pub fn is_password_hash(value: &str) -> bool {
+    PasswordHash::new(value).is_ok()
+}
+
+// hash and verify take tens of milliseconds by design, so they run on blocking pool
+pub async fn hash_password_async(password: String) -> connectors::Result<String> {
+    match task::spawn_blocking(move || hash_password(&password).map_err(|e| e.to_string())).await {
+        Ok(Ok(hash)) => Ok(hash),
+        Ok(Err(e)) => {
+            error!("hash password: {}", e);
+            Err(errors::ErrorKind::Internal.into())
+        }
+        Err(e) => {
+            error!("hash password task: {}", e);
+            Err(errors::ErrorKind::Internal.into())
+        }
+    }
+}
+
+// unknown usr is verified against dummy hash, so reply takes as long as for known one
+pub async fn verify_password_async(password: String, hash: Option<String>) -> bool {
+    let is_known = hash.is_some();
+    let hash = hash.unwrap_or_else(|| get_dummy_hash().to_string());
+    let is_valid = task::spawn_blocking(move || verify_password(&password, &hash))
+        .await
+        .unwrap_or(false);
+    is_known && is_valid
+}
+
+fn get_dummy_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_HASH.get_or_init(|| hash_password("dummy").unwrap_or_default())
+}
+
+fn get_header_digest(header: &str) -> Vec<u8> {
+    Sha256::digest(header.as_bytes()).to_vec()
 }
 
 impl AccessChecker {
     pub fn get_basic_authorization_token(user: &String, password: &String) -> String {
         format!(
-            "Basic {}",
+            "{}{}",
+            BASIC_PREFIX,
             base64::encode(&format!("{}:{}", user, password))
         )
     }
 
+    // usr name and password of basic authorization header
+    fn parse_basic_authorization_token(header: &str) -> Option<(String, String)> {
+        let credentials = base64::decode(header.strip_prefix(BASIC_PREFIX)?.trim()).ok()?;
+        let credentials = String::from_utf8(credentials).ok()?;
+        let (usr_name, password) = credentials.split_once(':')?;
+        Some((usr_name.to_string(), password.to_string()))
+    }
+
     pub fn get_client_basic_authorization_token(
         &self,
         service_name: &String,
@@ -25,52 +108,91 @@ impl AccessChecker {
         } else if self.cba_common.is_some() {
             Ok(self.cba_common.as_ref().unwrap().clone())
         } else {
-            return Err(errors::UnknownServiceNameError.into());
+            return Err(errors::ErrorKind::UnknownServiceName.into());
         }
     }
 
     pub async fn _from_app_settings(
         access: &settings::Access,
     ) -> connectors::Result<AccessChecker> {
-        let mut sa: HashMap<String, String> = HashMap::new();
+        let mut password: HashMap<String, String> = HashMap::new();
         for item in &access.authentication.server {
-            sa.insert(
-                AccessChecker::get_basic_authorization_token(&item.0, &item.1),
-                item.0.to_string(),
-            );
+            password.insert(item.0.to_string(), hash_password(item.1)?);
         }
-        debug!("{} server users", sa.len());
-        let mut cba: HashMap<String, String> = HashMap::new();
-        let mut cba_common = None;
-        for item in &access.authentication.client {
-            let token =
-                AccessChecker::get_basic_authorization_token(&item.usr_name, &item.usr_password);
-            if item.service_name == "*" {
-                cba_common = Some(token.clone());
-            }
-            cba.insert(token, item.service_name.to_string());
-        }
-        debug!("{} client users", cba.len());
+        debug!("{} server users", password.len());
+        let (cba, cba_common) = AccessChecker::get_client_access(&access.authentication);
         Ok(AccessChecker {
-            sa: sa,
+            sa: RwLock::new(ServerAccess {
+                password: password,
+                admin: HashSet::new(),
+                verified: HashSet::new(),
+            }),
             cba: cba,
             cba_common: cba_common,
+            _cs: None,
         })
     }
 
     pub async fn from_data_connector(
         dc: &connectors::DataConnector,
         authentication: &settings::Authentication,
+        cs: mpsc::Sender<workers::SignalCode>,
     ) -> connectors::Result<AccessChecker> {
+        let (password, admin) = AccessChecker::get_server_access(dc).await?;
+        debug!("{} server users", password.len());
+        let (cba, cba_common) = AccessChecker::get_client_access(authentication);
+        Ok(AccessChecker {
+            sa: RwLock::new(ServerAccess {
+                password: password,
+                admin: admin,
+                verified: HashSet::new(),
+            }),
+            cba: cba,
+            cba_common: cba_common,
+            _cs: Some(cs),
+        })
+    }
+
+    // password hash by name of enabled usr and names of enabled admin usrs
+    async fn get_server_access(
+        dc: &connectors::DataConnector,
+    ) -> connectors::Result<(HashMap<String, String>, HashSet<String>)> {
         let items = dc.usr.get(None).await?;
-        let mut server_authorization: HashMap<String, String> = HashMap::new();
+        let mut password: HashMap<String, String> = HashMap::new();
+        let mut admin: HashSet<String> = HashSet::new();
         for item in items {
-            server_authorization.insert(
-                AccessChecker::get_basic_authorization_token(&item.usr_name, &item.usr_password),
-                item.usr_name,
-            );
+            let usr_password = if is_password_hash(&item.usr_password) {
+                item.usr_password
+            } else {
+                // plain password of usr added before hashing, replaced by hash once
+                let hash = hash_password_async(item.usr_password.clone()).await?;
+                let result = dc.usr.change_password(&item.usr_name, &hash).await?;
+                if result != errors::ErrorCode::ReplyOk {
+                    error!(
+                        "hash password of usr {}: {}",
+                        item.usr_name,
+                        result.to_string()
+                    );
+                    return Err(errors::Error::new(errors::ErrorKind::DataIntegrity)
+                        .with_field("usr_password")
+                        .into());
+                }
+                info!("password of usr {} is replaced by hash", item.usr_name);
+                hash
+            };
+            if !item.is_disabled {
+                if item.is_admin {
+                    admin.insert(item.usr_name.clone());
+                }
+                password.insert(item.usr_name, usr_password);
+            }
         }
-        debug!("{} server users", server_authorization.len());
+        Ok((password, admin))
+    }
+
+    fn get_client_access(
+        authentication: &settings::Authentication,
+    ) -> (HashMap<String, String>, Option<String>) {
         let mut cba: HashMap<String, String> = HashMap::new();
         let mut cba_common = None;
         for item in &authentication.client {
@@ -79,17 +201,196 @@ impl AccessChecker {
             if item.service_name == "*" {
                 cba_common = Some(token.clone());
             }
-            cba.insert(token, item.service_name.to_string());
+            cba.insert(item.service_name.to_string(), token);
         }
         debug!("{} client users", cba.len());
-        Ok(AccessChecker {
-            sa: server_authorization,
-            cba: cba,
-            cba_common: cba_common,
-        })
+        (cba, cba_common)
+    }
+
+    // replace server users by current ones of database, verified headers are dropped
+    pub async fn reload(&self, dc: &connectors::DataConnector) -> connectors::Result<()> {
+        let (password, admin) = AccessChecker::get_server_access(dc).await?;
+        debug!("reload {} server users", password.len());
+        let mut sa = self.sa.write().unwrap();
+        sa.password = password;
+        sa.admin = admin;
+        sa.verified.clear();
+        Ok(())
+    }
+
+    pub async fn send_signal(&self, signal_code: workers::SignalCode) -> connectors::Result<()> {
+        let s = match &self._cs {
+            Some(s) => s.clone(),
+            None => return Ok({}),
+        };
+        match s.send(signal_code).await {
+            Ok(_) => Ok({}),
+            Err(e) => {
+                error!("send_signal_access_checker: {}", e);
+                return Err(errors::ErrorKind::SignalSend.into());
+            }
+        }
+    }
+
+    pub fn is_admin(&self, usr_name: &str) -> bool {
+        self.sa.read().unwrap().admin.contains(usr_name)
+    }
+
+    pub async fn is_authorized_by_header(&self, header: &str) -> bool {
+        self.get_authorized_usr(header).await.is_some()
     }
 
-    pub fn is_authorized_by_header(&self, header: &str) -> bool {
-        *&self.sa.contains_key(header)
+    // name of enabled usr of basic authorization header, none if not authorized
+    pub async fn get_authorized_usr(&self, header: &str) -> Option<String> {
+        let (usr_name, password) = AccessChecker::parse_basic_authorization_token(header)?;
+        let digest = get_header_digest(header);
+        let hash = {
+            let sa = self.sa.read().unwrap();
+            if sa.verified.contains(&digest) {
+                return Some(usr_name);
+            }
+            sa.password.get(&usr_name).cloned()
+        };
+        if !verify_password_async(password, hash.clone()).await {
+            return None;
+        }
+        // usr may be changed by reload while hash is verified
+        let mut sa = self.sa.write().unwrap();
+        if sa.password.get(&usr_name) == hash.as_ref() {
+            if sa.verified.len() >= MAX_VERIFIED_HEADERS {
+                sa.verified.clear();
+            }
+            sa.verified.insert(digest);
+            Some(usr_name)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests;
+    use super::*;
+
+    async fn get_access_checker(
+        dc: &connectors::DataConnector,
+    ) -> (AccessChecker, mpsc::Receiver<workers::SignalCode>) {
+        let (sender, receiver) = mpsc::channel::<workers::SignalCode>(5);
+        let ac = AccessChecker::from_data_connector(
+            dc,
+            &settings::Authentication {
+                server: HashMap::new(),
+                client: vec![],
+            },
+            sender,
+        )
+        .await
+        .unwrap();
+        (ac, receiver)
+    }
+
+    fn get_header(usr_name: &str, password: &str) -> String {
+        AccessChecker::get_basic_authorization_token(&usr_name.to_string(), &password.to_string())
+    }
+
+    #[test]
+    fn hash_and_verify() {
+        let hash = hash_password("secret").unwrap();
+        assert!(is_password_hash(&hash));
+        assert!(!is_password_hash("secret"));
+        assert_ne!(hash, hash_password("secret").unwrap());
+        assert!(verify_password("secret", &hash));
+        assert!(!verify_password("Secret", &hash));
+        assert!(!verify_password("secret", "secret"));
+    }
+
+    #[tokio::test]
+    async fn verify_unknown_usr_against_dummy_hash() {
+        let hash = hash_password_async("dummy".to_string()).await.unwrap();
+        assert!(verify_password_async("dummy".to_string(), Some(hash)).await);
+        // dummy password never matches unknown usr
+        assert!(!verify_password_async("dummy".to_string(), None).await);
+    }
+
+    #[tokio::test]
+    async fn plain_password_is_replaced_by_hash() {
+        let dc = tests::get_memory_data_connector().await;
+        dc.usr.change_password("user1", "pass1").await.unwrap();
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        let item = dc.usr.get_by_name("user1").await.unwrap().unwrap();
+        assert!(is_password_hash(&item.usr_password));
+        assert!(verify_password("pass1", &item.usr_password));
+        assert!(
+            ac.is_authorized_by_header(&get_header("user1", "pass1"))
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn authorize_by_header() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        assert_eq!(
+            ac.get_authorized_usr(&get_header("user1", "pass1")).await,
+            Some("user1".to_string())
+        );
+        assert!(
+            !ac.is_authorized_by_header(&get_header("user1", "pass2"))
+                .await
+        );
+        assert!(
+            !ac.is_authorized_by_header(&get_header("nobody", "pass1"))
+                .await
+        );
+        assert!(!ac.is_authorized_by_header("Basic !!!").await);
+        assert!(!ac.is_authorized_by_header("Bearer token").await);
+        // verified header is cached by digest only
+        let header = get_header("test", "1234567890");
+        assert!(ac.is_authorized_by_header(&header).await);
+        let sa = ac.sa.read().unwrap();
+        assert!(sa.verified.contains(&get_header_digest(&header)));
+        assert!(!sa
+            .verified
+            .iter()
+            .any(|v| v.as_slice() == header.as_bytes()));
+    }
+
+    #[tokio::test]
+    async fn admin_usr_is_loaded() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        assert!(ac.is_admin("test"));
+        assert!(!ac.is_admin("user1"));
+        assert!(!ac.is_admin("nobody"));
+    }
+
+    #[tokio::test]
+    async fn disabled_usr_is_dropped_on_reload() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        let header = get_header("user1", "pass1");
+        assert!(ac.is_authorized_by_header(&header).await);
+        dc.usr.disable(vec!["user1".to_string()]).await.unwrap();
+        assert!(ac.is_authorized_by_header(&header).await);
+        ac.reload(&dc).await.unwrap();
+        assert!(!ac.is_authorized_by_header(&header).await);
+    }
+
+    #[tokio::test]
+    async fn verified_cache_is_bounded() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        {
+            let mut sa = ac.sa.write().unwrap();
+            for i in 0..MAX_VERIFIED_HEADERS {
+                sa.verified.insert(get_header_digest(&i.to_string()));
+            }
+        }
+        assert!(
+            ac.is_authorized_by_header(&get_header("user1", "pass1"))
+                .await
+        );
+        assert_eq!(ac.sa.read().unwrap().verified.len(), 1);
     }
 }
@@ -1,8 +1,15 @@
-use super::super::{connectors, entities::car, errors, providers};
+use super::super::{
+    commands, connectors,
+    entities::car::{self, CAR_FILTER},
+    errors, filters, providers, traits,
+};
+use async_trait::async_trait;
 #[cfg(feature = "postgres")]
 use sqlx::postgres::PgPool;
 #[cfg(feature = "mysql")]
-use sqlx::MySqlPool;
+use sqlx::{MySqlPool, Row};
+#[cfg(feature = "sqlite")]
+use sqlx::{Row, SqlitePool};
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -21,28 +28,76 @@ impl CarCollection {
             exp_helper: &helper,
         }
     }
+}
 
-    pub async fn get(&self, ids: Option<Vec<i32>>) -> connectors::Result<Vec<car::Car>> {
+#[async_trait]
+impl traits::CarStorage for CarCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<i32>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<car::Car>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
-        if ids.is_none() {
-            Ok(
-                sqlx::query_as!(car::Car, r#"SELECT id as "id?",car_name FROM webapi.car"#)
-                    .fetch_all(pool)
-                    .await?,
-            )
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let first_param = if ids.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, CAR_FILTER, first_param)?;
+        let paging = filters::Paging::parse(page, CAR_FILTER, &["id"])?;
+        if ids.is_none() && filter.is_none() && paging.is_none() {
+            #[cfg(feature = "postgres")]
+            let items = sqlx::query_as!(car::Car, r#"SELECT id as "id?",car_name FROM webapi.car"#)
+                .fetch_all(pool)
+                .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let items: Vec<car::Car> = sqlx::query_as(r#"SELECT id,car_name FROM webapi.car"#)
+                .fetch_all(pool)
+                .await?;
+            let page_info =
+                filters::Paging::get_page_info(&paging, items.len(), items.len() as i64);
+            Ok((items, page_info))
         } else {
-            let query = self
+            let ids_count = ids.as_ref().map(|i| i.len());
+            let query = self.exp_helper.get_select_filter_exp(
+                "webapi.car",
+                "id",
+                ids_count,
+                &filter,
+                &paging,
+            );
+            let mut q = sqlx::query_as(&query);
+            if let Some(ids) = &ids {
+                q = self.exp_helper.bind_int_ids_as(q, ids);
+            }
+            let items: Vec<car::Car> = self
+                .exp_helper
+                .bind_filter_as(q, &filter)
+                .fetch_all(pool)
+                .await?;
+            let count_query =
+                self.exp_helper
+                    .get_count_filter_exp("webapi.car", "id", ids_count, &filter);
+            let mut q = sqlx::query(&count_query);
+            if let Some(ids) = &ids {
+                q = self.exp_helper.bind_int_ids(q, ids);
+            }
+            let page_info = self
                 .exp_helper
-                .get_select_int_exp("webapi.car", "id", &ids.unwrap());
-            let items: Vec<car::Car> = sqlx::query_as(&query).fetch_all(pool).await?;
-            Ok(items)
+                .get_page_info(
+                    pool,
+                    self.exp_helper.bind_filter(q, &filter),
+                    &paging,
+                    items.len(),
+                )
+                .await?;
+            Ok((items, page_info))
         }
     }
 
-    pub async fn add(
+    async fn add(
         &self,
         items: Vec<car::Car>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
@@ -51,6 +106,8 @@ impl CarCollection {
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut tx = pool.begin().await?;
         for item in items {
             #[cfg(feature = "postgres")]
@@ -58,7 +115,7 @@ impl CarCollection {
                 r#"INSERT INTO webapi.car ( car_name ) VALUES ( $1 ) RETURNING id"#,
                 item.car_name
             )
-            .fetch_one(&mut tx)
+            .fetch_one(&mut *tx)
             .await
             {
                 Ok(rec) => ids.push(rec.id),
@@ -68,10 +125,10 @@ impl CarCollection {
                     return Ok((errors::ErrorCode::DatabaseError, None));
                 }
             };
-            #[cfg(feature = "mysql")]
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
             match sqlx::query(r#"INSERT INTO webapi.car ( car_name ) VALUES ( ? )"#)
                 .bind(item.car_name)
-                .execute(&mut tx)
+                .execute(&mut *tx)
                 .await
             {
                 Ok(_) => {}
@@ -83,10 +140,22 @@ impl CarCollection {
             };
             #[cfg(feature = "mysql")]
             match sqlx::query(r#"SELECT LAST_INSERT_ID() AS id;"#)
-                .fetch_one(&mut tx)
+                .fetch_one(&mut *tx)
                 .await
             {
-                Ok(rec) => ids.push(rec.get(0)),
+                Ok(rec) => ids.push(rec.get::<u64, _>(0) as i32),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_cars db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(feature = "sqlite")]
+            match sqlx::query(r#"SELECT last_insert_rowid() AS id;"#)
+                .fetch_one(&mut *tx)
+                .await
+            {
+                Ok(rec) => ids.push(rec.get::<i64, _>(0) as i32),
                 Err(e) => {
                     tx.rollback().await.unwrap();
                     error!("add_cars db insert: {}", e);
@@ -104,11 +173,13 @@ impl CarCollection {
         Ok((errors::ErrorCode::ReplyOk, Some(ids)))
     }
 
-    pub async fn change(&self, items: Vec<car::Car>) -> connectors::Result<errors::ErrorCode> {
+    async fn change(&self, items: Vec<car::Car>) -> connectors::Result<errors::ErrorCode> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut tx = pool.begin().await?;
         let mut count: u64 = 0;
         for item in &items {
@@ -118,7 +189,7 @@ impl CarCollection {
                 item.car_name,
                 item.id.unwrap_or(0)
             )
-            .execute(&mut tx)
+            .execute(&mut *tx)
             .await
             {
                 Ok(ret) => count += ret.rows_affected(),
@@ -128,16 +199,14 @@ impl CarCollection {
                     return Ok(errors::ErrorCode::DatabaseError);
                 }
             };
-            #[cfg(feature = "mysql")]
-            match sqlx::query!(
-                r#"UPDATE car SET car_name = ? WHERE id = ?"#,
-                item.car_name,
-                item.id.unwrap_or(0)
-            )
-            .execute(&mut tx)
-            .await
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            match sqlx::query(r#"UPDATE webapi.car SET car_name = ? WHERE id = ?"#)
+                .bind(&item.car_name)
+                .bind(item.id.unwrap_or(0))
+                .execute(&mut *tx)
+                .await
             {
-                Ok(ret) => count += ret,
+                Ok(ret) => count += ret.rows_affected(),
                 Err(e) => {
                     error!("update_cars db update: {}", e);
                     tx.rollback().await?;
@@ -160,14 +229,21 @@ impl CarCollection {
         }
     }
 
-    pub async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut tx = pool.begin().await?;
-        match sqlx::query(&self.exp_helper.get_delete_int_exp("webapi.car", "id", &ids))
-            .execute(&mut tx)
+        match self
+            .exp_helper
+            .bind_int_ids(
+                sqlx::query(&self.exp_helper.get_delete_int_exp("webapi.car", "id", &ids)),
+                &ids,
+            )
+            .execute(&mut *tx)
             .await
         {
             Ok(ret) => {
@@ -1,11 +1,17 @@
-use super::super::{connectors, entities::executor, errors, providers};
-use chrono::{DateTime, Utc};
+use super::super::{
+    commands, connectors,
+    entities::executor::{self, RECEIVED_ASYNC_COMMAND_ORDER, SENDED_ASYNC_COMMAND_ORDER},
+    errors, filters, providers, traits,
+};
+use async_trait::async_trait;
 use futures::TryStreamExt;
 #[cfg(feature = "postgres")]
 use sqlx::postgres::PgPool;
 #[cfg(feature = "mysql")]
 use sqlx::MySqlPool;
 use sqlx::Row;
+#[cfg(feature = "sqlite")]
+use sqlx::SqlitePool;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -24,23 +30,54 @@ impl SendedAsyncCommandCollection {
             exp_helper: &helper,
         }
     }
+}
 
-    pub async fn get(
+#[async_trait]
+impl traits::SendedAsyncCommandStorage for SendedAsyncCommandCollection {
+    async fn get(
         &self,
         ids: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<executor::SendedAsyncCommand>> {
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::SendedAsyncCommand>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut items = Vec::<executor::SendedAsyncCommand>::new();
-        if ids.is_none() {
+        let paging = filters::Paging::parse(page, SENDED_ASYNC_COMMAND_ORDER, &["id"])?;
+        let ids_count = ids.as_ref().map(|i| i.len());
+        if ids.is_none() && paging.is_none() {
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
-                r#"SELECT id, object_type, "service_name", "state", change_state_event, added_at, state_changed_at 
+                r#"SELECT id, object_type, "service_name", "state", change_state_event, added_at, state_changed_at, reply_body 
                     FROM webapi.v_sended_async_command"#
             )
             .fetch_all(pool)
             .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT id, object_type, `service_name`, `state`, change_state_event, added_at, state_changed_at, reply_body 
+                    FROM webapi.v_sended_async_command"#,
+            )
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                items.push(executor::SendedAsyncCommand {
+                    id: rec.get(0),
+                    object_type: rec.get(1),
+                    service_name: rec.get(2),
+                    state: rec.get(3),
+                    change_state_event: rec.get(4),
+                    added_at: rec.get(5),
+                    state_changed_at: rec.get(6),
+                    reply_body: rec.get(7),
+                    history: None,
+                })
+            }
+            #[cfg(feature = "postgres")]
             for rec in recs {
                 items.push(executor::SendedAsyncCommand {
                     id: rec.id.unwrap(),
@@ -50,16 +87,23 @@ impl SendedAsyncCommandCollection {
                     change_state_event: rec.change_state_event.unwrap(),
                     added_at: rec.added_at.unwrap(),
                     state_changed_at: rec.state_changed_at.unwrap(),
+                    reply_body: rec.reply_body,
                     history: None,
                 })
             }
         } else {
-            let query = self.exp_helper.get_select_str_exp(
+            let query = self.exp_helper.get_select_filter_exp(
                 "webapi.v_sended_async_command",
                 "id",
-                &ids.unwrap(),
+                ids_count,
+                &None,
+                &paging,
             );
-            let mut cursor = sqlx::query(&query).fetch(pool);
+            let mut q = sqlx::query(&query);
+            if let Some(ids) = &ids {
+                q = self.exp_helper.bind_str_ids(q, ids);
+            }
+            let mut cursor = q.fetch(pool);
             while let Some(rec) = cursor.try_next().await? {
                 items.push(executor::SendedAsyncCommand {
                     id: rec.get(0),
@@ -69,11 +113,14 @@ impl SendedAsyncCommandCollection {
                     change_state_event: rec.get(4),
                     added_at: rec.get(5),
                     state_changed_at: rec.get(6),
+                    reply_body: rec.get(7),
                     history: None,
                 })
             }
         }
         for mut item in &mut items {
+            let mut p = Vec::<executor::SendedAsyncCommandHistory>::new();
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
                 r#"SELECT command_id, "state", added_at
                     FROM webapi.v_sended_async_command_state_history
@@ -82,22 +129,53 @@ impl SendedAsyncCommandCollection {
             )
             .fetch_all(pool)
             .await?;
-            if recs.len() > 0 {
-                let mut p = Vec::<executor::SendedAsyncCommandHistory>::new();
-                for rec in recs {
-                    p.push(executor::SendedAsyncCommandHistory {
-                        command_id: None,
-                        state: rec.state.as_ref().unwrap().to_string(),
-                        added_at: *rec.added_at.as_ref().unwrap(),
-                    });
-                }
+            #[cfg(feature = "postgres")]
+            for rec in recs {
+                p.push(executor::SendedAsyncCommandHistory {
+                    command_id: None,
+                    state: rec.state.as_ref().unwrap().to_string(),
+                    added_at: *rec.added_at.as_ref().unwrap(),
+                });
+            }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT command_id, `state`, added_at
+                    FROM webapi.v_sended_async_command_state_history
+                        WHERE command_id = ?"#,
+            )
+            .bind(&item.id)
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                p.push(executor::SendedAsyncCommandHistory {
+                    command_id: None,
+                    state: rec.get(1),
+                    added_at: rec.get(2),
+                });
+            }
+            if p.len() > 0 {
                 item.history = Some(p);
             }
         }
-        Ok(items)
+        let count_query = self.exp_helper.get_count_filter_exp(
+            "webapi.v_sended_async_command",
+            "id",
+            ids_count,
+            &None,
+        );
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &ids {
+            q = self.exp_helper.bind_str_ids(q, ids);
+        }
+        let page_info = self
+            .exp_helper
+            .get_page_info(pool, q, &paging, items.len())
+            .await?;
+        Ok((items, page_info))
     }
 
-    pub async fn add(
+    async fn add(
         &self,
         items: Vec<executor::SendedAsyncCommand>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
@@ -106,6 +184,8 @@ impl SendedAsyncCommandCollection {
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut tx = pool.begin().await?;
         for command in items {
             #[cfg(feature = "postgres")]
@@ -119,7 +199,7 @@ impl SendedAsyncCommandCollection {
                 command.state,
                 command.change_state_event
             )
-            .execute(&mut tx)
+            .execute(&mut *tx)
             .await
             {
                 Ok(_) => ids.push(command.id),
@@ -129,10 +209,10 @@ impl SendedAsyncCommandCollection {
                     return Ok((errors::ErrorCode::DatabaseError, None));
                 }
             };
-            #[cfg(feature = "mysql")]
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
             match sqlx::query(
                 r#"INSERT INTO webapi.sended_async_command 
-                ( id, object_type, 'service_name', 'state', change_state_event ) 
+                ( id, object_type, `service_name`, `state`, change_state_event ) 
                     VALUES ( ?, ?, ?, ?, ? )"#,
             )
             .bind(&command.id)
@@ -140,10 +220,10 @@ impl SendedAsyncCommandCollection {
             .bind(&command.service_name)
             .bind(&command.state)
             .bind(&command.change_state_event)
-            .execute(&mut tx)
+            .execute(&mut *tx)
             .await
             {
-                Ok(_) => ids.push(service_name.clone()),
+                Ok(_) => ids.push(command.id.clone()),
                 Err(e) => {
                     tx.rollback().await.unwrap();
                     error!("add_sended_async_commands db command insert: {}", e);
@@ -161,7 +241,7 @@ impl SendedAsyncCommandCollection {
                         history.state,
                         history.added_at
                     )
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
                         Ok(_) => {}
@@ -171,19 +251,19 @@ impl SendedAsyncCommandCollection {
                             return Ok((errors::ErrorCode::DatabaseError, None));
                         }
                     };
-                    #[cfg(feature = "mysql")]
+                    #[cfg(any(feature = "mysql", feature = "sqlite"))]
                     match sqlx::query(
                         r#"INSERT INTO webapi.sended_async_command_state_history 
-                    ( command_id, 'state', added_at ) 
+                    ( command_id, `state`, added_at ) 
                         VALUES ( ?, ?, ? )"#,
                     )
                     .bind(&history.command_id)
                     .bind(&history.state)
                     .bind(&history.added_at)
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
-                        Ok(_) => ids.push(service_name.clone()),
+                        Ok(_) => {}
                         Err(e) => {
                             tx.rollback().await.unwrap();
                             error!("add_sended_async_commands db history insert: {}", e);
@@ -203,28 +283,313 @@ impl SendedAsyncCommandCollection {
         Ok((errors::ErrorCode::ReplyOk, Some(ids)))
     }
 
-    pub async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+    async fn complete(
+        &self,
+        id: String,
+        reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let state = executor::CommandSystemState::Completed.to_string();
         let mut tx = pool.begin().await?;
-        match sqlx::query(&self.exp_helper.get_delete_str_exp(
-            "webapi.sended_async_command_state_history",
-            "command_id",
-            &ids,
-        ))
-        .execute(&mut tx)
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"UPDATE webapi.sended_async_command 
+                SET "state" = $1, reply_body = $2, state_changed_at = CURRENT_TIMESTAMP 
+                    WHERE id = $3 AND "state" <> $1"#,
+            state,
+            reply_body,
+            id
+        )
+        .execute(&mut *tx)
         .await
         {
-            Ok(_) => {
-                match sqlx::query(&self.exp_helper.get_delete_str_exp(
-                    "webapi.sended_async_command",
-                    "id",
+            Ok(ret) => {
+                if ret.rows_affected() != 1 {
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::NotFoundError);
+                }
+            }
+            Err(e) => {
+                error!("complete_sended_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        match sqlx::query(
+            r#"UPDATE webapi.sended_async_command 
+            SET `state` = ?, reply_body = ?, state_changed_at = CURRENT_TIMESTAMP 
+                WHERE id = ? AND `state` <> ?"#,
+        )
+        .bind(&state)
+        .bind(&reply_body)
+        .bind(&id)
+        .bind(&state)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() != 1 {
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::NotFoundError);
+                }
+            }
+            Err(e) => {
+                error!("complete_sended_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"INSERT INTO webapi.sended_async_command_state_history 
+                ( command_id, "state", added_at ) 
+                    VALUES ( $1, $2, CURRENT_TIMESTAMP )"#,
+            id,
+            state
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("complete_sended_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        match sqlx::query(
+            r#"INSERT INTO webapi.sended_async_command_state_history 
+            ( command_id, `state`, added_at ) 
+                VALUES ( ?, ?, CURRENT_TIMESTAMP )"#,
+        )
+        .bind(&id)
+        .bind(&state)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("complete_sended_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => Ok(errors::ErrorCode::ReplyOk),
+            Err(e) => {
+                error!("complete_sended_async_commands db commit: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+
+    // mark commands not completed in time as expired
+    async fn expire(&self, wait_in_hours: u16) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let completed = executor::CommandSystemState::Completed.to_string();
+        let expired = executor::CommandSystemState::Expired.to_string();
+        let hours = wait_in_hours as i32;
+        let mut tx = pool.begin().await?;
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"INSERT INTO webapi.sended_async_command_state_history ( command_id, "state", added_at ) 
+                SELECT id, $2, CURRENT_TIMESTAMP FROM webapi.sended_async_command 
+                    WHERE "state" <> $1 AND "state" <> $2 
+                        AND added_at < CURRENT_TIMESTAMP - make_interval(hours => $3)"#,
+            completed,
+            expired,
+            hours
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("expire_sended_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "mysql")]
+        match sqlx::query(
+            r#"INSERT INTO webapi.sended_async_command_state_history ( command_id, `state`, added_at ) 
+            SELECT id, ?, CURRENT_TIMESTAMP FROM webapi.sended_async_command 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND added_at < DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? HOUR)"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("expire_sended_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "sqlite")]
+        match sqlx::query(
+            r#"INSERT INTO webapi.sended_async_command_state_history ( command_id, `state`, added_at ) 
+            SELECT id, ?, CURRENT_TIMESTAMP FROM webapi.sended_async_command 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND datetime(added_at) < datetime('now', '-' || ? || ' hours')"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("expire_sended_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"UPDATE webapi.sended_async_command 
+                SET "state" = $2, state_changed_at = CURRENT_TIMESTAMP 
+                    WHERE "state" <> $1 AND "state" <> $2 
+                        AND added_at < CURRENT_TIMESTAMP - make_interval(hours => $3)"#,
+            completed,
+            expired,
+            hours
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() > 0 {
+                    warn!(
+                        "expire_sended_async_commands: {} expired",
+                        ret.rows_affected()
+                    );
+                }
+            }
+            Err(e) => {
+                error!("expire_sended_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "mysql")]
+        match sqlx::query(
+            r#"UPDATE webapi.sended_async_command 
+            SET `state` = ?, state_changed_at = CURRENT_TIMESTAMP 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND added_at < DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? HOUR)"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() > 0 {
+                    warn!(
+                        "expire_sended_async_commands: {} expired",
+                        ret.rows_affected()
+                    );
+                }
+            }
+            Err(e) => {
+                error!("expire_sended_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "sqlite")]
+        match sqlx::query(
+            r#"UPDATE webapi.sended_async_command 
+            SET `state` = ?, state_changed_at = CURRENT_TIMESTAMP 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND datetime(added_at) < datetime('now', '-' || ? || ' hours')"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() > 0 {
+                    warn!(
+                        "expire_sended_async_commands: {} expired",
+                        ret.rows_affected()
+                    );
+                }
+            }
+            Err(e) => {
+                error!("expire_sended_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => Ok(errors::ErrorCode::ReplyOk),
+            Err(e) => {
+                error!("expire_sended_async_commands db commit: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+
+    async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        match self
+            .exp_helper
+            .bind_str_ids(
+                sqlx::query(&self.exp_helper.get_delete_str_exp(
+                    "webapi.sended_async_command_state_history",
+                    "command_id",
                     &ids,
-                ))
-                .execute(&mut tx)
-                .await
+                )),
+                &ids,
+            )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => {
+                match self
+                    .exp_helper
+                    .bind_str_ids(
+                        sqlx::query(&self.exp_helper.get_delete_str_exp(
+                            "webapi.sended_async_command",
+                            "id",
+                            &ids,
+                        )),
+                        &ids,
+                    )
+                    .execute(&mut *tx)
+                    .await
                 {
                     Ok(ret) => {
                         if ids.len() == usize::try_from(ret.rows_affected()).unwrap() {
@@ -272,101 +637,41 @@ impl ReceivedAsyncCommandCollection {
         }
     }
 
-    pub async fn get(
-        &self,
-        ids: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<executor::ReceivedAsyncCommand>> {
-        #[cfg(feature = "postgres")]
-        let pool: &PgPool = &self.data_provider.pool;
-        #[cfg(feature = "mysql")]
-        let pool: &MySqlPool = &self.data_provider.pool;
-        let mut items = Vec::<executor::ReceivedAsyncCommand>::new();
-        if ids.is_none() {
-            let recs = sqlx::query!(
-                r#"SELECT id, object_type, "service_name", request_body, "state", change_state_event,
-                    reply_body, proto, added_at, state_changed_at
-                        FROM webapi.v_received_async_command"#
-            )
-            .fetch_all(pool)
-            .await?;
-            for rec in recs {
-                items.push(executor::ReceivedAsyncCommand {
-                    id: rec.id.unwrap(),
-                    object_type: rec.object_type.unwrap(),
-                    service_name: rec.service_name.unwrap(),
-                    request_body: rec.request_body.unwrap(),
-                    state: rec.state.unwrap(),
-                    change_state_event: rec.change_state_event.unwrap(),
-                    reply_body: rec.reply_body.unwrap(),
-                    proto: rec.proto.unwrap(),
-                    added_at: rec.added_at.unwrap(),
-                    state_changed_at: rec.state_changed_at.unwrap(),
-                    history: None,
-                })
-            }
-        } else {
-            let query = self.exp_helper.get_select_str_exp(
-                "webapi.v_received_async_command",
-                "id",
-                &ids.unwrap(),
-            );
-            let mut cursor = sqlx::query(&query).fetch(pool);
-            while let Some(rec) = cursor.try_next().await? {
-                items.push(executor::ReceivedAsyncCommand {
-                    id: rec.get(0),
-                    object_type: rec.get(1),
-                    service_name: rec.get(2),
-                    request_body: rec.get(3),
-                    state: rec.get(4),
-                    change_state_event: rec.get(5),
-                    reply_body: rec.get(6),
-                    proto: rec.get(7),
-                    added_at: rec.get(8),
-                    state_changed_at: rec.get(9),
-                    history: None,
-                })
-            }
-        }
-        for mut item in &mut items {
-            let recs = sqlx::query!(
-                r#"SELECT command_id, "state", added_at
-                    FROM webapi.v_received_async_command_state_history
-                        WHERE command_id = $1"#,
-                &item.id
-            )
-            .fetch_all(pool)
-            .await?;
-            if recs.len() > 0 {
-                let mut p = Vec::<executor::ReceivedAsyncCommandHistory>::new();
-                for rec in recs {
-                    p.push(executor::ReceivedAsyncCommandHistory {
-                        command_id: None,
-                        state: rec.state.as_ref().unwrap().to_string(),
-                        added_at: *rec.added_at.as_ref().unwrap(),
-                    });
-                }
-                item.history = Some(p);
-            }
-        }
-        Ok(items)
-    }
-
-    pub async fn add(
+    // insert commands with history in one transaction, with max_pending none queue is not limited
+    async fn insert(
         &self,
         items: Vec<executor::ReceivedAsyncCommand>,
+        max_pending: Option<i64>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
         let mut ids = Vec::<String>::new();
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let completed = executor::CommandSystemState::Completed.to_string();
+        let expired = executor::CommandSystemState::Expired.to_string();
+        let limit = max_pending.unwrap_or(i64::MAX);
         let mut tx = pool.begin().await?;
+        // concurrent intakes wait here, so pending count is not changed until commit
+        #[cfg(feature = "postgres")]
+        if max_pending.is_some() {
+            sqlx::query("LOCK TABLE webapi.received_async_command IN SHARE ROW EXCLUSIVE MODE")
+                .execute(&mut *tx)
+                .await?;
+        }
         for command in items {
+            // row is inserted only if pending commands, with rows of this transaction, are under limit
             #[cfg(feature = "postgres")]
             match sqlx::query!(
                 r#"INSERT INTO webapi.received_async_command 
-                    ( id, object_type, "service_name", request_body, "state", change_state_event, reply_body, proto ) 
-                        VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 )"#,
+                    ( id, object_type, "service_name", request_body, "state", change_state_event, reply_body, proto, reply_to,
+                        authorized_usr_name, locale ) 
+                        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11 
+                        FROM ( SELECT COUNT(*) AS pending FROM webapi.received_async_command 
+                            WHERE "state" <> $12 AND "state" <> $13 ) p 
+                        WHERE p.pending < $14"#,
                 command.id,
                 command.object_type,
                 command.service_name,
@@ -374,11 +679,21 @@ impl ReceivedAsyncCommandCollection {
                 command.state,
                 command.change_state_event,
                 command.reply_body,
-                command.proto
+                command.proto,
+                command.reply_to,
+                command.authorized_usr_name,
+                command.locale,
+                completed,
+                expired,
+                limit
             )
-            .execute(&mut tx)
+            .execute(&mut *tx)
             .await
             {
+                Ok(r) if r.rows_affected() == 0 => {
+                    tx.rollback().await?;
+                    return Ok((errors::ErrorCode::TooManyRequestsError, None));
+                }
                 Ok(_) => ids.push(command.id),
                 Err(e) => {
                     tx.rollback().await.unwrap();
@@ -386,11 +701,15 @@ impl ReceivedAsyncCommandCollection {
                     return Ok((errors::ErrorCode::DatabaseError, None));
                 }
             };
-            #[cfg(feature = "mysql")]
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
             match sqlx::query(
                 r#"INSERT INTO webapi.received_async_command 
-                ( id, object_type, "service_name", request_body, "state", change_state_event, reply_body, proto ) 
-                    VALUES ( ?, ?, ?, ?, ?, ?, ?, ? )"#,
+                ( id, object_type, `service_name`, request_body, `state`, change_state_event, reply_body, proto, reply_to,
+                    authorized_usr_name, locale ) 
+                    SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? 
+                    FROM ( SELECT COUNT(*) AS pending FROM webapi.received_async_command 
+                        WHERE `state` <> ? AND `state` <> ? ) p 
+                    WHERE p.pending < ?"#,
             )
             .bind(&command.id)
             .bind(&command.object_type)
@@ -400,10 +719,20 @@ impl ReceivedAsyncCommandCollection {
             .bind(&command.change_state_event)
             .bind(&command.reply_body)
             .bind(&command.proto)
-            .execute(&mut tx)
+            .bind(&command.reply_to)
+            .bind(&command.authorized_usr_name)
+            .bind(&command.locale)
+            .bind(&completed)
+            .bind(&expired)
+            .bind(limit)
+            .execute(&mut *tx)
             .await
             {
-                Ok(_) => ids.push(service_name.clone()),
+                Ok(r) if r.rows_affected() == 0 => {
+                    tx.rollback().await?;
+                    return Ok((errors::ErrorCode::TooManyRequestsError, None));
+                }
+                Ok(_) => ids.push(command.id.clone()),
                 Err(e) => {
                     tx.rollback().await.unwrap();
                     error!("add_received_async_commands db command insert: {}", e);
@@ -421,7 +750,7 @@ impl ReceivedAsyncCommandCollection {
                         history.state,
                         history.added_at
                     )
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
                         Ok(_) => {}
@@ -431,19 +760,19 @@ impl ReceivedAsyncCommandCollection {
                             return Ok((errors::ErrorCode::DatabaseError, None));
                         }
                     };
-                    #[cfg(feature = "mysql")]
+                    #[cfg(any(feature = "mysql", feature = "sqlite"))]
                     match sqlx::query(
                         r#"INSERT INTO webapi.received_async_command_state_history 
-                    ( command_id, 'state', added_at ) 
+                    ( command_id, `state`, added_at ) 
                         VALUES ( ?, ?, ? )"#,
                     )
                     .bind(&history.command_id)
                     .bind(&history.state)
                     .bind(&history.added_at)
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
-                        Ok(_) => ids.push(service_name.clone()),
+                        Ok(_) => {}
                         Err(e) => {
                             tx.rollback().await.unwrap();
                             error!("add_received_async_commands db history insert: {}", e);
@@ -462,16 +791,193 @@ impl ReceivedAsyncCommandCollection {
         }
         Ok((errors::ErrorCode::ReplyOk, Some(ids)))
     }
+}
 
-    pub async fn change_state(
+#[async_trait]
+impl traits::ReceivedAsyncCommandStorage for ReceivedAsyncCommandCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<String>>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::ReceivedAsyncCommand>, filters::PageInfo)> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut items = Vec::<executor::ReceivedAsyncCommand>::new();
+        let paging = filters::Paging::parse(page, RECEIVED_ASYNC_COMMAND_ORDER, &["id"])?;
+        let ids_count = ids.as_ref().map(|i| i.len());
+        if ids.is_none() && paging.is_none() {
+            #[cfg(feature = "postgres")]
+            let recs = sqlx::query!(
+                r#"SELECT id, object_type, "service_name", request_body, "state", change_state_event,
+                    reply_body, proto, added_at, state_changed_at, reply_to, authorized_usr_name, locale
+                        FROM webapi.v_received_async_command"#
+            )
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT id, object_type, `service_name`, request_body, `state`, change_state_event,
+                    reply_body, proto, added_at, state_changed_at, reply_to, authorized_usr_name, locale
+                        FROM webapi.v_received_async_command"#,
+            )
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                items.push(executor::ReceivedAsyncCommand {
+                    id: rec.get(0),
+                    object_type: rec.get(1),
+                    service_name: rec.get(2),
+                    request_body: rec.get(3),
+                    state: rec.get(4),
+                    change_state_event: rec.get(5),
+                    reply_body: rec.get(6),
+                    proto: rec.get(7),
+                    added_at: rec.get(8),
+                    state_changed_at: rec.get(9),
+                    reply_to: rec.get(10),
+                    authorized_usr_name: rec.get(11),
+                    locale: rec.get(12),
+                    history: None,
+                })
+            }
+            #[cfg(feature = "postgres")]
+            for rec in recs {
+                items.push(executor::ReceivedAsyncCommand {
+                    id: rec.id.unwrap(),
+                    object_type: rec.object_type.unwrap(),
+                    service_name: rec.service_name.unwrap(),
+                    request_body: rec.request_body.unwrap(),
+                    state: rec.state.unwrap(),
+                    change_state_event: rec.change_state_event.unwrap(),
+                    reply_body: rec.reply_body.unwrap(),
+                    proto: rec.proto.unwrap(),
+                    added_at: rec.added_at.unwrap(),
+                    state_changed_at: rec.state_changed_at.unwrap(),
+                    reply_to: rec.reply_to.unwrap(),
+                    authorized_usr_name: rec.authorized_usr_name,
+                    locale: rec.locale,
+                    history: None,
+                })
+            }
+        } else {
+            let query = self.exp_helper.get_select_filter_exp(
+                "webapi.v_received_async_command",
+                "id",
+                ids_count,
+                &None,
+                &paging,
+            );
+            let mut q = sqlx::query(&query);
+            if let Some(ids) = &ids {
+                q = self.exp_helper.bind_str_ids(q, ids);
+            }
+            let mut cursor = q.fetch(pool);
+            while let Some(rec) = cursor.try_next().await? {
+                items.push(executor::ReceivedAsyncCommand {
+                    id: rec.get(0),
+                    object_type: rec.get(1),
+                    service_name: rec.get(2),
+                    request_body: rec.get(3),
+                    state: rec.get(4),
+                    change_state_event: rec.get(5),
+                    reply_body: rec.get(6),
+                    proto: rec.get(7),
+                    added_at: rec.get(8),
+                    state_changed_at: rec.get(9),
+                    reply_to: rec.get(10),
+                    authorized_usr_name: rec.get(11),
+                    locale: rec.get(12),
+                    history: None,
+                })
+            }
+        }
+        for mut item in &mut items {
+            let mut p = Vec::<executor::ReceivedAsyncCommandHistory>::new();
+            #[cfg(feature = "postgres")]
+            let recs = sqlx::query!(
+                r#"SELECT command_id, "state", added_at
+                    FROM webapi.v_received_async_command_state_history
+                        WHERE command_id = $1"#,
+                &item.id
+            )
+            .fetch_all(pool)
+            .await?;
+            #[cfg(feature = "postgres")]
+            for rec in recs {
+                p.push(executor::ReceivedAsyncCommandHistory {
+                    command_id: None,
+                    state: rec.state.as_ref().unwrap().to_string(),
+                    added_at: *rec.added_at.as_ref().unwrap(),
+                });
+            }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT command_id, `state`, added_at
+                    FROM webapi.v_received_async_command_state_history
+                        WHERE command_id = ?"#,
+            )
+            .bind(&item.id)
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                p.push(executor::ReceivedAsyncCommandHistory {
+                    command_id: None,
+                    state: rec.get(1),
+                    added_at: rec.get(2),
+                });
+            }
+            if p.len() > 0 {
+                item.history = Some(p);
+            }
+        }
+        let count_query = self.exp_helper.get_count_filter_exp(
+            "webapi.v_received_async_command",
+            "id",
+            ids_count,
+            &None,
+        );
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &ids {
+            q = self.exp_helper.bind_str_ids(q, ids);
+        }
+        let page_info = self
+            .exp_helper
+            .get_page_info(pool, q, &paging, items.len())
+            .await?;
+        Ok((items, page_info))
+    }
+
+    async fn add(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        self.insert(items, None).await
+    }
+
+    async fn add_within_limit(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+        max_pending: i64,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        self.insert(items, Some(max_pending)).await
+    }
+
+    async fn change_state(
         &self,
         state: String,
         ids: Vec<String>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<executor::AsyncCommandState>>)> {
         if state == executor::CommandSystemState::Initial.to_string()
             || state == executor::CommandSystemState::Completed.to_string()
+            || state == executor::CommandSystemState::Expired.to_string()
         {
-            return Err(errors::UnknownAsyncCommandStateError.into());
+            return Err(errors::ErrorKind::UnknownAsyncCommandState.into());
         }
         // todo: set state for ids receive command, if already set, not update
         // state must be exists in ServiceCommand.state else raise UnknownAsyncCommandStateError
@@ -480,14 +986,18 @@ impl ReceivedAsyncCommandCollection {
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let tx = pool.begin().await?;
         let mut items = Vec::<executor::AsyncCommandState>::new();
-        let query = self.exp_helper.get_select_str_exp(
-            "webapi.received_async_command",
-            "id,state,state_changed_at",
-            &ids,
+        let query = format!(
+            "SELECT id, state, state_changed_at FROM webapi.received_async_command WHERE {}",
+            self.exp_helper.get_ids_exp("id", ids.len())
         );
-        let mut cursor = sqlx::query(&query).fetch(pool);
+        let mut cursor = self
+            .exp_helper
+            .bind_str_ids(sqlx::query(&query), &ids)
+            .fetch(pool);
         while let Some(rec) = cursor.try_next().await? {
             let cur_state: String = rec.get(1);
             if cur_state != state {
@@ -519,33 +1029,383 @@ impl ReceivedAsyncCommandCollection {
         }
     }
 
-    pub async fn complete(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
-        //todo: set CommandSystemState.Completed state for ids command, if already set, not update
-        Ok(errors::ErrorCode::ReplyOk)
+    async fn complete(
+        &self,
+        id: String,
+        reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let state = executor::CommandSystemState::Completed.to_string();
+        let mut tx = pool.begin().await?;
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"UPDATE webapi.received_async_command 
+                SET "state" = $1, reply_body = $2, state_changed_at = CURRENT_TIMESTAMP 
+                    WHERE id = $3 AND "state" <> $1"#,
+            state,
+            reply_body,
+            id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() != 1 {
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::NotFoundError);
+                }
+            }
+            Err(e) => {
+                error!("complete_received_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        match sqlx::query(
+            r#"UPDATE webapi.received_async_command 
+            SET `state` = ?, reply_body = ?, state_changed_at = CURRENT_TIMESTAMP 
+                WHERE id = ? AND `state` <> ?"#,
+        )
+        .bind(&state)
+        .bind(&reply_body)
+        .bind(&id)
+        .bind(&state)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() != 1 {
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::NotFoundError);
+                }
+            }
+            Err(e) => {
+                error!("complete_received_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"INSERT INTO webapi.received_async_command_state_history 
+                ( command_id, "state", added_at ) 
+                    VALUES ( $1, $2, CURRENT_TIMESTAMP )"#,
+            id,
+            state
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("complete_received_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        match sqlx::query(
+            r#"INSERT INTO webapi.received_async_command_state_history 
+            ( command_id, `state`, added_at ) 
+                VALUES ( ?, ?, CURRENT_TIMESTAMP )"#,
+        )
+        .bind(&id)
+        .bind(&state)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("complete_received_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => Ok(errors::ErrorCode::ReplyOk),
+            Err(e) => {
+                error!("complete_received_async_commands db commit: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
     }
 
-    pub async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+    async fn get_by_state(
+        &self,
+        state: String,
+    ) -> connectors::Result<Vec<executor::ReceivedAsyncCommand>> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut items = Vec::<executor::ReceivedAsyncCommand>::new();
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let recs = sqlx::query(
+            r#"SELECT id, object_type, `service_name`, request_body, `state`, change_state_event,
+                reply_body, proto, added_at, state_changed_at, reply_to, authorized_usr_name, locale
+                    FROM webapi.v_received_async_command WHERE `state` = ?"#,
+        )
+        .bind(&state)
+        .fetch_all(pool)
+        .await?;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        for rec in recs {
+            items.push(executor::ReceivedAsyncCommand {
+                id: rec.get(0),
+                object_type: rec.get(1),
+                service_name: rec.get(2),
+                request_body: rec.get(3),
+                state: rec.get(4),
+                change_state_event: rec.get(5),
+                reply_body: rec.get(6),
+                proto: rec.get(7),
+                added_at: rec.get(8),
+                state_changed_at: rec.get(9),
+                reply_to: rec.get(10),
+                authorized_usr_name: rec.get(11),
+                locale: rec.get(12),
+                history: None,
+            })
+        }
+        #[cfg(feature = "postgres")]
+        let recs = sqlx::query!(
+            r#"SELECT id, object_type, "service_name", request_body, "state", change_state_event,
+                reply_body, proto, added_at, state_changed_at, reply_to, authorized_usr_name, locale
+                    FROM webapi.v_received_async_command WHERE "state" = $1"#,
+            state
+        )
+        .fetch_all(pool)
+        .await?;
+        #[cfg(feature = "postgres")]
+        for rec in recs {
+            items.push(executor::ReceivedAsyncCommand {
+                id: rec.id.unwrap(),
+                object_type: rec.object_type.unwrap(),
+                service_name: rec.service_name.unwrap(),
+                request_body: rec.request_body.unwrap(),
+                state: rec.state.unwrap(),
+                change_state_event: rec.change_state_event.unwrap(),
+                reply_body: rec.reply_body.unwrap(),
+                proto: rec.proto.unwrap(),
+                added_at: rec.added_at.unwrap(),
+                state_changed_at: rec.state_changed_at.unwrap(),
+                reply_to: rec.reply_to.unwrap(),
+                authorized_usr_name: rec.authorized_usr_name,
+                locale: rec.locale,
+                history: None,
+            })
+        }
+        Ok(items)
+    }
+
+    // mark commands not completed in time as expired
+    async fn expire(&self, wait_in_hours: u16) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let completed = executor::CommandSystemState::Completed.to_string();
+        let expired = executor::CommandSystemState::Expired.to_string();
+        let hours = wait_in_hours as i32;
         let mut tx = pool.begin().await?;
-        match sqlx::query(&self.exp_helper.get_delete_str_exp(
-            "webapi.received_async_command_state_history",
-            "command_id",
-            &ids,
-        ))
-        .execute(&mut tx)
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"INSERT INTO webapi.received_async_command_state_history ( command_id, "state", added_at ) 
+                SELECT id, $2, CURRENT_TIMESTAMP FROM webapi.received_async_command 
+                    WHERE "state" <> $1 AND "state" <> $2 
+                        AND added_at < CURRENT_TIMESTAMP - make_interval(hours => $3)"#,
+            completed,
+            expired,
+            hours
+        )
+        .execute(&mut *tx)
         .await
         {
-            Ok(_) => {
-                match sqlx::query(&self.exp_helper.get_delete_str_exp(
-                    "webapi.received_async_command",
-                    "id",
+            Ok(_) => {}
+            Err(e) => {
+                error!("expire_received_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "mysql")]
+        match sqlx::query(
+            r#"INSERT INTO webapi.received_async_command_state_history ( command_id, `state`, added_at ) 
+            SELECT id, ?, CURRENT_TIMESTAMP FROM webapi.received_async_command 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND added_at < DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? HOUR)"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("expire_received_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "sqlite")]
+        match sqlx::query(
+            r#"INSERT INTO webapi.received_async_command_state_history ( command_id, `state`, added_at ) 
+            SELECT id, ?, CURRENT_TIMESTAMP FROM webapi.received_async_command 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND datetime(added_at) < datetime('now', '-' || ? || ' hours')"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("expire_received_async_commands db history insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "postgres")]
+        match sqlx::query!(
+            r#"UPDATE webapi.received_async_command 
+                SET "state" = $2, state_changed_at = CURRENT_TIMESTAMP 
+                    WHERE "state" <> $1 AND "state" <> $2 
+                        AND added_at < CURRENT_TIMESTAMP - make_interval(hours => $3)"#,
+            completed,
+            expired,
+            hours
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() > 0 {
+                    warn!(
+                        "expire_received_async_commands: {} expired",
+                        ret.rows_affected()
+                    );
+                }
+            }
+            Err(e) => {
+                error!("expire_received_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "mysql")]
+        match sqlx::query(
+            r#"UPDATE webapi.received_async_command 
+            SET `state` = ?, state_changed_at = CURRENT_TIMESTAMP 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND added_at < DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? HOUR)"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() > 0 {
+                    warn!(
+                        "expire_received_async_commands: {} expired",
+                        ret.rows_affected()
+                    );
+                }
+            }
+            Err(e) => {
+                error!("expire_received_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        #[cfg(feature = "sqlite")]
+        match sqlx::query(
+            r#"UPDATE webapi.received_async_command 
+            SET `state` = ?, state_changed_at = CURRENT_TIMESTAMP 
+                WHERE `state` <> ? AND `state` <> ? 
+                    AND datetime(added_at) < datetime('now', '-' || ? || ' hours')"#,
+        )
+        .bind(&expired)
+        .bind(&completed)
+        .bind(&expired)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() > 0 {
+                    warn!(
+                        "expire_received_async_commands: {} expired",
+                        ret.rows_affected()
+                    );
+                }
+            }
+            Err(e) => {
+                error!("expire_received_async_commands db command update: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => Ok(errors::ErrorCode::ReplyOk),
+            Err(e) => {
+                error!("expire_received_async_commands db commit: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+
+    async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        match self
+            .exp_helper
+            .bind_str_ids(
+                sqlx::query(&self.exp_helper.get_delete_str_exp(
+                    "webapi.received_async_command_state_history",
+                    "command_id",
                     &ids,
-                ))
-                .execute(&mut tx)
-                .await
+                )),
+                &ids,
+            )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => {
+                match self
+                    .exp_helper
+                    .bind_str_ids(
+                        sqlx::query(&self.exp_helper.get_delete_str_exp(
+                            "webapi.received_async_command",
+                            "id",
+                            &ids,
+                        )),
+                        &ids,
+                    )
+                    .execute(&mut *tx)
+                    .await
                 {
                     Ok(ret) => {
                         if ids.len() == usize::try_from(ret.rows_affected()).unwrap() {
@@ -1,4 +1,5 @@
 pub mod car;
 pub mod executor;
+pub mod publisher;
 pub mod route;
 pub mod usr;
@@ -0,0 +1,606 @@
+use super::super::{
+    commands, connectors,
+    entities::publisher::{self, DEAD_LETTER_EVENT_FILTER},
+    errors, filters, providers, traits,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "postgres")]
+use sqlx::postgres::{PgPool, Postgres};
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+use sqlx::Row;
+#[cfg(feature = "mysql")]
+use sqlx::{MySql, MySqlPool};
+#[cfg(feature = "sqlite")]
+use sqlx::{Sqlite, SqlitePool};
+use std::sync::Arc;
+
+// write events in transaction of entity change, drained later by event publisher
+#[cfg(feature = "postgres")]
+pub async fn add_outbox_events(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    items: &Vec<publisher::OutboxEvent>,
+) -> Result<(), sqlx::Error> {
+    for item in items {
+        sqlx::query!(
+            r#"INSERT INTO webapi.event_outbox ( correlation_id, object_type, body ) 
+                VALUES ( $1, $2, $3 )"#,
+            item.correlation_id,
+            item.object_type,
+            item.body
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok({})
+}
+
+// write events in transaction of entity change, drained later by event publisher
+#[cfg(feature = "mysql")]
+pub async fn add_outbox_events(
+    tx: &mut sqlx::Transaction<'_, MySql>,
+    items: &Vec<publisher::OutboxEvent>,
+) -> Result<(), sqlx::Error> {
+    for item in items {
+        sqlx::query(
+            r#"INSERT INTO webapi.event_outbox ( correlation_id, object_type, body ) 
+            VALUES ( ?, ?, ? )"#,
+        )
+        .bind(&item.correlation_id)
+        .bind(&item.object_type)
+        .bind(&item.body)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok({})
+}
+
+#[cfg(feature = "sqlite")]
+pub async fn add_outbox_events(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    items: &Vec<publisher::OutboxEvent>,
+) -> Result<(), sqlx::Error> {
+    for item in items {
+        sqlx::query(
+            r#"INSERT INTO webapi.event_outbox ( correlation_id, object_type, body ) 
+            VALUES ( ?, ?, ? )"#,
+        )
+        .bind(&item.correlation_id)
+        .bind(&item.object_type)
+        .bind(&item.body)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok({})
+}
+
+pub struct EventOutboxCollection {
+    data_provider: Arc<providers::SqlDbProvider>,
+    exp_helper: &'static connectors::ExpHelper,
+}
+
+impl EventOutboxCollection {
+    pub fn new(
+        data_provider: Arc<providers::SqlDbProvider>,
+        helper: &'static connectors::ExpHelper,
+    ) -> EventOutboxCollection {
+        EventOutboxCollection {
+            data_provider: data_provider,
+            exp_helper: &helper,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::EventOutboxStorage for EventOutboxCollection {
+    // oldest first
+    async fn get(&self, limit: i64) -> connectors::Result<Vec<publisher::OutboxEvent>> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        #[cfg(feature = "postgres")]
+        let query = r#"SELECT * FROM webapi.event_outbox ORDER BY id LIMIT $1"#;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let query = r#"SELECT * FROM webapi.event_outbox ORDER BY id LIMIT ?"#;
+        Ok(sqlx::query_as(query).bind(limit).fetch_all(pool).await?)
+    }
+
+    // events without entity change
+    async fn add(
+        &self,
+        items: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        match add_outbox_events(&mut tx, &items).await {
+            Ok(_) => {}
+            Err(e) => {
+                tx.rollback().await.unwrap();
+                error!("add_outbox_events db insert: {}", e);
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("add_outbox_events db commit: {}", e);
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        }
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        match self
+            .exp_helper
+            .bind_int_ids(
+                sqlx::query(
+                    &self
+                        .exp_helper
+                        .get_delete_int_exp("webapi.event_outbox", "id", &ids),
+                ),
+                &ids,
+            )
+            .execute(pool)
+            .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() as usize == ids.len() {
+                    Ok(errors::ErrorCode::ReplyOk)
+                } else {
+                    Ok(errors::ErrorCode::NotFoundError)
+                }
+            }
+            Err(e) => {
+                error!("remove_outbox_events db delete: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+}
+
+pub struct UndeliveredEventCollection {
+    data_provider: Arc<providers::SqlDbProvider>,
+    exp_helper: &'static connectors::ExpHelper,
+}
+
+impl UndeliveredEventCollection {
+    pub fn new(
+        data_provider: Arc<providers::SqlDbProvider>,
+        helper: &'static connectors::ExpHelper,
+    ) -> UndeliveredEventCollection {
+        UndeliveredEventCollection {
+            data_provider: data_provider,
+            exp_helper: &helper,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::UndeliveredEventStorage for UndeliveredEventCollection {
+    // events with elapsed next attempt time, oldest first
+    async fn get_due(&self, limit: i64) -> connectors::Result<Vec<publisher::UndeliveredEvent>> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        #[cfg(feature = "postgres")]
+        let query = r#"SELECT * FROM webapi.undelivered_event 
+            WHERE next_attempt_at <= CURRENT_TIMESTAMP ORDER BY next_attempt_at LIMIT $1"#;
+        #[cfg(feature = "mysql")]
+        let query = r#"SELECT * FROM webapi.undelivered_event 
+            WHERE next_attempt_at <= CURRENT_TIMESTAMP ORDER BY next_attempt_at LIMIT ?"#;
+        #[cfg(feature = "sqlite")]
+        let query = r#"SELECT * FROM webapi.undelivered_event 
+            WHERE datetime(next_attempt_at) <= datetime('now') ORDER BY next_attempt_at LIMIT ?"#;
+        Ok(sqlx::query_as(query).bind(limit).fetch_all(pool).await?)
+    }
+
+    async fn add(
+        &self,
+        items: Vec<publisher::UndeliveredEvent>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        let mut ids = Vec::<i32>::new();
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        for item in items {
+            #[cfg(feature = "postgres")]
+            match sqlx::query!(
+                r#"INSERT INTO webapi.undelivered_event ( correlation_id, object_type, "service_name", 
+                    proto, "path", body, attempt, last_error, next_attempt_at ) 
+                        VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9 ) RETURNING id"#,
+                item.correlation_id,
+                item.object_type,
+                item.service_name,
+                item.proto,
+                item.path,
+                item.body,
+                item.attempt,
+                item.last_error,
+                item.next_attempt_at
+            )
+            .fetch_one(&mut *tx)
+            .await
+            {
+                Ok(rec) => ids.push(rec.id),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_undelivered_events db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            match sqlx::query(
+                r#"INSERT INTO webapi.undelivered_event ( correlation_id, object_type, service_name, 
+                proto, path, body, attempt, last_error, next_attempt_at ) 
+                    VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, ? )"#,
+            )
+            .bind(item.correlation_id)
+            .bind(item.object_type)
+            .bind(item.service_name)
+            .bind(item.proto)
+            .bind(item.path)
+            .bind(item.body)
+            .bind(item.attempt)
+            .bind(item.last_error)
+            .bind(item.next_attempt_at)
+            .execute(&mut *tx)
+            .await
+            {
+                Ok(_) => {}
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_undelivered_events db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(feature = "mysql")]
+            match sqlx::query(r#"SELECT LAST_INSERT_ID() AS id;"#)
+                .fetch_one(&mut *tx)
+                .await
+            {
+                Ok(rec) => ids.push(rec.get::<u64, _>(0) as i32),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_undelivered_events db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(feature = "sqlite")]
+            match sqlx::query(r#"SELECT last_insert_rowid() AS id;"#)
+                .fetch_one(&mut *tx)
+                .await
+            {
+                Ok(rec) => ids.push(rec.get::<i64, _>(0) as i32),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_undelivered_events db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+        }
+        match tx.commit().await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("add_undelivered_events db commit: {}", e);
+                return Ok((errors::ErrorCode::DatabaseError, None));
+            }
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    // count failed attempt and schedule next one
+    async fn postpone(
+        &self,
+        id: i32,
+        last_error: String,
+        next_attempt_at: DateTime<Utc>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        #[cfg(feature = "postgres")]
+        let query = r#"UPDATE webapi.undelivered_event 
+            SET attempt = attempt + 1, last_error = $1, next_attempt_at = $2 WHERE id = $3"#;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let query = r#"UPDATE webapi.undelivered_event 
+            SET attempt = attempt + 1, last_error = ?, next_attempt_at = ? WHERE id = ?"#;
+        match sqlx::query(query)
+            .bind(last_error)
+            .bind(next_attempt_at)
+            .bind(id)
+            .execute(pool)
+            .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() == 1 {
+                    Ok(errors::ErrorCode::ReplyOk)
+                } else {
+                    Ok(errors::ErrorCode::NotFoundError)
+                }
+            }
+            Err(e) => {
+                error!("postpone_undelivered_events db update: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+
+    // move event with exhausted attempts to dead letter table
+    async fn move_to_dead_letter(
+        &self,
+        id: i32,
+        last_error: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        #[cfg(feature = "postgres")]
+        let query = r#"INSERT INTO webapi.dead_letter_event ( id, correlation_id, object_type, 
+            "service_name", proto, "path", body, attempt, last_error, next_attempt_at, added_at ) 
+                SELECT id, correlation_id, object_type, "service_name", proto, "path", body, 
+                    attempt + 1, $1, CURRENT_TIMESTAMP, added_at 
+                        FROM webapi.undelivered_event WHERE id = $2"#;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let query = r#"INSERT INTO webapi.dead_letter_event ( id, correlation_id, object_type, 
+            service_name, proto, path, body, attempt, last_error, next_attempt_at, added_at ) 
+                SELECT id, correlation_id, object_type, service_name, proto, path, body, 
+                    attempt + 1, ?, CURRENT_TIMESTAMP, added_at 
+                        FROM webapi.undelivered_event WHERE id = ?"#;
+        match sqlx::query(query)
+            .bind(last_error)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() != 1 {
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::NotFoundError);
+                }
+            }
+            Err(e) => {
+                error!("move_undelivered_events db dead letter insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match self
+            .exp_helper
+            .bind_int_ids(
+                sqlx::query(&self.exp_helper.get_delete_int_exp(
+                    "webapi.undelivered_event",
+                    "id",
+                    &vec![id],
+                )),
+                &vec![id],
+            )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("move_undelivered_events db delete: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => Ok(errors::ErrorCode::ReplyOk),
+            Err(e) => {
+                error!("move_undelivered_events db commit: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        match self
+            .exp_helper
+            .bind_int_ids(
+                sqlx::query(&self.exp_helper.get_delete_int_exp(
+                    "webapi.undelivered_event",
+                    "id",
+                    &ids,
+                )),
+                &ids,
+            )
+            .execute(pool)
+            .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() as usize == ids.len() {
+                    Ok(errors::ErrorCode::ReplyOk)
+                } else {
+                    Ok(errors::ErrorCode::NotFoundError)
+                }
+            }
+            Err(e) => {
+                error!("remove_undelivered_events db delete: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+}
+
+pub struct DeadLetterEventCollection {
+    data_provider: Arc<providers::SqlDbProvider>,
+    exp_helper: &'static connectors::ExpHelper,
+}
+
+impl DeadLetterEventCollection {
+    pub fn new(
+        data_provider: Arc<providers::SqlDbProvider>,
+        helper: &'static connectors::ExpHelper,
+    ) -> DeadLetterEventCollection {
+        DeadLetterEventCollection {
+            data_provider: data_provider,
+            exp_helper: &helper,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::DeadLetterEventStorage for DeadLetterEventCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<i32>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<publisher::UndeliveredEvent>, filters::PageInfo)> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let first_param = if ids.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, DEAD_LETTER_EVENT_FILTER, first_param)?;
+        let paging = filters::Paging::parse(page, DEAD_LETTER_EVENT_FILTER, &["id"])?;
+        let ids_count = ids.as_ref().map(|i| i.len());
+        let query = self.exp_helper.get_select_filter_exp(
+            "webapi.dead_letter_event",
+            "id",
+            ids_count,
+            &filter,
+            &paging,
+        );
+        let mut q = sqlx::query_as(&query);
+        if let Some(ids) = &ids {
+            q = self.exp_helper.bind_int_ids_as(q, ids);
+        }
+        let items: Vec<publisher::UndeliveredEvent> = self
+            .exp_helper
+            .bind_filter_as(q, &filter)
+            .fetch_all(pool)
+            .await?;
+        let count_query = self.exp_helper.get_count_filter_exp(
+            "webapi.dead_letter_event",
+            "id",
+            ids_count,
+            &filter,
+        );
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &ids {
+            q = self.exp_helper.bind_int_ids(q, ids);
+        }
+        let page_info = self
+            .exp_helper
+            .get_page_info(
+                pool,
+                self.exp_helper.bind_filter(q, &filter),
+                &paging,
+                items.len(),
+            )
+            .await?;
+        Ok((items, page_info))
+    }
+
+    // return events to undelivered with reset attempt counter
+    async fn replay(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        #[cfg(feature = "postgres")]
+        let query = format!(
+            r#"INSERT INTO webapi.undelivered_event ( id, correlation_id, object_type, 
+            "service_name", proto, "path", body, attempt, last_error, next_attempt_at, added_at ) 
+                SELECT id, correlation_id, object_type, "service_name", proto, "path", body, 
+                    0, last_error, CURRENT_TIMESTAMP, added_at 
+                        FROM webapi.dead_letter_event WHERE {}"#,
+            self.exp_helper.get_ids_exp("id", ids.len())
+        );
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let query = format!(
+            r#"INSERT INTO webapi.undelivered_event ( id, correlation_id, object_type, 
+            service_name, proto, path, body, attempt, last_error, next_attempt_at, added_at ) 
+                SELECT id, correlation_id, object_type, service_name, proto, path, body, 
+                    0, last_error, CURRENT_TIMESTAMP, added_at 
+                        FROM webapi.dead_letter_event WHERE {}"#,
+            self.exp_helper.get_ids_exp("id", ids.len())
+        );
+        match self
+            .exp_helper
+            .bind_int_ids(sqlx::query(&query), &ids)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(ret) => {
+                if ret.rows_affected() as usize != ids.len() {
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::NotFoundError);
+                }
+            }
+            Err(e) => {
+                error!("replay_dead_letter_events db undelivered insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match self
+            .exp_helper
+            .bind_int_ids(
+                sqlx::query(&self.exp_helper.get_delete_int_exp(
+                    "webapi.dead_letter_event",
+                    "id",
+                    &ids,
+                )),
+                &ids,
+            )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => {}
+            Err(e) => {
+                error!("replay_dead_letter_events db delete: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match tx.commit().await {
+            Ok(_) => Ok(errors::ErrorCode::ReplyOk),
+            Err(e) => {
+                error!("replay_dead_letter_events db commit: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+}
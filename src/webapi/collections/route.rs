@@ -1,10 +1,23 @@
-use super::super::{connectors, entities::route, errors, providers};
+use super::super::{
+    commands, connectors,
+    entities::{
+        publisher,
+        route::{
+            self, ROUTE_FILTER, SERVICE_COMMAND_FILTER, SERVICE_EVENT_FILTER, SERVICE_FILTER,
+            SERVICE_SUBSCRIPTION_FILTER,
+        },
+    },
+    errors, filters, providers, traits,
+};
+use async_trait::async_trait;
 use futures::TryStreamExt;
 #[cfg(feature = "postgres")]
 use sqlx::postgres::PgPool;
 #[cfg(feature = "mysql")]
 use sqlx::MySqlPool;
 use sqlx::Row;
+#[cfg(feature = "sqlite")]
+use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -24,23 +37,40 @@ impl RouteCollection {
             exp_helper: &helper,
         }
     }
+}
 
-    pub async fn get_command(
+#[async_trait]
+impl traits::RouteStorage for RouteCollection {
+    async fn get_command(
         &self,
         services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::ServiceCommand>> {
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceCommand>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut items = Vec::<route::ServiceCommand>::new();
-        if services.is_none() {
+        let first_param = if services.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, SERVICE_COMMAND_FILTER, first_param)?;
+        let paging = filters::Paging::parse(
+            page,
+            SERVICE_COMMAND_FILTER,
+            &["service_name", "object_type"],
+        )?;
+        let ids_count = services.as_ref().map(|i| i.len());
+        if services.is_none() && filter.is_none() && paging.is_none() {
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
                 r#"SELECT service_name, priority, object_type, description, exec_mode, reply_type
             FROM webapi.v_service_command"#,
             )
             .fetch_all(pool)
             .await?;
+            #[cfg(feature = "postgres")]
             for rec in recs {
                 items.push(route::ServiceCommand {
                     service_name: rec.service_name,
@@ -53,13 +83,39 @@ impl RouteCollection {
                     state: None,
                 })
             }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT service_name, priority, object_type, description, exec_mode, reply_type
+            FROM webapi.v_service_command"#,
+            )
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                items.push(route::ServiceCommand {
+                    service_name: rec.get(0),
+                    priority: rec.get(1),
+                    object_type: rec.get(2),
+                    description: rec.get(3),
+                    exec_mode: rec.get(4),
+                    reply_type: rec.get(5),
+                    path: None,
+                    state: None,
+                })
+            }
         } else {
-            let query = self.exp_helper.get_select_str_exp(
+            let query = self.exp_helper.get_select_filter_exp(
                 "webapi.v_service_command",
                 "service_name",
-                &services.unwrap(),
+                ids_count,
+                &filter,
+                &paging,
             );
-            let mut cursor = sqlx::query(&query).fetch(pool);
+            let mut q = sqlx::query(&query);
+            if let Some(ids) = &services {
+                q = self.exp_helper.bind_str_ids(q, ids);
+            }
+            let mut cursor = self.exp_helper.bind_filter(q, &filter).fetch(pool);
             while let Some(rec) = cursor.try_next().await? {
                 items.push(route::ServiceCommand {
                     service_name: rec.get(0),
@@ -74,6 +130,8 @@ impl RouteCollection {
             }
         }
         for mut item in &mut items {
+            let mut p = HashMap::<String, String>::new();
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
                 r#"SELECT proto, "to" FROM webapi.v_service_command_path 
                     WHERE "service_name" = $1 AND object_type = $2"#,
@@ -82,16 +140,31 @@ impl RouteCollection {
             )
             .fetch_all(pool)
             .await?;
-            let mut p = HashMap::<String, String>::new();
+            #[cfg(feature = "postgres")]
             for rec in recs {
                 p.insert(
                     rec.proto.as_ref().unwrap().to_string(),
                     rec.to.as_ref().unwrap().to_string(),
                 );
             }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT proto, `to` FROM webapi.v_service_command_path 
+                    WHERE `service_name` = ? AND object_type = ?"#,
+            )
+            .bind(item.service_name.as_ref().unwrap())
+            .bind(&item.object_type)
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                p.insert(rec.get(0), rec.get(1));
+            }
             item.path = Some(p);
         }
         for mut item in &mut items {
+            let mut p = HashMap::<String, String>::new();
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
                 r#"SELECT "state", "description" FROM webapi.v_service_command_state 
                     WHERE "service_name" = $1 AND object_type = $2"#,
@@ -100,57 +173,150 @@ impl RouteCollection {
             )
             .fetch_all(pool)
             .await?;
-            if recs.len() > 0 {
-                let mut p = HashMap::<String, String>::new();
-                for rec in recs {
-                    p.insert(
-                        rec.state.as_ref().unwrap().to_string(),
-                        rec.description.as_ref().unwrap().to_string(),
-                    );
-                }
+            #[cfg(feature = "postgres")]
+            for rec in recs {
+                p.insert(
+                    rec.state.as_ref().unwrap().to_string(),
+                    rec.description.as_ref().unwrap().to_string(),
+                );
+            }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT `state`, `description` FROM webapi.v_service_command_state 
+                    WHERE `service_name` = ? AND object_type = ?"#,
+            )
+            .bind(item.service_name.as_ref().unwrap())
+            .bind(&item.object_type)
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                p.insert(rec.get(0), rec.get(1));
+            }
+            if p.len() > 0 {
                 item.state = Some(p);
             }
         }
-        Ok(items)
+        let count_query = self.exp_helper.get_count_filter_exp(
+            "webapi.v_service_command",
+            "service_name",
+            ids_count,
+            &filter,
+        );
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &services {
+            q = self.exp_helper.bind_str_ids(q, ids);
+        }
+        let page_info = self
+            .exp_helper
+            .get_page_info(
+                pool,
+                self.exp_helper.bind_filter(q, &filter),
+                &paging,
+                items.len(),
+            )
+            .await?;
+        Ok((items, page_info))
     }
 
-    pub async fn get_event(
+    async fn get_event(
         &self,
         services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::ServiceEvent>> {
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceEvent>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
-        if services.is_none() {
-            Ok(sqlx::query_as!(
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let first_param = if services.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, SERVICE_EVENT_FILTER, first_param)?;
+        let paging =
+            filters::Paging::parse(page, SERVICE_EVENT_FILTER, &["service_name", "object_type"])?;
+        let ids_count = services.as_ref().map(|i| i.len());
+        let items: Vec<route::ServiceEvent> = if services.is_none()
+            && filter.is_none()
+            && paging.is_none()
+        {
+            #[cfg(feature = "postgres")]
+            let items = sqlx::query_as!(
                 route::ServiceEvent,
                 r#"SELECT "service_name" as "service_name?", object_type as "object_type!", "description" as "description!"
             FROM webapi.v_service_event"#
             )
             .fetch_all(pool)
-            .await?)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let items = sqlx::query_as(
+                r#"SELECT `service_name`, object_type, `description` FROM webapi.v_service_event"#,
+            )
+            .fetch_all(pool)
+            .await?;
+            items
         } else {
-            let query = self.exp_helper.get_select_str_exp(
+            let query = self.exp_helper.get_select_filter_exp(
                 "webapi.v_service_event",
                 "service_name",
-                &services.unwrap(),
+                ids_count,
+                &filter,
+                &paging,
             );
-            let items: Vec<route::ServiceEvent> = sqlx::query_as(&query).fetch_all(pool).await?;
-            Ok(items)
+            let mut q = sqlx::query_as(&query);
+            if let Some(ids) = &services {
+                q = self.exp_helper.bind_str_ids_as(q, ids);
+            }
+            self.exp_helper
+                .bind_filter_as(q, &filter)
+                .fetch_all(pool)
+                .await?
+        };
+        let count_query = self.exp_helper.get_count_filter_exp(
+            "webapi.v_service_event",
+            "service_name",
+            ids_count,
+            &filter,
+        );
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &services {
+            q = self.exp_helper.bind_str_ids(q, ids);
         }
+        let page_info = self
+            .exp_helper
+            .get_page_info(
+                pool,
+                self.exp_helper.bind_filter(q, &filter),
+                &paging,
+                items.len(),
+            )
+            .await?;
+        Ok((items, page_info))
     }
 
-    pub async fn get_subscription(
+    async fn get_subscription(
         &self,
         services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::ServiceSubscription>> {
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceSubscription>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut items = Vec::<route::ServiceSubscription>::new();
-        if services.is_none() {
+        let first_param = if services.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, SERVICE_SUBSCRIPTION_FILTER, first_param)?;
+        let paging = filters::Paging::parse(
+            page,
+            SERVICE_SUBSCRIPTION_FILTER,
+            &["service_name", "object_type"],
+        )?;
+        let ids_count = services.as_ref().map(|i| i.len());
+        if services.is_none() && filter.is_none() && paging.is_none() {
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
                 r#"SELECT "service_name", object_type
             FROM webapi."v_service_subscription"
@@ -158,6 +324,7 @@ impl RouteCollection {
             )
             .fetch_all(pool)
             .await?;
+            #[cfg(feature = "postgres")]
             for rec in recs {
                 items.push(route::ServiceSubscription {
                     service_name: rec.service_name,
@@ -165,13 +332,33 @@ impl RouteCollection {
                     path: None,
                 })
             }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT `service_name`, object_type FROM webapi.v_service_subscription"#,
+            )
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                items.push(route::ServiceSubscription {
+                    service_name: rec.get(0),
+                    object_type: rec.get(1),
+                    path: None,
+                })
+            }
         } else {
-            let query = self.exp_helper.get_select_str_exp(
+            let query = self.exp_helper.get_select_filter_exp(
                 "webapi.v_service_subscription",
                 "service_name",
-                &services.unwrap(),
+                ids_count,
+                &filter,
+                &paging,
             );
-            let mut cursor = sqlx::query(&query).fetch(pool);
+            let mut q = sqlx::query(&query);
+            if let Some(ids) = &services {
+                q = self.exp_helper.bind_str_ids(q, ids);
+            }
+            let mut cursor = self.exp_helper.bind_filter(q, &filter).fetch(pool);
             while let Some(rec) = cursor.try_next().await? {
                 items.push(route::ServiceSubscription {
                     service_name: rec.get(0),
@@ -181,6 +368,8 @@ impl RouteCollection {
             }
         }
         for mut item in &mut items {
+            let mut p = HashMap::<String, String>::new();
+            #[cfg(feature = "postgres")]
             let recs = sqlx::query!(
                 r#"SELECT proto, "to" FROM webapi.v_service_subscription_path 
                     WHERE "service_name" = $1 AND object_type = $2"#,
@@ -189,44 +378,120 @@ impl RouteCollection {
             )
             .fetch_all(pool)
             .await?;
-            let mut p = HashMap::<String, String>::new();
+            #[cfg(feature = "postgres")]
             for rec in recs {
                 p.insert(
                     rec.proto.as_ref().unwrap().to_string(),
                     rec.to.as_ref().unwrap().to_string(),
                 );
             }
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let recs = sqlx::query(
+                r#"SELECT proto, `to` FROM webapi.v_service_subscription_path 
+                    WHERE `service_name` = ? AND object_type = ?"#,
+            )
+            .bind(item.service_name.as_ref().unwrap())
+            .bind(&item.object_type)
+            .fetch_all(pool)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            for rec in recs {
+                p.insert(rec.get(0), rec.get(1));
+            }
             item.path = Some(p);
         }
-        Ok(items)
+        let count_query = self.exp_helper.get_count_filter_exp(
+            "webapi.v_service_subscription",
+            "service_name",
+            ids_count,
+            &filter,
+        );
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &services {
+            q = self.exp_helper.bind_str_ids(q, ids);
+        }
+        let page_info = self
+            .exp_helper
+            .get_page_info(
+                pool,
+                self.exp_helper.bind_filter(q, &filter),
+                &paging,
+                items.len(),
+            )
+            .await?;
+        Ok((items, page_info))
     }
 
-    pub async fn get_service(
+    async fn get_service(
         &self,
         names: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::Service>> {
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Service>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
-        if names.is_none() {
-            Ok(sqlx::query_as!(
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let first_param = if names.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, SERVICE_FILTER, first_param)?;
+        let paging = filters::Paging::parse(page, SERVICE_FILTER, &["name"])?;
+        let ids_count = names.as_ref().map(|i| i.len());
+        let items: Vec<route::Service> = if names.is_none() && filter.is_none() && paging.is_none()
+        {
+            #[cfg(feature = "postgres")]
+            let items = sqlx::query_as!(
                 route::Service,
                 r#"SELECT name as "name!", description as "description!", priority as "priority!", "state" as "state!", added_at as "added_at!"
             FROM webapi.v_service"#
             )
             .fetch_all(pool)
-            .await?)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let items = sqlx::query_as(
+                r#"SELECT `name`, `description`, priority, `state`, added_at FROM webapi.v_service"#,
+            )
+            .fetch_all(pool)
+            .await?;
+            items
         } else {
-            let query =
-                self.exp_helper
-                    .get_select_str_exp("webapi.v_service", "name", &names.unwrap());
-            let items: Vec<route::Service> = sqlx::query_as(&query).fetch_all(pool).await?;
-            Ok(items)
+            let query = self.exp_helper.get_select_filter_exp(
+                "webapi.v_service",
+                "name",
+                ids_count,
+                &filter,
+                &paging,
+            );
+            let mut q = sqlx::query_as(&query);
+            if let Some(ids) = &names {
+                q = self.exp_helper.bind_str_ids_as(q, ids);
+            }
+            self.exp_helper
+                .bind_filter_as(q, &filter)
+                .fetch_all(pool)
+                .await?
+        };
+        let count_query =
+            self.exp_helper
+                .get_count_filter_exp("webapi.v_service", "name", ids_count, &filter);
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &names {
+            q = self.exp_helper.bind_str_ids(q, ids);
         }
+        let page_info = self
+            .exp_helper
+            .get_page_info(
+                pool,
+                self.exp_helper.bind_filter(q, &filter),
+                &paging,
+                items.len(),
+            )
+            .await?;
+        Ok((items, page_info))
     }
 
-    pub async fn get_service_path(
+    async fn get_service_path(
         &self,
         services: Option<Vec<String>>,
     ) -> connectors::Result<Vec<route::ServicePath>> {
@@ -234,21 +499,26 @@ impl RouteCollection {
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut items = Vec::<route::ServicePath>::new();
         let is_services_some = services.is_some();
-        let query = self.exp_helper.get_select_str_exp(
-            "webapi.v_service_path",
-            "service_name",
-            &services.unwrap_or_default(),
-        );
+        let ids = services.unwrap_or_default();
+        let query =
+            self.exp_helper
+                .get_select_str_exp("webapi.v_service_path", "service_name", &ids);
+        #[cfg(feature = "postgres")]
+        let all_query = r#"SELECT "service_name", proto, helth, "schema", reply_to, "state", "error"
+            FROM webapi.v_service_path"#;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let all_query = r#"SELECT `service_name`, proto, helth, `schema`, reply_to, `state`, `error`
+            FROM webapi.v_service_path"#;
         let mut cursor = if !is_services_some {
-            sqlx::query(
-                r#"SELECT "service_name", proto, helth, "schema", reply_to, "state", "error"
-            FROM webapi.v_service_path"#,
-            )
-            .fetch(pool)
+            sqlx::query(all_query).fetch(pool)
         } else {
-            sqlx::query(&query).fetch(pool)
+            self.exp_helper
+                .bind_str_ids(sqlx::query(&query), &ids)
+                .fetch(pool)
         };
         while let Some(rec) = cursor.try_next().await? {
             items.push(route::ServicePath {
@@ -266,141 +536,271 @@ impl RouteCollection {
         Ok(items)
     }
 
-    pub async fn get(
+    async fn get(
         &self,
         services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::Route>> {
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Route>, filters::PageInfo)> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut items = Vec::<route::Route>::new();
-        let is_services_some = services.is_some();
-        let query = self.exp_helper.get_select_str_exp(
+        let first_param = if services.is_some() { 2 } else { 1 };
+        let filter = filters::Filter::parse(filter, ROUTE_FILTER, first_param)?;
+        let paging = filters::Paging::parse(page, ROUTE_FILTER, &["name"])?;
+        let ids_count = services.as_ref().map(|i| i.len());
+        let query = self.exp_helper.get_select_filter_exp(
             "webapi.v_service",
             "name",
-            &services.unwrap_or_default(),
+            ids_count,
+            &filter,
+            &paging,
         );
-        let mut cursor = if !is_services_some {
-            sqlx::query(r#"SELECT "name", "description", "priority" FROM webapi.v_service"#)
-                .fetch(pool)
-        } else {
-            sqlx::query(&query).fetch(pool)
-        };
+        let mut q = sqlx::query(&query);
+        if let Some(ids) = &services {
+            q = self.exp_helper.bind_str_ids(q, ids);
+        }
+        let mut cursor = self.exp_helper.bind_filter(q, &filter).fetch(pool);
         while let Some(service_rec) = cursor.try_next().await? {
             let service_name: String = service_rec.get(0);
-            let mut commands = Vec::<route::ServiceCommand>::new();
-            let command_recs = sqlx::query!(
-                r#"SELECT object_type, reply_type, description, exec_mode 
-                    FROM webapi.v_service_command WHERE service_name = $1"#,
-                &service_name,
-            )
-            .fetch_all(pool)
-            .await?;
-            for command_rec in command_recs {
-                let path_recs = sqlx::query!(
-                    r#"SELECT proto, "to" 
-                        FROM webapi.v_service_command_path 
-                            WHERE service_name = $1 AND object_type = $2"#,
+            #[cfg(feature = "postgres")]
+            let (commands, events, subscriptions, p) = {
+                let mut commands = Vec::<route::ServiceCommand>::new();
+                let command_recs = sqlx::query!(
+                    r#"SELECT object_type, reply_type, description, exec_mode 
+                        FROM webapi.v_service_command WHERE service_name = $1"#,
                     &service_name,
-                    command_rec.object_type.as_ref().unwrap()
                 )
                 .fetch_all(pool)
                 .await?;
-                let mut p = HashMap::<String, String>::new();
-                for rec in path_recs {
-                    p.insert(rec.proto.unwrap(), rec.to.unwrap());
+                for command_rec in command_recs {
+                    let path_recs = sqlx::query!(
+                        r#"SELECT proto, "to" 
+                            FROM webapi.v_service_command_path 
+                                WHERE service_name = $1 AND object_type = $2"#,
+                        &service_name,
+                        command_rec.object_type.as_ref().unwrap()
+                    )
+                    .fetch_all(pool)
+                    .await?;
+                    let mut p = HashMap::<String, String>::new();
+                    for rec in path_recs {
+                        p.insert(rec.proto.unwrap(), rec.to.unwrap());
+                    }
+                    let state_recs = sqlx::query!(
+                        r#"SELECT "state", "description" 
+                            FROM webapi.v_service_command_state 
+                                WHERE service_name = $1 AND object_type = $2"#,
+                        &service_name,
+                        command_rec.object_type.as_ref().unwrap()
+                    )
+                    .fetch_all(pool)
+                    .await?;
+                    let mut sh = HashMap::<String, String>::new();
+                    for rec in state_recs {
+                        sh.insert(rec.state.unwrap(), rec.description.unwrap());
+                    }
+                    let s = if sh.len() > 0 { Some(sh) } else { None };
+                    commands.push(route::ServiceCommand {
+                        service_name: None,
+                        priority: None,
+                        object_type: command_rec.object_type.unwrap(),
+                        reply_type: command_rec.reply_type.unwrap(),
+                        description: command_rec.description.unwrap(),
+                        exec_mode: command_rec.exec_mode.unwrap(),
+                        path: Some(p),
+                        state: s,
+                    });
                 }
-                let state_recs = sqlx::query!(
-                    r#"SELECT "state", "description" 
-                        FROM webapi.v_service_command_state 
-                            WHERE service_name = $1 AND object_type = $2"#,
-                    &service_name,
-                    command_rec.object_type.as_ref().unwrap()
+                let mut events = Vec::<route::ServiceEvent>::new();
+                let event_recs = sqlx::query!(
+                    r#"SELECT object_type, description FROM webapi.v_service_event WHERE service_name = $1"#,
+                    &service_name
                 )
                 .fetch_all(pool)
                 .await?;
-                let mut sh = HashMap::<String, String>::new();
-                for rec in state_recs {
-                    sh.insert(rec.state.unwrap(), rec.description.unwrap());
+                for event_rec in event_recs {
+                    events.push(route::ServiceEvent {
+                        service_name: None,
+                        object_type: event_rec.object_type.unwrap(),
+                        description: event_rec.description.unwrap(),
+                    });
+                }
+                let mut subscriptions = Vec::<route::ServiceSubscription>::new();
+                let subscription_recs = sqlx::query!(
+                    r#"SELECT object_type FROM webapi.v_service_subscription WHERE service_name = $1"#,
+                    &service_name
+                )
+                .fetch_all(pool)
+                .await?;
+                for subscription_rec in subscription_recs {
+                    let recs = sqlx::query!(
+                        r#"SELECT proto, "to" FROM webapi.v_service_subscription_path 
+                            WHERE service_name = $1 AND object_type = $2"#,
+                        &service_name,
+                        subscription_rec.object_type.as_ref().unwrap()
+                    )
+                    .fetch_all(pool)
+                    .await?;
+                    let mut p = HashMap::<String, String>::new();
+                    for rec in recs {
+                        p.insert(rec.proto.unwrap(), rec.to.unwrap());
+                    }
+                    subscriptions.push(route::ServiceSubscription {
+                        service_name: None,
+                        object_type: subscription_rec.object_type.unwrap(),
+                        path: Some(p),
+                    });
                 }
-                let s = if sh.len() > 0 { Some(sh) } else { None };
-                commands.push(route::ServiceCommand {
-                    service_name: None,
-                    priority: None,
-                    object_type: command_rec.object_type.unwrap(),
-                    reply_type: command_rec.reply_type.unwrap(),
-                    description: command_rec.description.unwrap(),
-                    exec_mode: command_rec.exec_mode.unwrap(),
-                    path: Some(p),
-                    state: s,
-                });
-            }
-            let mut events = Vec::<route::ServiceEvent>::new();
-            let event_recs = sqlx::query!(
-                r#"SELECT object_type, description FROM webapi.v_service_event WHERE service_name = $1"#,
-                &service_name
-            )
-            .fetch_all(pool)
-            .await?;
-            for event_rec in event_recs {
-                events.push(route::ServiceEvent {
-                    service_name: None,
-                    object_type: event_rec.object_type.unwrap(),
-                    description: event_rec.description.unwrap(),
-                });
-            }
-            let mut subscriptions = Vec::<route::ServiceSubscription>::new();
-            let subscription_recs = sqlx::query!(
-                r#"SELECT object_type FROM webapi.v_service_subscription WHERE service_name = $1"#,
-                &service_name
-            )
-            .fetch_all(pool)
-            .await?;
-            for subscription_rec in subscription_recs {
                 let recs = sqlx::query!(
-                    r#"SELECT proto, "to" FROM webapi.v_service_subscription_path 
-                        WHERE service_name = $1 AND object_type = $2"#,
-                    &service_name,
-                    subscription_rec.object_type.as_ref().unwrap()
+                    r#"SELECT proto, helth, schema, reply_to, "state", "error" 
+                        FROM webapi.v_service_path WHERE service_name = $1"#,
+                    &service_name
                 )
                 .fetch_all(pool)
                 .await?;
-                let mut p = HashMap::<String, String>::new();
+                let mut p = HashMap::<String, route::ServicePath>::new();
                 for rec in recs {
-                    p.insert(rec.proto.unwrap(), rec.to.unwrap());
+                    let proto = rec.proto.unwrap();
+                    p.insert(
+                        proto.clone(),
+                        route::ServicePath {
+                            service_name: None,
+                            proto: Some(proto),
+                            helth: rec.helth.unwrap(),
+                            schema: rec.schema.unwrap(),
+                            reply_to: rec.reply_to.unwrap(),
+                            state: rec.state.unwrap(),
+                            error: rec.error.unwrap(),
+                            request: None,
+                            event: None,
+                        },
+                    );
                 }
-                subscriptions.push(route::ServiceSubscription {
-                    service_name: None,
-                    object_type: subscription_rec.object_type.unwrap(),
-                    path: Some(p),
-                });
-            }
-            let recs = sqlx::query!(
-                r#"SELECT proto, helth, schema, reply_to, "state", "error" 
-                    FROM webapi.v_service_path WHERE service_name = $1"#,
-                &service_name
-            )
-            .fetch_all(pool)
-            .await?;
-            let mut p = HashMap::<String, route::ServicePath>::new();
-            for rec in recs {
-                let proto = rec.proto.unwrap();
-                p.insert(
-                    proto.clone(),
-                    route::ServicePath {
+                (commands, events, subscriptions, p)
+            };
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let (commands, events, subscriptions, p) = {
+                let mut commands = Vec::<route::ServiceCommand>::new();
+                let command_recs = sqlx::query(
+                    r#"SELECT object_type, reply_type, description, exec_mode 
+                        FROM webapi.v_service_command WHERE service_name = ?"#,
+                )
+                .bind(&service_name)
+                .fetch_all(pool)
+                .await?;
+                for command_rec in command_recs {
+                    let object_type: String = command_rec.get(0);
+                    let path_recs = sqlx::query(
+                        r#"SELECT proto, `to` 
+                            FROM webapi.v_service_command_path 
+                                WHERE service_name = ? AND object_type = ?"#,
+                    )
+                    .bind(&service_name)
+                    .bind(&object_type)
+                    .fetch_all(pool)
+                    .await?;
+                    let mut p = HashMap::<String, String>::new();
+                    for rec in path_recs {
+                        p.insert(rec.get(0), rec.get(1));
+                    }
+                    let state_recs = sqlx::query(
+                        r#"SELECT `state`, `description` 
+                            FROM webapi.v_service_command_state 
+                                WHERE service_name = ? AND object_type = ?"#,
+                    )
+                    .bind(&service_name)
+                    .bind(&object_type)
+                    .fetch_all(pool)
+                    .await?;
+                    let mut sh = HashMap::<String, String>::new();
+                    for rec in state_recs {
+                        sh.insert(rec.get(0), rec.get(1));
+                    }
+                    let s = if sh.len() > 0 { Some(sh) } else { None };
+                    commands.push(route::ServiceCommand {
                         service_name: None,
-                        proto: Some(proto),
-                        helth: rec.helth.unwrap(),
-                        schema: rec.schema.unwrap(),
-                        reply_to: rec.reply_to.unwrap(),
-                        state: rec.state.unwrap(),
-                        error: rec.error.unwrap(),
-                        request: None,
-                        event: None,
-                    },
-                );
-            }
+                        priority: None,
+                        object_type: object_type,
+                        reply_type: command_rec.get(1),
+                        description: command_rec.get(2),
+                        exec_mode: command_rec.get(3),
+                        path: Some(p),
+                        state: s,
+                    });
+                }
+                let mut events = Vec::<route::ServiceEvent>::new();
+                let event_recs = sqlx::query(
+                    r#"SELECT object_type, description FROM webapi.v_service_event WHERE service_name = ?"#,
+                )
+                .bind(&service_name)
+                .fetch_all(pool)
+                .await?;
+                for event_rec in event_recs {
+                    events.push(route::ServiceEvent {
+                        service_name: None,
+                        object_type: event_rec.get(0),
+                        description: event_rec.get(1),
+                    });
+                }
+                let mut subscriptions = Vec::<route::ServiceSubscription>::new();
+                let subscription_recs = sqlx::query(
+                    r#"SELECT object_type FROM webapi.v_service_subscription WHERE service_name = ?"#,
+                )
+                .bind(&service_name)
+                .fetch_all(pool)
+                .await?;
+                for subscription_rec in subscription_recs {
+                    let object_type: String = subscription_rec.get(0);
+                    let recs = sqlx::query(
+                        r#"SELECT proto, `to` FROM webapi.v_service_subscription_path 
+                            WHERE service_name = ? AND object_type = ?"#,
+                    )
+                    .bind(&service_name)
+                    .bind(&object_type)
+                    .fetch_all(pool)
+                    .await?;
+                    let mut p = HashMap::<String, String>::new();
+                    for rec in recs {
+                        p.insert(rec.get(0), rec.get(1));
+                    }
+                    subscriptions.push(route::ServiceSubscription {
+                        service_name: None,
+                        object_type: object_type,
+                        path: Some(p),
+                    });
+                }
+                let recs = sqlx::query(
+                    r#"SELECT proto, helth, `schema`, reply_to, `state`, `error` 
+                        FROM webapi.v_service_path WHERE service_name = ?"#,
+                )
+                .bind(&service_name)
+                .fetch_all(pool)
+                .await?;
+                let mut p = HashMap::<String, route::ServicePath>::new();
+                for rec in recs {
+                    let proto: String = rec.get(0);
+                    p.insert(
+                        proto.clone(),
+                        route::ServicePath {
+                            service_name: None,
+                            proto: Some(proto),
+                            helth: rec.get(1),
+                            schema: rec.get(2),
+                            reply_to: rec.get(3),
+                            state: rec.get(4),
+                            error: rec.get(5),
+                            request: None,
+                            event: None,
+                        },
+                    );
+                }
+                (commands, events, subscriptions, p)
+            };
             items.push(route::Route {
                 service_name: Some(service_name),
                 description: service_rec.get(1),
@@ -411,19 +811,46 @@ impl RouteCollection {
                 path: Some(p),
             });
         }
-        Ok(items)
+        let count_query =
+            self.exp_helper
+                .get_count_filter_exp("webapi.v_service", "name", ids_count, &filter);
+        let mut q = sqlx::query(&count_query);
+        if let Some(ids) = &services {
+            q = self.exp_helper.bind_str_ids(q, ids);
+        }
+        let page_info = self
+            .exp_helper
+            .get_page_info(
+                pool,
+                self.exp_helper.bind_filter(q, &filter),
+                &paging,
+                items.len(),
+            )
+            .await?;
+        Ok((items, page_info))
     }
 
-    pub async fn add(
+    async fn add(
         &self,
         items: Vec<route::Route>,
+        events: Vec<publisher::OutboxEvent>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
         let mut ids = Vec::<String>::new();
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut tx = pool.begin().await?;
+        match super::publisher::add_outbox_events(&mut tx, &events).await {
+            Ok(_) => {}
+            Err(e) => {
+                tx.rollback().await.unwrap();
+                error!("add_routes db outbox insert: {}", e);
+                return Ok((errors::ErrorCode::DatabaseError, None));
+            }
+        };
         for route in items {
             let service_name = route.service_name.unwrap();
             #[cfg(feature = "postgres")]
@@ -433,7 +860,7 @@ impl RouteCollection {
                 route.description,
                 route.priority
             )
-            .execute(&mut tx)
+            .execute(&mut *tx)
             .await
             {
                 Ok(_) => ids.push(service_name.clone()),
@@ -443,12 +870,12 @@ impl RouteCollection {
                     return Ok((errors::ErrorCode::DatabaseError, None));
                 }
             };
-            #[cfg(feature = "mysql")]
-            match sqlx::query(r#"INSERT INTO webapi.service ( 'name', description, priority ) VALUES ( ?, ?, ? )"#)
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            match sqlx::query(r#"INSERT INTO webapi.service ( `name`, description, priority ) VALUES ( ?, ?, ? )"#)
                 .bind(&service_name)
                 .bind(route.description)
                 .bind(route.priority)
-                .execute(&mut tx)
+                .execute(&mut *tx)
                 .await
             {
                 Ok(_) => ids.push(service_name.clone()),
@@ -472,7 +899,7 @@ impl RouteCollection {
                         (path.1).state,
                         (path.1).error
                     )
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -482,20 +909,20 @@ impl RouteCollection {
                             return Ok((errors::ErrorCode::DatabaseError, None));
                         }
                     };
-                #[cfg(feature = "mysql")]
+                #[cfg(any(feature = "mysql", feature = "sqlite"))]
                 match sqlx::query(
                     r#"INSERT INTO webapi.service_path 
-                        ( 'service_name', proto, helth, 'schema', 'reply_to', 'state', 'error' ) 
+                        ( `service_name`, proto, helth, `schema`, `reply_to`, `state`, `error` ) 
                         VALUES ( ?, ?, ?, ?, ?, ?, ? )"#,
                 )
                 .bind(&service_name)
                 .bind(path.0.clone())
-                .bind((path.1).helth)
-                .bind((path.1).schema)
-                .bind((path.1).reply_to)
-                .bind((path.1).state)
-                .bind((path.1).error)
-                .execute(&mut tx)
+                .bind(&(path.1).helth)
+                .bind(&(path.1).schema)
+                .bind(&(path.1).reply_to)
+                .bind(&(path.1).state)
+                .bind(&(path.1).error)
+                .execute(&mut *tx)
                 .await
                 {
                     Ok(_) => ids.push(service_name.clone()),
@@ -517,7 +944,7 @@ impl RouteCollection {
                     command.description,
                     command.exec_mode
                 )
-                .execute(&mut tx)
+                .execute(&mut *tx)
                 .await
                 {
                     Ok(_) => {},
@@ -527,18 +954,18 @@ impl RouteCollection {
                         return Ok((errors::ErrorCode::DatabaseError, None));
                     }
                 };
-                #[cfg(feature = "mysql")]
+                #[cfg(any(feature = "mysql", feature = "sqlite"))]
                 match sqlx::query(r#"INSERT INTO webapi.service_command ( service_name, object_type, reply_type, description, exec_mode ) 
                     VALUES ( ?, ?, ?, ?, ? )"#)
                     .bind(&service_name)
-                    .bind(command.object_type)
+                    .bind(&command.object_type)
                     .bind(command.reply_type)
                     .bind(command.description)
                     .bind(command.exec_mode)
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                 {
-                    Ok(_) => ids.push(service_name.clone()),
+                    Ok(_) => {}
                     Err(e) => {
                         tx.rollback().await.unwrap();
                         error!("add_routes db command insert: {}", e);
@@ -555,7 +982,7 @@ impl RouteCollection {
                         path.0,
                         path.1
                     )
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -565,14 +992,14 @@ impl RouteCollection {
                             return Ok((errors::ErrorCode::DatabaseError, None));
                         }
                     };
-                    #[cfg(feature = "mysql")]
-                    match sqlx::query(r#"INSERT INTO webapi.service_command_path ( 'service_name', object_type, proto, 'to' ) 
+                    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+                    match sqlx::query(r#"INSERT INTO webapi.service_command_path ( `service_name`, object_type, proto, `to` ) 
                         VALUES ( ?, ?, ?, ? )"#)
                         .bind(&service_name)
-                        .bind(command.object_type)
+                        .bind(&command.object_type)
                         .bind(path.0)
                         .bind(path.1)
-                        .execute(&mut tx)
+                        .execute(&mut *tx)
                         .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -594,7 +1021,7 @@ impl RouteCollection {
                         state.0,
                         state.1
                     )
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -604,14 +1031,14 @@ impl RouteCollection {
                             return Ok((errors::ErrorCode::DatabaseError, None));
                         }
                     };
-                        #[cfg(feature = "mysql")]
-                    match sqlx::query(r#"INSERT INTO webapi.service_command_state ( 'service_name', object_type, 'state', 'description' ) 
+                        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+                    match sqlx::query(r#"INSERT INTO webapi.service_command_state ( `service_name`, object_type, `state`, `description` ) 
                         VALUES ( ?, ?, ?, ? )"#)
                         .bind(&service_name)
-                        .bind(command.object_type)
+                        .bind(&command.object_type)
                         .bind(state.0)
                         .bind(state.1)
-                        .execute(&mut tx)
+                        .execute(&mut *tx)
                         .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -633,7 +1060,7 @@ impl RouteCollection {
                     event.object_type,
                     event.description
                 )
-                .execute(&mut tx)
+                .execute(&mut *tx)
                 .await
                 {
                     Ok(_) => {}
@@ -643,18 +1070,18 @@ impl RouteCollection {
                         return Ok((errors::ErrorCode::DatabaseError, None));
                     }
                 };
-                #[cfg(feature = "mysql")]
+                #[cfg(any(feature = "mysql", feature = "sqlite"))]
                 match sqlx::query(
                     r#"INSERT INTO webapi.service_event ( service_name, object_type, description ) 
                     VALUES ( ?, ?, ? )"#,
                 )
-                .bind(route.service_name)
+                .bind(&service_name)
                 .bind(event.object_type)
                 .bind(event.description)
-                .execute(&mut tx)
+                .execute(&mut *tx)
                 .await
                 {
-                    Ok(_) => ids.push(route.service_name),
+                    Ok(_) => {}
                     Err(e) => {
                         tx.rollback().await.unwrap();
                         error!("add_routes db event insert: {}", e);
@@ -669,7 +1096,7 @@ impl RouteCollection {
                     service_name.clone(),
                     subscription.object_type
                 )
-                .execute(&mut tx)
+                .execute(&mut *tx)
                 .await
                 {
                     Ok(_) => {},
@@ -679,14 +1106,14 @@ impl RouteCollection {
                         return Ok((errors::ErrorCode::DatabaseError, None));
                     }
                 };
-                #[cfg(feature = "mysql")]
+                #[cfg(any(feature = "mysql", feature = "sqlite"))]
                 match sqlx::query(r#"INSERT INTO webapi.service_subscription ( service_name, object_type ) VALUES ( ?, ? )"#)
-                    .bind(route.service_name)
-                    .bind(subscription.object_type)
-                    .execute(&mut tx)
+                    .bind(&service_name)
+                    .bind(&subscription.object_type)
+                    .execute(&mut *tx)
                     .await
                 {
-                    Ok(_) => ids.push(route.service_name),
+                    Ok(_) => {}
                     Err(e) => {
                         tx.rollback().await.unwrap();
                         error!("add_routes db subscription insert: {}", e);
@@ -703,7 +1130,7 @@ impl RouteCollection {
                         path.0,
                         path.1
                     )
-                    .execute(&mut tx)
+                    .execute(&mut *tx)
                     .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -713,14 +1140,14 @@ impl RouteCollection {
                             return Ok((errors::ErrorCode::DatabaseError, None));
                         }
                     };
-                    #[cfg(feature = "mysql")]
-                    match sqlx::query(r#"INSERT INTO webapi.service_subscription_path ( 'service_name', object_type, proto, 'to' ) 
+                    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+                    match sqlx::query(r#"INSERT INTO webapi.service_subscription_path ( `service_name`, object_type, proto, `to` ) 
                         VALUES ( ?, ?, ?, ? )"#)
                         .bind(&service_name)
-                        .bind(subscription.object_type)
+                        .bind(&subscription.object_type)
                         .bind(path.0)
                         .bind(path.1)
-                        .execute(&mut tx)
+                        .execute(&mut *tx)
                         .await
                     {
                         Ok(_) => ids.push(service_name.clone()),
@@ -743,90 +1170,146 @@ impl RouteCollection {
         Ok((errors::ErrorCode::ReplyOk, Some(ids)))
     }
 
-    pub async fn remove(&self, services: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+    async fn remove(
+        &self,
+        services: Vec<String>,
+        events: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         let mut tx = pool.begin().await?;
-        match sqlx::query(&self.exp_helper.get_delete_str_exp(
-            "webapi.service_subscription_path",
-            "service_name",
-            &services,
-        ))
-        .execute(&mut tx)
-        .await
-        {
-            Ok(_) => {
-                match sqlx::query(&self.exp_helper.get_delete_str_exp(
-                    "webapi.service_subscription",
+        match super::publisher::add_outbox_events(&mut tx, &events).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("remove_routes db outbox insert: {}", e);
+                tx.rollback().await?;
+                return Ok(errors::ErrorCode::DatabaseError);
+            }
+        };
+        match self
+            .exp_helper
+            .bind_str_ids(
+                sqlx::query(&self.exp_helper.get_delete_str_exp(
+                    "webapi.service_subscription_path",
                     "service_name",
                     &services,
-                ))
-                .execute(&mut tx)
-                .await
-                {
-                    Ok(_) => {
-                        match sqlx::query(&self.exp_helper.get_delete_str_exp(
-                            "webapi.service_event",
+                )),
+                &services,
+            )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => {
+                match self
+                    .exp_helper
+                    .bind_str_ids(
+                        sqlx::query(&self.exp_helper.get_delete_str_exp(
+                            "webapi.service_subscription",
                             "service_name",
                             &services,
-                        ))
-                        .execute(&mut tx)
-                        .await
-                        {
-                            Ok(_) => {
-                                match sqlx::query(&self.exp_helper.get_delete_str_exp(
-                                    "webapi.service_command_state",
+                        )),
+                        &services,
+                    )
+                    .execute(&mut *tx)
+                    .await
+                {
+                    Ok(_) => {
+                        match self
+                            .exp_helper
+                            .bind_str_ids(
+                                sqlx::query(&self.exp_helper.get_delete_str_exp(
+                                    "webapi.service_event",
                                     "service_name",
                                     &services,
-                                ))
-                                .execute(&mut tx)
-                                .await
-                                {
-                                    Ok(_) => {
-                                        match sqlx::query(&self.exp_helper.get_delete_str_exp(
-                                            "webapi.service_command_path",
+                                )),
+                                &services,
+                            )
+                            .execute(&mut *tx)
+                            .await
+                        {
+                            Ok(_) => {
+                                match self
+                                    .exp_helper
+                                    .bind_str_ids(
+                                        sqlx::query(&self.exp_helper.get_delete_str_exp(
+                                            "webapi.service_command_state",
                                             "service_name",
                                             &services,
-                                        ))
-                                        .execute(&mut tx)
-                                        .await
+                                        )),
+                                        &services,
+                                    )
+                                    .execute(&mut *tx)
+                                    .await
+                                {
+                                    Ok(_) => {
+                                        match self
+                                            .exp_helper
+                                            .bind_str_ids(
+                                                sqlx::query(&self.exp_helper.get_delete_str_exp(
+                                                    "webapi.service_command_path",
+                                                    "service_name",
+                                                    &services,
+                                                )),
+                                                &services,
+                                            )
+                                            .execute(&mut *tx)
+                                            .await
                                         {
                                             Ok(_) => {
-                                                match sqlx::query(
-                                                    &self.exp_helper.get_delete_str_exp(
-                                                        "webapi.service_command",
-                                                        "service_name",
-                                                        &services,
-                                                    ),
-                                                )
-                                                .execute(&mut tx)
-                                                .await
-                                                {
-                                                    Ok(_) => {
-                                                        match sqlx::query(
+                                                match self
+                                                    .exp_helper
+                                                    .bind_str_ids(
+                                                        sqlx::query(
                                                             &self.exp_helper.get_delete_str_exp(
-                                                                "webapi.service_path",
+                                                                "webapi.service_command",
                                                                 "service_name",
                                                                 &services,
                                                             ),
-                                                        )
-                                                        .execute(&mut tx)
-                                                        .await
-                                                        {
-                                                            Ok(_) => {
-                                                                match sqlx::query(
+                                                        ),
+                                                        &services,
+                                                    )
+                                                    .execute(&mut *tx)
+                                                    .await
+                                                {
+                                                    Ok(_) => {
+                                                        match self
+                                                            .exp_helper
+                                                            .bind_str_ids(
+                                                                sqlx::query(
                                                                     &self
                                                                         .exp_helper
                                                                         .get_delete_str_exp(
-                                                                            "webapi.service",
-                                                                            "name",
+                                                                            "webapi.service_path",
+                                                                            "service_name",
                                                                             &services,
                                                                         ),
-                                                                )
-                                                                .execute(&mut tx)
-                                                                .await
+                                                                ),
+                                                                &services,
+                                                            )
+                                                            .execute(&mut *tx)
+                                                            .await
+                                                        {
+                                                            Ok(_) => {
+                                                                match self
+                                                                    .exp_helper
+                                                                    .bind_str_ids(
+                                                                        sqlx::query(
+                                                                            &self
+                                                                                .exp_helper
+                                                                                .get_delete_str_exp(
+                                                                                "webapi.service",
+                                                                                "name",
+                                                                                &services,
+                                                                            ),
+                                                                        ),
+                                                                        &services,
+                                                                    )
+                                                                    .execute(&mut *tx)
+                                                                    .await
                                                                 {
                                                                     Ok(ret) => {
                                                                         if services.len()
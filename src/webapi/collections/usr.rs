@@ -1,8 +1,12 @@
-use super::super::{connectors, entities::usr, providers};
+use super::super::{connectors, entities::usr, errors, providers, traits};
+use async_trait::async_trait;
 #[cfg(feature = "postgres")]
 use sqlx::postgres::PgPool;
 #[cfg(feature = "mysql")]
-use sqlx::MySqlPool;
+use sqlx::{MySqlPool, Row};
+#[cfg(feature = "sqlite")]
+use sqlx::{Row, SqlitePool};
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 pub struct UsrCollection {
@@ -20,25 +24,238 @@ impl UsrCollection {
             exp_helper: &helper,
         }
     }
+}
 
-    pub async fn get(&self, ids: Option<Vec<i32>>) -> connectors::Result<Vec<usr::Usr>> {
+#[async_trait]
+impl traits::UsrStorage for UsrCollection {
+    async fn get(&self, ids: Option<Vec<i32>>) -> connectors::Result<Vec<usr::Usr>> {
         #[cfg(feature = "postgres")]
         let pool: &PgPool = &self.data_provider.pool;
         #[cfg(feature = "mysql")]
         let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
         if ids.is_none() {
-            Ok(sqlx::query_as!(
+            #[cfg(feature = "postgres")]
+            let items = sqlx::query_as!(
                 usr::Usr,
-                r#"SELECT id,usr_name,usr_password FROM webapi.usr"#
+                r#"SELECT id,usr_name,usr_password,is_disabled,is_admin FROM webapi.usr"#
             )
             .fetch_all(pool)
-            .await?)
+            .await?;
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            let items: Vec<usr::Usr> =
+                sqlx::query_as(r#"SELECT id,usr_name,usr_password,is_disabled,is_admin FROM webapi.usr"#)
+                    .fetch_all(pool)
+                    .await?;
+            Ok(items)
         } else {
-            let query = self
+            let ids = ids.unwrap();
+            let query = self.exp_helper.get_select_int_exp("webapi.usr", "id", &ids);
+            let items: Vec<usr::Usr> = self
                 .exp_helper
-                .get_select_int_exp("webapi.usr", "id", &ids.unwrap());
-            let items: Vec<usr::Usr> = sqlx::query_as(&query).fetch_all(pool).await?;
+                .bind_int_ids_as(sqlx::query_as(&query), &ids)
+                .fetch_all(pool)
+                .await?;
             Ok(items)
         }
     }
+
+    async fn get_by_name(&self, usr_name: &str) -> connectors::Result<Option<usr::Usr>> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        #[cfg(feature = "postgres")]
+        let item = sqlx::query_as!(
+            usr::Usr,
+            r#"SELECT id,usr_name,usr_password,is_disabled,is_admin FROM webapi.usr WHERE usr_name = $1"#,
+            usr_name
+        )
+        .fetch_optional(pool)
+        .await?;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let item: Option<usr::Usr> = sqlx::query_as(
+            r#"SELECT id,usr_name,usr_password,is_disabled,is_admin FROM webapi.usr WHERE usr_name = ?"#,
+        )
+        .bind(usr_name)
+        .fetch_optional(pool)
+        .await?;
+        Ok(item)
+    }
+
+    async fn add(
+        &self,
+        items: Vec<usr::Usr>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        let mut ids = Vec::<i32>::new();
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        for item in items {
+            #[cfg(feature = "postgres")]
+            match sqlx::query!(
+                r#"INSERT INTO webapi.usr ( usr_name, usr_password, is_disabled, is_admin ) VALUES ( $1, $2, $3, $4 ) RETURNING id"#,
+                item.usr_name,
+                item.usr_password,
+                item.is_disabled,
+                item.is_admin
+            )
+            .fetch_one(&mut *tx)
+            .await
+            {
+                Ok(rec) => ids.push(rec.id),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_usrs db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            match sqlx::query(
+                r#"INSERT INTO webapi.usr ( usr_name, usr_password, is_disabled, is_admin ) VALUES ( ?, ?, ?, ? )"#,
+            )
+            .bind(item.usr_name)
+            .bind(item.usr_password)
+            .bind(item.is_disabled)
+            .bind(item.is_admin)
+            .execute(&mut *tx)
+            .await
+            {
+                Ok(_) => {}
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_usrs db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(feature = "mysql")]
+            match sqlx::query(r#"SELECT LAST_INSERT_ID() AS id;"#)
+                .fetch_one(&mut *tx)
+                .await
+            {
+                Ok(rec) => ids.push(rec.get::<u64, _>(0) as i32),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_usrs db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+            #[cfg(feature = "sqlite")]
+            match sqlx::query(r#"SELECT last_insert_rowid() AS id;"#)
+                .fetch_one(&mut *tx)
+                .await
+            {
+                Ok(rec) => ids.push(rec.get::<i64, _>(0) as i32),
+                Err(e) => {
+                    tx.rollback().await.unwrap();
+                    error!("add_usrs db insert: {}", e);
+                    return Ok((errors::ErrorCode::DatabaseError, None));
+                }
+            };
+        }
+        match tx.commit().await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("add_usrs db commit: {}", e);
+                return Ok((errors::ErrorCode::DatabaseError, None));
+            }
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    async fn change_password(
+        &self,
+        usr_name: &str,
+        usr_password: &str,
+    ) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        #[cfg(feature = "postgres")]
+        let result = sqlx::query!(
+            r#"UPDATE webapi.usr SET usr_password = $1 WHERE usr_name = $2"#,
+            usr_password,
+            usr_name
+        )
+        .execute(pool)
+        .await;
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        let result = sqlx::query(r#"UPDATE webapi.usr SET usr_password = ? WHERE usr_name = ?"#)
+            .bind(usr_password)
+            .bind(usr_name)
+            .execute(pool)
+            .await;
+        match result {
+            Ok(ret) if ret.rows_affected() == 1 => Ok(errors::ErrorCode::ReplyOk),
+            Ok(_) => Ok(errors::ErrorCode::NotFoundError),
+            Err(e) => {
+                error!("change_usr_password db update: {}", e);
+                Ok(errors::ErrorCode::DatabaseError)
+            }
+        }
+    }
+
+    async fn disable(&self, usr_names: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        #[cfg(feature = "postgres")]
+        let pool: &PgPool = &self.data_provider.pool;
+        #[cfg(feature = "mysql")]
+        let pool: &MySqlPool = &self.data_provider.pool;
+        #[cfg(feature = "sqlite")]
+        let pool: &SqlitePool = &self.data_provider.pool;
+        let mut tx = pool.begin().await?;
+        let mut count: u64 = 0;
+        for usr_name in &usr_names {
+            #[cfg(feature = "postgres")]
+            match sqlx::query!(
+                r#"UPDATE webapi.usr SET is_disabled = true WHERE usr_name = $1"#,
+                usr_name
+            )
+            .execute(&mut *tx)
+            .await
+            {
+                Ok(ret) => count += ret.rows_affected(),
+                Err(e) => {
+                    error!("disable_usrs db update: {}", e);
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::DatabaseError);
+                }
+            };
+            #[cfg(any(feature = "mysql", feature = "sqlite"))]
+            match sqlx::query(r#"UPDATE webapi.usr SET is_disabled = true WHERE usr_name = ?"#)
+                .bind(usr_name)
+                .execute(&mut *tx)
+                .await
+            {
+                Ok(ret) => count += ret.rows_affected(),
+                Err(e) => {
+                    error!("disable_usrs db update: {}", e);
+                    tx.rollback().await?;
+                    return Ok(errors::ErrorCode::DatabaseError);
+                }
+            };
+        }
+        if usr_names.len() == usize::try_from(count).unwrap() {
+            match tx.commit().await {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("disable_usrs db commit: {}", e);
+                    return Ok(errors::ErrorCode::DatabaseError);
+                }
+            }
+            Ok(errors::ErrorCode::ReplyOk)
+        } else {
+            tx.rollback().await?;
+            Ok(errors::ErrorCode::NotFoundError)
+        }
+    }
 }
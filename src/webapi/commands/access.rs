@@ -0,0 +1,8 @@
+use super::super::routes::path;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// reload server users from database without waiting for interval
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ACCESS_RELOAD)]
+pub struct ReloadAccess {}
@@ -1,18 +1,15 @@
-use super::super::{entities::car, traits};
+use super::super::entities::car;
+use super::common;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 // use std::collections::HashMap;
 
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
 pub struct GetCar {
     pub filter: Option<String>,
     pub ids: Option<Vec<i32>>,
-}
-
-impl traits::ObjectType for GetCar {
-    fn get_type_name() -> &'static str {
-        "GetCar"
-    }
+    #[serde(flatten, default)]
+    pub page: common::Page,
 }
 
 // impl traits::CommandState for GetCar {
@@ -21,46 +18,22 @@ impl traits::ObjectType for GetCar {
 //     }
 // }
 
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
 pub struct AddCar {
     pub items: Vec<car::Car>,
 }
 
-impl traits::ObjectType for AddCar {
-    fn get_type_name() -> &'static str {
-        "AddCar"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
 pub struct ChangeCar {
     pub items: Vec<car::Car>,
 }
 
-impl traits::ObjectType for ChangeCar {
-    fn get_type_name() -> &'static str {
-        "ChangeCar"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
 pub struct RemoveCar {
     pub ids: Vec<i32>,
 }
 
-impl traits::ObjectType for RemoveCar {
-    fn get_type_name() -> &'static str {
-        "RemoveCar"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
 pub struct ReserveCar {
     pub services: Vec<i32>,
 }
-
-impl traits::ObjectType for ReserveCar {
-    fn get_type_name() -> &'static str {
-        "ReserveCar"
-    }
-}
@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// paging params of Get* commands, next page is read by next_offset of previous reply
+#[derive(Deserialize, Serialize, Clone, Default, JsonSchema)]
+pub struct Page {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_by: Option<String>, //comma separated fields with optional asc/desc
+}
+
+impl Page {
+    pub fn is_none(&self) -> bool {
+        self.limit.is_none() && self.offset.is_none() && self.order_by.is_none()
+    }
+}
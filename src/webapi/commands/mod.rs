@@ -1,2 +1,6 @@
+pub mod access;
 pub mod car;
+pub mod common;
+pub mod publisher;
 pub mod route;
+pub mod usr;
@@ -0,0 +1,19 @@
+use super::super::routes::path;
+use super::common;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_DEAD_LETTER_GET)]
+pub struct GetDeadLetterEvent {
+    pub filter: Option<String>,
+    pub ids: Option<Vec<i32>>,
+    #[serde(flatten, default)]
+    pub page: common::Page,
+}
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_DEAD_LETTER_REPLAY)]
+pub struct ReplayDeadLetterEvent {
+    pub ids: Vec<i32>,
+}
@@ -1,85 +1,62 @@
-use super::super::{entities::route, traits};
+use super::super::entities::route;
+use super::super::routes::path;
+use super::common;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_ROUTE_GET)]
 pub struct GetRoute {
     pub filter: Option<String>,
     pub services: Option<Vec<String>>,
+    #[serde(flatten, default)]
+    pub page: common::Page,
 }
 
-impl traits::ObjectType for GetRoute {
-    fn get_type_name() -> &'static str {
-        "GetRoute"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_ROUTE_ADD)]
 pub struct AddRoute {
     pub items: Vec<route::Route>,
 }
 
-impl traits::ObjectType for AddRoute {
-    fn get_type_name() -> &'static str {
-        "AddRoute"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_ROUTE_REMOVE)]
 pub struct RemoveRoute {
     pub services: Vec<String>,
 }
 
-impl traits::ObjectType for RemoveRoute {
-    fn get_type_name() -> &'static str {
-        "RemoveRoute"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_COMMAND_GET)]
 pub struct GetServiceCommand {
     pub filter: Option<String>,
     pub services: Option<Vec<String>>,
+    #[serde(flatten, default)]
+    pub page: common::Page,
 }
 
-impl traits::ObjectType for GetServiceCommand {
-    fn get_type_name() -> &'static str {
-        "GetServiceCommand"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_EVENT_GET)]
 pub struct GetServiceEvent {
     pub filter: Option<String>,
     pub services: Option<Vec<String>>,
+    #[serde(flatten, default)]
+    pub page: common::Page,
 }
 
-impl traits::ObjectType for GetServiceEvent {
-    fn get_type_name() -> &'static str {
-        "GetServiceEvent"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_SUBSCIBTION_GET)]
 pub struct GetServiceSubscription {
     pub filter: Option<String>,
     pub services: Option<Vec<String>>,
+    #[serde(flatten, default)]
+    pub page: common::Page,
 }
 
-impl traits::ObjectType for GetServiceSubscription {
-    fn get_type_name() -> &'static str {
-        "GetServiceSubscription"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_SERVICE_GET)]
 pub struct GetService {
     pub filter: Option<String>,
     pub names: Option<Vec<String>>,
-}
-
-impl traits::ObjectType for GetService {
-    fn get_type_name() -> &'static str {
-        "GetService"
-    }
+    #[serde(flatten, default)]
+    pub page: common::Page,
 }
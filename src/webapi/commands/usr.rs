@@ -0,0 +1,38 @@
+use super::super::routes::path;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::USR_SIGHN_IN)]
+pub struct SignIn {
+    #[schemars(length(min = 1))]
+    pub usr_name: String,
+    #[schemars(length(min = 1))]
+    pub usr_password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::USR_SIGHN_UP)]
+pub struct SignUp {
+    #[schemars(length(min = 1))]
+    pub usr_name: String,
+    #[schemars(length(min = 1))]
+    pub usr_password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::USR_CHANGE_PASSWORD)]
+pub struct ChangeUsrPassword {
+    #[schemars(length(min = 1))]
+    pub usr_name: String,
+    #[schemars(length(min = 1))]
+    pub usr_password: String, //current password
+    #[schemars(length(min = 1))]
+    pub new_usr_password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::USR_DISABLE)]
+pub struct DisableUsr {
+    pub usr_names: Vec<String>,
+}
@@ -1,161 +1,462 @@
-#[cfg(not(test))]
+#[cfg(any(not(test), not(feature = "postgres")))]
 use super::collections;
-use super::entities;
-#[cfg(test)]
-use super::tests::fakes;
-use std::collections::HashMap;
-#[cfg(not(test))]
+#[cfg(any(not(test), not(feature = "postgres")))]
+use super::filters;
+use super::{errors, memory, providers, traits};
+#[cfg(any(not(test), not(feature = "postgres")))]
+use sqlx::Row;
 use std::sync::Arc;
 
 pub type Result<T, E = Box<dyn std::error::Error>> = std::result::Result<T, E>;
 
-#[cfg(not(test))]
+// backend is selected at build time, exactly one database feature is enabled
+#[cfg(any(
+    all(feature = "postgres", feature = "mysql"),
+    all(feature = "postgres", feature = "sqlite"),
+    all(feature = "mysql", feature = "sqlite")
+))]
+compile_error!("enable only one of features postgres, mysql, sqlite, e.g. --no-default-features --features mysql,amqp");
+#[cfg(not(any(feature = "postgres", feature = "mysql", feature = "sqlite")))]
+compile_error!("enable one of features postgres, mysql, sqlite");
+
+#[cfg(all(not(test), feature = "postgres"))]
+pub type Db = sqlx::Postgres;
+#[cfg(all(not(test), feature = "postgres"))]
+type DbArguments<'q> = sqlx::postgres::PgArguments;
+#[cfg(feature = "mysql")]
+pub type Db = sqlx::MySql;
+#[cfg(feature = "mysql")]
+type DbArguments<'q> = sqlx::mysql::MySqlArguments;
+#[cfg(feature = "sqlite")]
+pub type Db = sqlx::Sqlite;
+#[cfg(feature = "sqlite")]
+type DbArguments<'q> = sqlx::sqlite::SqliteArguments<'q>;
+
+#[cfg(any(not(test), not(feature = "postgres")))]
+pub type DbPool = sqlx::Pool<Db>;
+#[cfg(any(not(test), not(feature = "postgres")))]
+pub type DbQuery<'q> = sqlx::query::Query<'q, Db, DbArguments<'q>>;
+#[cfg(any(not(test), not(feature = "postgres")))]
+pub type DbQueryAs<'q, O> = sqlx::query::QueryAs<'q, Db, O, DbArguments<'q>>;
+
+// builds id list expressions with placeholders, ids must be bound by bind_*_ids,
+// for postgres id list is always first parameter of query
+#[cfg(any(not(test), not(feature = "postgres")))]
 pub struct ExpHelper;
 
-#[cfg(not(test))]
+#[cfg(any(not(test), not(feature = "postgres")))]
 impl ExpHelper {
     fn new() -> &'static ExpHelper {
         &ExpHelper {}
     }
 
-    pub fn get_int_ids_as_exp(&self, ids: &Vec<i32>) -> String {
-        let mut result: String = String::with_capacity(100);
-        for item in ids {
-            if result.len() != 0 {
-                result.push(',');
-            }
-            result.push_str(&item.to_string());
-        }
-        result
+    #[cfg(feature = "postgres")]
+    pub fn get_ids_exp(&self, field: &str, _count: usize) -> String {
+        format!("{} = ANY($1)", field)
     }
 
-    pub fn get_str_ids_exp(&self, ids: &Vec<String>) -> String {
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    pub fn get_ids_exp(&self, field: &str, count: usize) -> String {
         let mut result: String = String::with_capacity(100);
-        for item in ids {
+        for _ in 0..count {
             if result.len() != 0 {
                 result.push(',');
             }
-            result.push_str(&format!("'{}'", item.to_string()));
+            result.push('?');
+        }
+        if count == 0 {
+            result.push_str("NULL");
         }
-        result
+        format!("{} IN ({})", field, result)
     }
 
     pub fn get_select_int_exp(&self, table: &str, field: &str, ids: &Vec<i32>) -> String {
         format!(
-            "SELECT * FROM {} WHERE {} IN ({})",
+            "SELECT * FROM {} WHERE {}",
             table,
-            field,
-            self.get_int_ids_as_exp(ids)
+            self.get_ids_exp(field, ids.len())
         )
     }
 
     pub fn get_delete_int_exp(&self, table: &str, field: &str, ids: &Vec<i32>) -> String {
         format!(
-            "DELETE FROM {} WHERE {} IN ({})",
+            "DELETE FROM {} WHERE {}",
             table,
-            field,
-            self.get_int_ids_as_exp(ids)
+            self.get_ids_exp(field, ids.len())
         )
     }
 
     pub fn get_select_str_exp(&self, table: &str, field: &str, ids: &Vec<String>) -> String {
         format!(
-            "SELECT * FROM {} WHERE {} IN ({})",
+            "SELECT * FROM {} WHERE {}",
             table,
-            field,
-            self.get_str_ids_exp(ids)
+            self.get_ids_exp(field, ids.len())
         )
     }
 
     pub fn get_delete_str_exp(&self, table: &str, field: &str, ids: &Vec<String>) -> String {
         format!(
-            "DELETE FROM {} WHERE {} IN ({})",
+            "DELETE FROM {} WHERE {}",
             table,
-            field,
-            self.get_str_ids_exp(ids)
+            self.get_ids_exp(field, ids.len())
         )
     }
+
+    fn get_where_exp(
+        &self,
+        field: &str,
+        ids_count: Option<usize>,
+        filter: &Option<filters::Filter>,
+    ) -> String {
+        let mut conditions = Vec::<String>::new();
+        if let Some(c) = ids_count {
+            conditions.push(self.get_ids_exp(field, c));
+        }
+        if let Some(f) = filter {
+            conditions.push(format!("({})", f.exp));
+        }
+        if conditions.len() == 0 {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        }
+    }
+
+    // ids count is none if select is not limited by ids, filter goes after ids
+    pub fn get_select_filter_exp(
+        &self,
+        table: &str,
+        field: &str,
+        ids_count: Option<usize>,
+        filter: &Option<filters::Filter>,
+        paging: &Option<filters::Paging>,
+    ) -> String {
+        let mut query = format!(
+            "SELECT * FROM {}{}",
+            table,
+            self.get_where_exp(field, ids_count, filter)
+        );
+        if let Some(p) = paging {
+            query.push_str(&format!(" ORDER BY {}", p.order_exp));
+            if let Some(l) = p.limit {
+                query.push_str(&format!(" LIMIT {} OFFSET {}", l, p.offset));
+            } else if p.offset != 0 {
+                query.push_str(&format!(" LIMIT {} OFFSET {}", i64::MAX, p.offset));
+            }
+        }
+        query
+    }
+
+    pub fn get_count_filter_exp(
+        &self,
+        table: &str,
+        field: &str,
+        ids_count: Option<usize>,
+        filter: &Option<filters::Filter>,
+    ) -> String {
+        format!(
+            "SELECT COUNT(*) FROM {}{}",
+            table,
+            self.get_where_exp(field, ids_count, filter)
+        )
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn bind_int_ids<'q>(&self, query: DbQuery<'q>, ids: &Vec<i32>) -> DbQuery<'q> {
+        query.bind(ids.clone())
+    }
+
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    pub fn bind_int_ids<'q>(&self, mut query: DbQuery<'q>, ids: &Vec<i32>) -> DbQuery<'q> {
+        for id in ids {
+            query = query.bind(*id);
+        }
+        query
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn bind_str_ids<'q>(&self, query: DbQuery<'q>, ids: &Vec<String>) -> DbQuery<'q> {
+        query.bind(ids.clone())
+    }
+
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    pub fn bind_str_ids<'q>(&self, mut query: DbQuery<'q>, ids: &Vec<String>) -> DbQuery<'q> {
+        for id in ids {
+            query = query.bind(id.clone());
+        }
+        query
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn bind_int_ids_as<'q, O>(
+        &self,
+        query: DbQueryAs<'q, O>,
+        ids: &Vec<i32>,
+    ) -> DbQueryAs<'q, O> {
+        query.bind(ids.clone())
+    }
+
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    pub fn bind_int_ids_as<'q, O>(
+        &self,
+        mut query: DbQueryAs<'q, O>,
+        ids: &Vec<i32>,
+    ) -> DbQueryAs<'q, O> {
+        for id in ids {
+            query = query.bind(*id);
+        }
+        query
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn bind_str_ids_as<'q, O>(
+        &self,
+        query: DbQueryAs<'q, O>,
+        ids: &Vec<String>,
+    ) -> DbQueryAs<'q, O> {
+        query.bind(ids.clone())
+    }
+
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    pub fn bind_str_ids_as<'q, O>(
+        &self,
+        mut query: DbQueryAs<'q, O>,
+        ids: &Vec<String>,
+    ) -> DbQueryAs<'q, O> {
+        for id in ids {
+            query = query.bind(id.clone());
+        }
+        query
+    }
+
+    // count rows only if page requested, else all rows are already fetched
+    pub async fn get_page_info<'q>(
+        &self,
+        pool: &DbPool,
+        count_query: DbQuery<'q>,
+        paging: &Option<filters::Paging>,
+        count: usize,
+    ) -> Result<filters::PageInfo> {
+        let total: i64 = if paging.is_some() {
+            count_query.fetch_one(pool).await?.get(0)
+        } else {
+            count as i64
+        };
+        Ok(filters::Paging::get_page_info(paging, count, total))
+    }
+
+    pub fn bind_filter<'q>(
+        &self,
+        mut query: DbQuery<'q>,
+        filter: &Option<filters::Filter>,
+    ) -> DbQuery<'q> {
+        if let Some(f) = filter {
+            for param in &f.params {
+                query = match param {
+                    filters::FilterValue::Int(v) => query.bind(*v),
+                    filters::FilterValue::Str(v) => query.bind(v.clone()),
+                    filters::FilterValue::DateTime(v) => query.bind(*v),
+                };
+            }
+        }
+        query
+    }
+
+    pub fn bind_filter_as<'q, O>(
+        &self,
+        mut query: DbQueryAs<'q, O>,
+        filter: &Option<filters::Filter>,
+    ) -> DbQueryAs<'q, O> {
+        if let Some(f) = filter {
+            for param in &f.params {
+                query = match param {
+                    filters::FilterValue::Int(v) => query.bind(*v),
+                    filters::FilterValue::Str(v) => query.bind(v.clone()),
+                    filters::FilterValue::DateTime(v) => query.bind(*v),
+                };
+            }
+        }
+        query
+    }
 }
 
 pub struct DataConnector {
-    pub error: HashMap<String, String>,
-    #[cfg(not(test))]
-    pub usr: collections::usr::UsrCollection,
-    #[cfg(test)]
-    pub usr: fakes::usr::UsrCollection,
-    #[cfg(not(test))]
-    pub car: collections::car::CarCollection,
-    #[cfg(test)]
-    pub car: fakes::car::CarCollection,
-    #[cfg(not(test))]
-    pub route: collections::route::RouteCollection,
-    #[cfg(test)]
-    pub route: fakes::route::RouteCollection,
-    #[cfg(not(test))]
-    pub sended_async_command: collections::executor::SendedAsyncCommandCollection,
-    #[cfg(test)]
-    pub sended_async_command: fakes::executor::SendedAsyncCommandCollection,
-    #[cfg(not(test))]
-    pub received_async_command: collections::executor::ReceivedAsyncCommandCollection,
-    #[cfg(test)]
-    pub received_async_command: fakes::executor::ReceivedAsyncCommandCollection,
+    pub error: errors::ErrorCatalogue,
+    pub usr: Box<dyn traits::UsrStorage>,
+    pub car: Box<dyn traits::CarStorage>,
+    pub route: Box<dyn traits::RouteStorage>,
+    pub sended_async_command: Box<dyn traits::SendedAsyncCommandStorage>,
+    pub received_async_command: Box<dyn traits::ReceivedAsyncCommandStorage>,
+    pub event_outbox: Box<dyn traits::EventOutboxStorage>,
+    pub undelivered_event: Box<dyn traits::UndeliveredEventStorage>,
+    pub dead_letter_event: Box<dyn traits::DeadLetterEventStorage>,
 }
 
 impl DataConnector {
-    pub async fn new(
-        _error: Option<HashMap<String, String>>,
-        _connection_string: &str,
-    ) -> Result<DataConnector> {
-        #[cfg(not(test))]
-        let _exp_helper: &'static ExpHelper = &ExpHelper::new();
-        #[cfg(all(not(test), feature = "postgres"))]
-        let dp = super::providers::SqlDbProvider::new(_connection_string).await?;
-        #[cfg(all(not(test), feature = "mysql"))]
-        let dp = super::providers::SqlDbProvider::new(_connection_string).await?;
-        let mut error = HashMap::<String, String>::new();
-        if _error.is_some() {
-            error.extend(_error.unwrap());
-        }
-        #[cfg(not(test))]
-        error.extend(DataConnector::_errors_as_hashmap(dp.get_errors().await?));
-        #[cfg(not(test))]
-        let _dp_arc = Arc::new(dp);
-        Ok(DataConnector {
+    // storages are chosen by caller, tests inject memory collections or own fakes
+    pub fn from_storages(
+        error: errors::ErrorCatalogue,
+        usr: Box<dyn traits::UsrStorage>,
+        car: Box<dyn traits::CarStorage>,
+        route: Box<dyn traits::RouteStorage>,
+        sended_async_command: Box<dyn traits::SendedAsyncCommandStorage>,
+        received_async_command: Box<dyn traits::ReceivedAsyncCommandStorage>,
+        event_outbox: Box<dyn traits::EventOutboxStorage>,
+        undelivered_event: Box<dyn traits::UndeliveredEventStorage>,
+        dead_letter_event: Box<dyn traits::DeadLetterEventStorage>,
+    ) -> DataConnector {
+        DataConnector {
             error: error,
-            #[cfg(not(test))]
-            usr: collections::usr::UsrCollection::new(_dp_arc.clone(), &_exp_helper),
-            #[cfg(test)]
-            usr: fakes::usr::UsrCollection::new(),
-            #[cfg(not(test))]
-            car: collections::car::CarCollection::new(_dp_arc.clone(), &_exp_helper),
-            #[cfg(test)]
-            car: fakes::car::CarCollection::new(),
-            #[cfg(not(test))]
-            route: collections::route::RouteCollection::new(_dp_arc.clone(), &_exp_helper),
-            #[cfg(test)]
-            route: fakes::route::RouteCollection::new(),
-            #[cfg(not(test))]
-            sended_async_command: collections::executor::SendedAsyncCommandCollection::new(
-                _dp_arc.clone(),
-                &_exp_helper,
-            ),
-            #[cfg(test)]
-            sended_async_command: fakes::executor::SendedAsyncCommandCollection::new(),
-            #[cfg(not(test))]
-            received_async_command: collections::executor::ReceivedAsyncCommandCollection::new(
-                _dp_arc.clone(),
-                &_exp_helper,
-            ),
-            #[cfg(test)]
-            received_async_command: fakes::executor::ReceivedAsyncCommandCollection::new(),
-        })
-    }
-
-    fn _errors_as_hashmap(items: Vec<entities::error::Error>) -> HashMap<String, String> {
-        let mut error = HashMap::<String, String>::new();
-        for item in items {
-            error.insert(item.error_code, item.error_name);
+            usr: usr,
+            car: car,
+            route: route,
+            sended_async_command: sended_async_command,
+            received_async_command: received_async_command,
+            event_outbox: event_outbox,
+            undelivered_event: undelivered_event,
+            dead_letter_event: dead_letter_event,
         }
-        error
+    }
+
+    #[cfg(any(not(test), not(feature = "postgres")))]
+    pub async fn new(
+        error: errors::ErrorCatalogue,
+        connection_string: &str,
+        dev_seed: bool,
+    ) -> Result<DataConnector> {
+        let exp_helper: &'static ExpHelper = &ExpHelper::new();
+        let dp = providers::SqlDbProvider::new(connection_string, dev_seed).await?;
+        let mut error = error;
+        error.extend_missing(dp.get_errors().await?);
+        let dp = Arc::new(dp);
+        Ok(DataConnector::from_storages(
+            error,
+            Box::new(collections::usr::UsrCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::car::CarCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::route::RouteCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::executor::SendedAsyncCommandCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::executor::ReceivedAsyncCommandCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::publisher::EventOutboxCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::publisher::UndeliveredEventCollection::new(
+                dp.clone(),
+                &exp_helper,
+            )),
+            Box::new(collections::publisher::DeadLetterEventCollection::new(
+                dp,
+                &exp_helper,
+            )),
+        ))
+    }
+
+    // storage without database, data lives while service is running
+    pub async fn new_memory(error: errors::ErrorCatalogue) -> Result<DataConnector> {
+        let dp = Arc::new(providers::MemoryDbProvider::new());
+        let mut error = error;
+        error.extend_missing(dp.get_errors().await?);
+        Ok(DataConnector::from_storages(
+            error,
+            Box::new(memory::usr::UsrCollection::new(dp.clone())),
+            Box::new(memory::car::CarCollection::new(dp.clone())),
+            Box::new(memory::route::RouteCollection::new(dp.clone())),
+            Box::new(memory::executor::SendedAsyncCommandCollection::new(
+                dp.clone(),
+            )),
+            Box::new(memory::executor::ReceivedAsyncCommandCollection::new(
+                dp.clone(),
+            )),
+            Box::new(memory::publisher::EventOutboxCollection::new(dp.clone())),
+            Box::new(memory::publisher::UndeliveredEventCollection::new(
+                dp.clone(),
+            )),
+            Box::new(memory::publisher::DeadLetterEventCollection::new(dp)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{fakes, get_fake_data_connector, get_memory_data_connector};
+    use super::super::{commands, entities::car};
+    use super::*;
+
+    #[tokio::test]
+    async fn memory_connector_is_seeded() {
+        let dc = get_memory_data_connector().await;
+        assert!(dc.usr.get_by_name("user1").await.unwrap().is_some());
+        assert_eq!(
+            dc.error
+                .get_name(&errors::ErrorCode::DatabaseError, Some("en")),
+            "Database error"
+        );
+    }
+
+    #[tokio::test]
+    async fn from_storages_uses_injected_storage() {
+        let dp = Arc::new(providers::MemoryDbProvider::new());
+        let dc = DataConnector::from_storages(
+            errors::ErrorCatalogue::new(errors::DEFAULT_LOCALE),
+            Box::new(fakes::usr::UsrCollection::new()),
+            Box::new(memory::car::CarCollection::new(dp)),
+            Box::new(fakes::route::RouteCollection::new()),
+            Box::new(fakes::executor::SendedAsyncCommandCollection::new()),
+            Box::new(fakes::executor::ReceivedAsyncCommandCollection::new()),
+            Box::new(fakes::publisher::EventOutboxCollection::new()),
+            Box::new(fakes::publisher::UndeliveredEventCollection::new()),
+            Box::new(fakes::publisher::DeadLetterEventCollection::new()),
+        );
+        let (result, ids) = dc
+            .car
+            .add(vec![car::Car {
+                id: None,
+                car_name: "audi".to_string(),
+            }])
+            .await
+            .unwrap();
+        assert_eq!(result, errors::ErrorCode::ReplyOk);
+        let (items, page_info) = dc
+            .car
+            .get(ids, None, &commands::common::Page::default())
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(page_info.total, 1);
+        // fake usr storage keeps nothing
+        assert!(dc.usr.get_by_name("user1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn fake_connector_keeps_nothing() {
+        let dc = get_fake_data_connector();
+        let (items, _) = dc
+            .car
+            .get(None, None, &commands::common::Page::default())
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        dc.car.remove(vec![items[0].id.unwrap()]).await.unwrap();
+        let (items, _) = dc
+            .car
+            .get(None, None, &commands::common::Page::default())
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
     }
 }
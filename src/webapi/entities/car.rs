@@ -1,7 +1,14 @@
+use super::super::filters::{self, FieldType};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+// filter and page fields, name of field in filter/column of sql view
+pub const CAR_FILTER: &[filters::FilterField] = &[
+    ("id", "id", FieldType::Int),
+    ("car_name", "car_name", FieldType::Str),
+];
+
 #[derive(Deserialize, Serialize, Clone, FromRow, JsonSchema)]
 pub struct Car {
     pub id: Option<i32>,
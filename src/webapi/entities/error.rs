@@ -1,7 +1,9 @@
 use serde::Serialize;
+use sqlx::FromRow;
 
-#[derive(Serialize)]
+#[derive(Serialize, FromRow)]
 pub struct Error {
     pub error_code: String,
+    pub locale: String,
     pub error_name: String,
 }
@@ -1,15 +1,37 @@
+use super::super::filters::{self, FieldType};
 use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use strum_macros::EnumString;
 
+// filter and page fields, name of field in filter/column of sql view
+pub const SENDED_ASYNC_COMMAND_ORDER: &[filters::FilterField] = &[
+    ("id", "id", FieldType::Str),
+    ("object_type", "object_type", FieldType::Str),
+    ("service_name", "service_name", FieldType::Str),
+    ("state", "state", FieldType::Str),
+    ("added_at", "added_at", FieldType::DateTime),
+    ("state_changed_at", "state_changed_at", FieldType::DateTime),
+];
+
+pub const RECEIVED_ASYNC_COMMAND_ORDER: &[filters::FilterField] = &[
+    ("id", "id", FieldType::Str),
+    ("object_type", "object_type", FieldType::Str),
+    ("service_name", "service_name", FieldType::Str),
+    ("state", "state", FieldType::Str),
+    ("proto", "proto", FieldType::Str),
+    ("added_at", "added_at", FieldType::DateTime),
+    ("state_changed_at", "state_changed_at", FieldType::DateTime),
+];
+
 #[derive(
     Deserialize, Serialize, Debug, PartialEq, Copy, Clone, EnumString, ToString, JsonSchema,
 )]
 pub enum CommandSystemState {
     Initial, //default for new async command
     Completed,
+    Expired, //reply not received in max_async_command_reply_wait_in_hours
 }
 
 #[derive(Deserialize, Serialize, Clone, FromRow, JsonSchema)]
@@ -22,6 +44,8 @@ pub struct SendedAsyncCommand {
     pub added_at: DateTime<Utc>,
     pub state_changed_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_body: Option<String>, //set when reply received
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub history: Option<Vec<SendedAsyncCommandHistory>>,
 }
 
@@ -43,10 +67,15 @@ pub struct ReceivedAsyncCommand {
     pub change_state_event: i32, //if = 1, must be send OnAsyncCommandStateChange for each state change, except system states
     pub reply_body: String,
     pub proto: String,
+    pub reply_to: String, //where to send reply, by proto
     pub added_at: DateTime<Utc>,
     pub state_changed_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub history: Option<Vec<ReceivedAsyncCommandHistory>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_usr_name: Option<String>, //caller, command is executed on its behalf
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>, //locale of caller for error names of reply
 }
 
 #[derive(Deserialize, Serialize, Clone, FromRow, JsonSchema)]
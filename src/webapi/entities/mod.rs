@@ -1,5 +1,6 @@
 pub mod error;
 pub mod executor;
+pub mod publisher;
 pub mod route;
 pub mod usr;
 
@@ -0,0 +1,40 @@
+use super::super::filters::{self, FieldType};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// filter and page fields, name of field in filter/column of sql view
+pub const DEAD_LETTER_EVENT_FILTER: &[filters::FilterField] = &[
+    ("id", "id", FieldType::Int),
+    ("correlation_id", "correlation_id", FieldType::Str),
+    ("object_type", "object_type", FieldType::Str),
+    ("service_name", "service_name", FieldType::Str),
+    ("proto", "proto", FieldType::Str),
+    ("attempt", "attempt", FieldType::Int),
+    ("added_at", "added_at", FieldType::DateTime),
+];
+
+#[derive(Deserialize, Serialize, Clone, FromRow, JsonSchema)]
+pub struct UndeliveredEvent {
+    pub id: Option<i32>,
+    pub correlation_id: String,
+    pub object_type: String,
+    pub service_name: String,
+    pub proto: String,
+    pub path: String, //subscriber path by proto
+    pub body: String,
+    pub attempt: i32,
+    pub last_error: String,
+    pub next_attempt_at: DateTime<Utc>,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, FromRow, JsonSchema)]
+pub struct OutboxEvent {
+    pub id: Option<i32>,
+    pub correlation_id: String,
+    pub object_type: String,
+    pub body: String,
+    pub added_at: DateTime<Utc>,
+}
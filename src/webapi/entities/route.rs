@@ -1,9 +1,45 @@
+use super::super::filters::{self, FieldType};
 use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use std::collections::HashMap;
 
+// filter and page fields, name of field in filter/column of sql view
+pub const ROUTE_FILTER: &[filters::FilterField] = &[
+    ("service_name", "name", FieldType::Str),
+    ("description", "description", FieldType::Str),
+    ("priority", "priority", FieldType::Int),
+];
+
+pub const SERVICE_FILTER: &[filters::FilterField] = &[
+    ("name", "name", FieldType::Str),
+    ("description", "description", FieldType::Str),
+    ("priority", "priority", FieldType::Int),
+    ("state", "state", FieldType::Str),
+    ("added_at", "added_at", FieldType::DateTime),
+];
+
+pub const SERVICE_COMMAND_FILTER: &[filters::FilterField] = &[
+    ("service_name", "service_name", FieldType::Str),
+    ("priority", "priority", FieldType::Int),
+    ("object_type", "object_type", FieldType::Str),
+    ("description", "description", FieldType::Str),
+    ("exec_mode", "exec_mode", FieldType::Str),
+    ("reply_type", "reply_type", FieldType::Str),
+];
+
+pub const SERVICE_EVENT_FILTER: &[filters::FilterField] = &[
+    ("service_name", "service_name", FieldType::Str),
+    ("object_type", "object_type", FieldType::Str),
+    ("description", "description", FieldType::Str),
+];
+
+pub const SERVICE_SUBSCRIPTION_FILTER: &[filters::FilterField] = &[
+    ("service_name", "service_name", FieldType::Str),
+    ("object_type", "object_type", FieldType::Str),
+];
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Copy, Clone, ToString, JsonSchema)]
 pub enum ServiceState {
     Alive,
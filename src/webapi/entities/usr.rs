@@ -6,5 +6,8 @@ use sqlx::FromRow;
 pub struct Usr {
     pub id: i32,
     pub usr_name: String,
-    pub usr_password: String,
+    #[serde(skip_serializing)]
+    pub usr_password: String, //argon2 hash in PHC string format
+    pub is_disabled: bool,
+    pub is_admin: bool, //may call admin commands, e.g. account of service
 }
@@ -1,8 +1,13 @@
+use super::{entities, replies};
+use hyper::StatusCode;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error;
 use std::fmt;
 
+pub const DEFAULT_LOCALE: &str = "en"; //locale of error names when requested one is not supported
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Copy, Clone, ToString, JsonSchema)]
 pub enum ErrorCode {
     ReplyOk,                  //command success result
@@ -12,384 +17,478 @@ pub enum ErrorCode {
     TimeoutExpiredError,
     DatabaseError, //internal error, database level
     NotFoundError,
-}
-
-#[derive(Debug, Clone)]
-pub struct ChannelError;
-
-impl fmt::Display for ChannelError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "channel error")
-    }
-}
-
-impl error::Error for ChannelError {
-    fn description(&self) -> &str {
-        "channel error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnsupportedProtoError;
-
-impl fmt::Display for UnsupportedProtoError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unsupported proto error")
-    }
-}
-
-impl error::Error for UnsupportedProtoError {
-    fn description(&self) -> &str {
-        "supported proto error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct ProtoProviderError;
-
-impl fmt::Display for ProtoProviderError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "proto provider error")
-    }
-}
-
-impl error::Error for ProtoProviderError {
-    fn description(&self) -> &str {
-        "proto provider error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct SignalSendError;
-
-impl fmt::Display for SignalSendError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "signal send error")
-    }
-}
-
-impl error::Error for SignalSendError {
-    fn description(&self) -> &str {
-        "signal send error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct EventSendError;
-
-impl fmt::Display for EventSendError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "event send error")
-    }
-}
-
-impl error::Error for EventSendError {
-    fn description(&self) -> &str {
-        "event send error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct HandlerError;
-
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "handler error")
-    }
-}
-
-impl error::Error for HandlerError {
-    fn description(&self) -> &str {
-        "handler error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnknownServiceNameError;
-
-impl fmt::Display for UnknownServiceNameError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unknown service name error")
-    }
-}
-
-impl error::Error for UnknownServiceNameError {
-    fn description(&self) -> &str {
-        "unknown service name error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnknownCommandError;
-
-impl fmt::Display for UnknownCommandError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unknown command error")
-    }
-}
-
-impl error::Error for UnknownCommandError {
-    fn description(&self) -> &str {
-        "unknown command error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnknownEventError;
-
-impl fmt::Display for UnknownEventError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unknown event error")
-    }
-}
-
-impl error::Error for UnknownEventError {
-    fn description(&self) -> &str {
-        "unknown event error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct BadReplyCommandError;
-
-impl fmt::Display for BadReplyCommandError {
+    BadFilterError,  //filter expression parse or validation error
+    BadPageError,    //bad limit, offset or order by field
+    BadRequestError, //request body or params are invalid
+    UnauthorizedError,
+    ForbiddenError, //command is allowed to admin usr only
+    InternalError,  //internal error, details are in service log
+}
+
+// Kind of service error, maps to reply error code and http status.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ErrorKind {
+    Channel,
+    UnsupportedProto,
+    ProtoProvider,
+    SignalSend,
+    UnknownServiceName,
+    UnknownCommand,
+    BadReplyCommand,
+    BadReplyEvent,
+    GetRemoteRouter,
+    UnsetRequiredValue,
+    TimeoutExpired,
+    UnknownErrorCode,
+    AsyncCommandNotFound,
+    DataIntegrity,
+    UnsupportedExecMode,
+    UnknownAsyncCommandState,
+    Database,
+    BadFilter,
+    BadPage,
+    BadRequest,
+    NotFound,
+    Unauthorized,
+    Forbidden,
+    Internal, //error of other crate
+}
+
+impl ErrorKind {
+    pub fn get_error_code(&self) -> ErrorCode {
+        match self {
+            ErrorKind::UnknownServiceName
+            | ErrorKind::UnknownCommand
+            | ErrorKind::AsyncCommandNotFound
+            | ErrorKind::NotFound => ErrorCode::NotFoundError,
+            ErrorKind::UnknownErrorCode
+            | ErrorKind::UnknownAsyncCommandState
+            | ErrorKind::BadRequest => ErrorCode::BadRequestError,
+            ErrorKind::TimeoutExpired => ErrorCode::TimeoutExpiredError,
+            ErrorKind::UnsupportedExecMode => ErrorCode::UnsupportedExecModeError,
+            ErrorKind::Database => ErrorCode::DatabaseError,
+            ErrorKind::BadFilter => ErrorCode::BadFilterError,
+            ErrorKind::BadPage => ErrorCode::BadPageError,
+            ErrorKind::Unauthorized => ErrorCode::UnauthorizedError,
+            ErrorKind::Forbidden => ErrorCode::ForbiddenError,
+            _ => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn get_status_code(&self) -> StatusCode {
+        match self.get_error_code() {
+            ErrorCode::NotFoundError => StatusCode::NOT_FOUND,
+            ErrorCode::BadRequestError
+            | ErrorCode::BadFilterError
+            | ErrorCode::BadPageError
+            | ErrorCode::UnsupportedExecModeError => StatusCode::BAD_REQUEST,
+            ErrorCode::TimeoutExpiredError => StatusCode::GATEWAY_TIMEOUT,
+            ErrorCode::TooManyRequestsError => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::UnauthorizedError => StatusCode::UNAUTHORIZED,
+            ErrorCode::ForbiddenError => StatusCode::FORBIDDEN,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "bad reply command error")
-    }
-}
-
-impl error::Error for BadReplyCommandError {
-    fn description(&self) -> &str {
-        "bad reply command error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct GeRemoteRouterError;
-
-impl fmt::Display for GeRemoteRouterError {
+        write!(
+            f,
+            "{}",
+            match self {
+                ErrorKind::Channel => "channel error",
+                ErrorKind::UnsupportedProto => "unsupported proto error",
+                ErrorKind::ProtoProvider => "proto provider error",
+                ErrorKind::SignalSend => "signal send error",
+                ErrorKind::UnknownServiceName => "unknown service name error",
+                ErrorKind::UnknownCommand => "unknown command error",
+                ErrorKind::BadReplyCommand => "bad reply command error",
+                ErrorKind::BadReplyEvent => "bad reply event error",
+                ErrorKind::GetRemoteRouter => "get from remote router error",
+                ErrorKind::UnsetRequiredValue => "required value not set",
+                ErrorKind::TimeoutExpired => "timeout expired error",
+                ErrorKind::UnknownErrorCode => "unknown error code error",
+                ErrorKind::AsyncCommandNotFound => "async command not found error",
+                ErrorKind::DataIntegrity => "data integrity error",
+                ErrorKind::UnsupportedExecMode => "unsupported exec mode error",
+                ErrorKind::UnknownAsyncCommandState => "unknown async command state error",
+                ErrorKind::Database => "database error",
+                ErrorKind::BadFilter => "bad filter error",
+                ErrorKind::BadPage => "bad page error",
+                ErrorKind::BadRequest => "bad request error",
+                ErrorKind::NotFound => "not found error",
+                ErrorKind::Unauthorized => "unauthorized error",
+                ErrorKind::Forbidden => "forbidden error",
+                ErrorKind::Internal => "internal error",
+            }
+        )
+    }
+}
+
+// Service error with context of failed request and chain of causes.
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub object_type: Option<String>,
+    pub correlation_id: Option<String>,
+    pub field: Option<String>, //offending field of body or param
+    pub errors: Vec<replies::common::ValidationError>, //body values not matching schema
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind: kind,
+            object_type: None,
+            correlation_id: None,
+            field: None,
+            errors: vec![],
+            source: None,
+        }
+    }
+
+    pub fn with_object_type(mut self, object_type: &str) -> Error {
+        self.object_type = Some(object_type.to_string());
+        self
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: &str) -> Error {
+        self.correlation_id = Some(correlation_id.to_string());
+        self
+    }
+
+    pub fn with_field(mut self, field: &str) -> Error {
+        self.field = Some(field.to_string());
+        self
+    }
+
+    pub fn with_errors(mut self, errors: Vec<replies::common::ValidationError>) -> Error {
+        self.errors = errors;
+        self
+    }
+
+    pub fn with_source<E>(mut self, source: E) -> Error
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        self.source = Some(source.into());
+        self
+    }
+
+    // kind of any boxed error, errors of other crates are internal
+    pub fn get_kind(e: &(dyn error::Error + 'static)) -> ErrorKind {
+        e.downcast_ref::<Error>()
+            .map_or(ErrorKind::Internal, |e| e.kind)
+    }
+
+    pub fn from_boxed(e: Box<dyn error::Error>) -> Error {
+        match e.downcast::<Error>() {
+            Ok(e) => *e,
+            Err(e) => Error::new(ErrorKind::Internal).with_source(Cause::new(&*e)),
+        }
+    }
+
+    pub fn get_causes(&self) -> Vec<String> {
+        let mut causes = Vec::<String>::new();
+        let mut source = error::Error::source(self);
+        while let Some(s) = source {
+            causes.push(s.to_string());
+            source = s.source();
+        }
+        causes
+    }
+}
+
+impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "get from remote router error")
+        write!(f, "{}", self.kind)?;
+        if let Some(ot) = &self.object_type {
+            write!(f, ", object type {}", ot)?;
+        }
+        if let Some(cid) = &self.correlation_id {
+            write!(f, ", correlation id {}", cid)?;
+        }
+        if let Some(fl) = &self.field {
+            write!(f, ", field {}", fl)?;
+        }
+        Ok(())
     }
 }
 
-impl error::Error for GeRemoteRouterError {
-    fn description(&self) -> &str {
-        "get from remote router error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.source {
+            Some(s) => Some(s.as_ref()),
+            None => None,
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct UnsetRequiredValueError;
-
-impl fmt::Display for UnsetRequiredValueError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "required value not set")
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Error {
+        Error::new(kind)
     }
 }
 
-impl error::Error for UnsetRequiredValueError {
-    fn description(&self) -> &str {
-        "required value not set"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+impl From<ErrorKind> for Box<dyn error::Error> {
+    fn from(kind: ErrorKind) -> Box<dyn error::Error> {
+        Box::new(Error::new(kind))
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct TooManyRequestsError;
-
-impl fmt::Display for TooManyRequestsError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "too many request error")
-    }
+// message chain of error that is not Send, to keep it as source
+#[derive(Debug)]
+struct Cause {
+    message: String,
+    source: Option<Box<Cause>>,
 }
 
-impl error::Error for TooManyRequestsError {
-    fn description(&self) -> &str {
-        "too many request error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+impl Cause {
+    fn new(e: &dyn error::Error) -> Cause {
+        Cause {
+            message: e.to_string(),
+            source: e.source().map(|s| Box::new(Cause::new(s))),
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct TimeoutExpiredError;
-
-impl fmt::Display for TimeoutExpiredError {
+impl fmt::Display for Cause {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "timeout expired error")
-    }
-}
-
-impl error::Error for TimeoutExpiredError {
-    fn description(&self) -> &str {
-        "timeout expired error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnknownErrorCodeError;
-
-impl fmt::Display for UnknownErrorCodeError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unknown error code error")
-    }
-}
-
-impl error::Error for UnknownErrorCodeError {
-    fn description(&self) -> &str {
-        "unknown error code error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct AsyncCommandNotFoundError;
-
-impl fmt::Display for AsyncCommandNotFoundError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "async command not found error")
-    }
-}
-
-impl error::Error for AsyncCommandNotFoundError {
-    fn description(&self) -> &str {
-        "async command not found error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct DataIntegrityError;
-
-impl fmt::Display for DataIntegrityError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "data integrity error")
-    }
-}
-
-impl error::Error for DataIntegrityError {
-    fn description(&self) -> &str {
-        "data integrity error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnsupportedExecModeError;
-
-impl fmt::Display for UnsupportedExecModeError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unsupported exec mode error")
-    }
-}
-
-impl error::Error for UnsupportedExecModeError {
-    fn description(&self) -> &str {
-        "unsupported exec mode error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct UnknownAsyncCommandStateError;
-
-impl fmt::Display for UnknownAsyncCommandStateError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unknown async command state error")
-    }
-}
-
-impl error::Error for UnknownAsyncCommandStateError {
-    fn description(&self) -> &str {
-        "unknown async command state error"
-    }
-
-    fn cause(&self) -> Option<&(dyn error::Error)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for Cause {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.source {
+            Some(s) => Some(s.as_ref()),
+            None => None,
+        }
+    }
+}
+
+// Error names of service by code and locale, error table of database is seeded from it on
+// every start and memory storage reads it as is.
+pub const ERROR_NAMES: &[(ErrorCode, &str, &str)] = &[
+    (ErrorCode::DatabaseError, "en", "Database error"),
+    (ErrorCode::DatabaseError, "ru", "Ошибка базы данных"),
+    (
+        ErrorCode::NotFoundError,
+        "en",
+        "Some items with specified id is not found",
+    ),
+    (
+        ErrorCode::NotFoundError,
+        "ru",
+        "Некоторые элементы с указанным id не найдены",
+    ),
+    (
+        ErrorCode::TooManyRequestsError,
+        "en",
+        "Too many requests, async command queue is full",
+    ),
+    (
+        ErrorCode::TooManyRequestsError,
+        "ru",
+        "Слишком много запросов, очередь асинхронных команд заполнена",
+    ),
+    (
+        ErrorCode::BadFilterError,
+        "en",
+        "Filter expression is invalid or uses unknown field",
+    ),
+    (
+        ErrorCode::BadFilterError,
+        "ru",
+        "Выражение фильтра неверно или использует неизвестное поле",
+    ),
+    (
+        ErrorCode::BadPageError,
+        "en",
+        "Page limit, offset or order by is invalid",
+    ),
+    (
+        ErrorCode::BadPageError,
+        "ru",
+        "Неверный limit, offset или order by страницы",
+    ),
+    (
+        ErrorCode::BadRequestError,
+        "en",
+        "Request body or params are invalid",
+    ),
+    (
+        ErrorCode::BadRequestError,
+        "ru",
+        "Неверное тело или параметры запроса",
+    ),
+    (
+        ErrorCode::TimeoutExpiredError,
+        "en",
+        "Timeout expired while waiting for reply",
+    ),
+    (
+        ErrorCode::TimeoutExpiredError,
+        "ru",
+        "Истекло время ожидания ответа",
+    ),
+    (
+        ErrorCode::UnsupportedExecModeError,
+        "en",
+        "Exec mode is not supported by command",
+    ),
+    (
+        ErrorCode::UnsupportedExecModeError,
+        "ru",
+        "Режим выполнения не поддерживается командой",
+    ),
+    (
+        ErrorCode::UnauthorizedError,
+        "en",
+        "Authorization is required",
+    ),
+    (ErrorCode::UnauthorizedError, "ru", "Требуется авторизация"),
+    (
+        ErrorCode::ForbiddenError,
+        "en",
+        "Command is allowed to admin only",
+    ),
+    (
+        ErrorCode::ForbiddenError,
+        "ru",
+        "Команда разрешена только администратору",
+    ),
+    (ErrorCode::InternalError, "en", "Internal error"),
+    (ErrorCode::InternalError, "ru", "Внутренняя ошибка"),
+];
+
+pub fn get_error_names() -> Vec<entities::error::Error> {
+    ERROR_NAMES
+        .iter()
+        .map(|(error_code, locale, error_name)| entities::error::Error {
+            error_code: error_code.to_string(),
+            locale: locale.to_string(),
+            error_name: error_name.to_string(),
+        })
+        .collect()
+}
+
+// Names of error codes by locale, loaded from database, settings override database names.
+// Name of unsupported locale is taken from default one, unknown code is named by itself.
+pub struct ErrorCatalogue {
+    default_locale: String,
+    name: HashMap<String, HashMap<String, String>>, //locale/error code/name
+}
+
+impl ErrorCatalogue {
+    pub fn new(default_locale: &str) -> ErrorCatalogue {
+        ErrorCatalogue {
+            default_locale: default_locale.to_lowercase(),
+            name: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, locale: &str, error_code: &str, error_name: &str) {
+        self.name
+            .entry(locale.to_lowercase())
+            .or_insert_with(HashMap::new)
+            .insert(error_code.to_string(), error_name.to_string());
+    }
+
+    // add names that are not set yet, so overrides are inserted first
+    pub fn extend_missing(&mut self, items: Vec<entities::error::Error>) {
+        for item in items {
+            self.name
+                .entry(item.locale.to_lowercase())
+                .or_insert_with(HashMap::new)
+                .entry(item.error_code)
+                .or_insert(item.error_name);
+        }
+    }
+
+    pub fn get_default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    // supported locale for Accept-Language value like "ru-RU,ru;q=0.9,en;q=0.8",
+    // region is dropped if only language is supported
+    pub fn negotiate(&self, accept_language: Option<&str>) -> &str {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|r| {
+                let mut parts = r.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                if tag.len() == 0 || quality <= 0.0 {
+                    None
+                } else {
+                    Some((tag, quality))
+                }
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        for (tag, _) in ranges {
+            if tag == "*" {
+                break;
+            }
+            let tag = tag.to_lowercase();
+            let language = tag.split('-').next().unwrap_or_default();
+            for l in &[tag.as_str(), language] {
+                if let Some((k, _)) = self.name.get_key_value(*l) {
+                    return k;
+                }
+            }
+        }
+        &self.default_locale
+    }
+
+    pub fn get(&self, error_code: &str, locale: &str) -> Option<&String> {
+        self.name
+            .get(locale)
+            .and_then(|n| n.get(error_code))
+            .or_else(|| {
+                self.name
+                    .get(&self.default_locale)
+                    .and_then(|n| n.get(error_code))
+            })
+    }
+
+    // name never fails, None is default locale
+    pub fn get_name(&self, error_code: &ErrorCode, locale: Option<&str>) -> String {
+        let error_code = error_code.to_string();
+        match self.get(&error_code, locale.unwrap_or(&self.default_locale)) {
+            Some(n) => n.clone(),
+            None => error_code,
+        }
+    }
+
+    // all known codes with names in locale
+    pub fn get_all(&self, locale: &str) -> Vec<entities::error::Error> {
+        let mut codes: Vec<&String> = self.name.values().flat_map(|n| n.keys()).collect();
+        codes.sort();
+        codes.dedup();
+        codes
+            .into_iter()
+            .filter_map(|c| {
+                self.get(c, locale).map(|n| entities::error::Error {
+                    error_code: c.clone(),
+                    locale: self.get_locale_of(c, locale).to_string(),
+                    error_name: n.clone(),
+                })
+            })
+            .collect()
+    }
+
+    // locale of name returned by get
+    pub fn get_locale_of<'a>(&'a self, error_code: &str, locale: &'a str) -> &'a str {
+        match self.name.get(locale).and_then(|n| n.get(error_code)) {
+            Some(_) => locale,
+            None => &self.default_locale,
+        }
     }
 }
@@ -1,14 +1,10 @@
-use super::super::{entities, traits};
+use super::super::entities;
+use super::super::routes::path;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::EVENT_ON_ASYNC_COMMAND_STATE_CHANGE)]
 pub struct OnAsyncCommandStateChange {
     pub commands: Vec<entities::executor::AsyncCommandState>,
 }
-
-impl traits::ObjectType for OnAsyncCommandStateChange {
-    fn get_type_name() -> &'static str {
-        "OnAsyncCommandStateChange"
-    }
-}
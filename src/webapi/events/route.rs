@@ -1,25 +1,15 @@
-use super::super::traits;
+use super::super::routes::path;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::ROUTER_EVENT_ON_SERVICE_UNAVAILABLE)]
 pub struct OnServiceUnavailable {
     pub services: Vec<String>,
 }
 
-impl traits::ObjectType for OnServiceUnavailable {
-    fn get_type_name() -> &'static str {
-        "OnServiceUnavailable"
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema, ObjectType)]
+#[object_type(path = path::EVENT_ON_ROUTE_UPDATE)]
 pub struct OnRouteUpdate {
     pub services: Vec<String>,
 }
-
-impl traits::ObjectType for OnRouteUpdate {
-    fn get_type_name() -> &'static str {
-        "OnRouteUpdate"
-    }
-}
@@ -1,19 +1,28 @@
-use super::{access, connectors, entities, errors, providers, router, traits, workers};
+use super::{
+    access, commands, connectors, entities, errors, providers, replies, router, routes, traits,
+    traits::ObjectType, workers,
+};
 use bytes::Buf;
 use hyper::Body;
 use serde::{de, ser};
 use std::collections::HashMap;
 use std::sync::Arc;
+use strum_macros::EnumString;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
-#[derive(Debug, PartialEq, Copy, Clone, ToString)]
+#[derive(Debug, PartialEq, Copy, Clone, EnumString, ToString)]
 pub enum ExecMode {
     Any,   //default if ommit in call, priority for sync
     Sync,  //only sync
     Async, //only async
 }
 
+pub enum CallReply<R> {
+    Sync(R),       //reply of sync call
+    Async(String), //id of registered sended async command
+}
+
 pub struct CommandExecutor {
     dc: Arc<connectors::DataConnector>,
     ac: Arc<access::AccessChecker>,
@@ -21,6 +30,8 @@ pub struct CommandExecutor {
     hp: providers::HttpProvider,
     mp: providers::MqProvider,
     _cs: mpsc::Sender<workers::SignalCode>,
+    max_async_queue_length: u16,
+    service_name: String, //local service, sended with async command for reply access
 }
 
 impl CommandExecutor {
@@ -28,25 +39,35 @@ impl CommandExecutor {
         dc: Arc<connectors::DataConnector>,
         ac: Arc<access::AccessChecker>,
         rt: Arc<router::Router>,
+        hp: providers::HttpProvider,
+        mp: providers::MqProvider,
         cs: mpsc::Sender<workers::SignalCode>,
+        max_async_queue_length: u16,
+        service_name: &str,
     ) -> connectors::Result<CommandExecutor> {
         Ok(CommandExecutor {
             dc: dc,
             ac: ac,
             rt: rt,
-            hp: providers::HttpProvider::new().await?,
-            mp: providers::MqProvider::new().await?,
+            hp: hp,
+            mp: mp,
             _cs: cs,
+            max_async_queue_length: max_async_queue_length,
+            service_name: service_name.to_string(),
         })
     }
 
+    pub fn get_max_async_queue_length(&self) -> u16 {
+        self.max_async_queue_length
+    }
+
     pub async fn send_signal(&self, signal_code: workers::SignalCode) -> connectors::Result<()> {
         let s = self._cs.clone();
         match s.send(signal_code).await {
             Ok(_) => Ok({}),
             Err(e) => {
                 error!("send_signal_command_executor: {}", e);
-                return Err(errors::SignalSendError.into());
+                return Err(errors::ErrorKind::SignalSend.into());
             }
         }
     }
@@ -56,10 +77,13 @@ impl CommandExecutor {
         state: String,
         id: &str,
     ) -> connectors::Result<entities::executor::AsyncCommandState> {
-        let c = self
+        let (c, _) = self
             .dc
             .received_async_command
-            .get(Some(vec![id.to_string()]))
+            .get(
+                Some(vec![id.to_string()]),
+                &commands::common::Page::default(),
+            )
             .await?;
         if c.len() == 1 {
             //todo: call change_state collection method
@@ -80,7 +104,7 @@ impl CommandExecutor {
                         "change_received_async_command_state_command_executor: {}",
                         e
                     );
-                    return Err(errors::AsyncCommandNotFoundError.into());
+                    return Err(errors::ErrorKind::AsyncCommandNotFound.into());
                 }
             }
             // Ok(entities::executor::AsyncCommandState {
@@ -89,7 +113,7 @@ impl CommandExecutor {
             //     state_changed_at: c[0].state_changed_at,
             // })
         } else {
-            Err(errors::AsyncCommandNotFoundError.into())
+            Err(errors::ErrorKind::AsyncCommandNotFound.into())
         }
     }
 
@@ -97,10 +121,13 @@ impl CommandExecutor {
         &self,
         id: &str,
     ) -> connectors::Result<entities::executor::AsyncCommandState> {
-        let c = self
+        let (c, _) = self
             .dc
             .received_async_command
-            .get(Some(vec![id.to_string()]))
+            .get(
+                Some(vec![id.to_string()]),
+                &commands::common::Page::default(),
+            )
             .await?;
         if c.len() == 1 {
             Ok(entities::executor::AsyncCommandState {
@@ -109,7 +136,7 @@ impl CommandExecutor {
                 state_changed_at: c[0].state_changed_at,
             })
         } else {
-            Err(errors::AsyncCommandNotFoundError.into())
+            Err(errors::ErrorKind::AsyncCommandNotFound.into())
         }
     }
 
@@ -117,10 +144,13 @@ impl CommandExecutor {
         &self,
         id: &str,
     ) -> connectors::Result<entities::executor::AsyncCommandState> {
-        let sac = self
+        let (sac, _) = self
             .dc
             .sended_async_command
-            .get(Some(vec![id.to_string()]))
+            .get(
+                Some(vec![id.to_string()]),
+                &commands::common::Page::default(),
+            )
             .await?;
         if sac.len() == 1 {
             let command = self.rt.get_command(&sac[0].object_type)?;
@@ -128,17 +158,17 @@ impl CommandExecutor {
             let mut prop = HashMap::<&str, &str>::new();
             prop.insert("correlation_id", &cid);
             prop.insert("async_command_id", &id);
-            let sp = self
-                .rt
-                .get_service_path(
-                    &command.service_name.as_ref().unwrap(),
-                    providers::Proto::Http,
-                )?
-                .state;
             if command
                 .path
                 .contains_key(&providers::Proto::Http.to_string())
             {
+                let sp = self
+                    .rt
+                    .get_service_path(
+                        &command.service_name.as_ref().unwrap(),
+                        providers::Proto::Http,
+                    )?
+                    .state;
                 let token = self
                     .ac
                     .get_client_basic_authorization_token(command.service_name.as_ref().unwrap())?;
@@ -149,23 +179,34 @@ impl CommandExecutor {
                 if reply.is_some() {
                     Ok(reply.unwrap())
                 } else {
-                    Err(errors::BadReplyCommandError.into())
+                    Err(errors::ErrorKind::BadReplyCommand.into())
                 }
             } else if command.path.contains_key(&providers::Proto::Mq.to_string()) {
-                let response = self.mp.execute(&sp, prop, Body::empty()).await?;
+                let sp = self
+                    .rt
+                    .get_service_path(
+                        &command.service_name.as_ref().unwrap(),
+                        providers::Proto::Mq,
+                    )?
+                    .state;
+                prop.insert(providers::MQ_PATH, routes::path::STATE);
+                let token = self
+                    .ac
+                    .get_client_basic_authorization_token(command.service_name.as_ref().unwrap())?;
+                let response = self.mp.execute(&sp, prop, token, Body::empty()).await?;
                 let reader = hyper::body::aggregate(response).await?.reader();
                 let reply: Option<entities::executor::AsyncCommandState> =
                     serde_json::from_reader(reader).unwrap_or(None);
                 if reply.is_some() {
                     Ok(reply.unwrap())
                 } else {
-                    Err(errors::BadReplyCommandError.into())
+                    Err(errors::ErrorKind::BadReplyCommand.into())
                 }
             } else {
-                Err(errors::UnsupportedProtoError.into())
+                Err(errors::ErrorKind::UnsupportedProto.into())
             }
         } else {
-            Err(errors::AsyncCommandNotFoundError.into())
+            Err(errors::ErrorKind::AsyncCommandNotFound.into())
         }
     }
 
@@ -175,21 +216,102 @@ impl CommandExecutor {
         T: traits::ObjectType,
         R: for<'de> de::Deserialize<'de>,
         R: traits::ObjectType,
+    {
+        match self.call_with_mode(request, ExecMode::Sync).await? {
+            CallReply::Sync(r) => Ok(r),
+            CallReply::Async(_) => Err(errors::ErrorKind::UnsupportedExecMode.into()),
+        }
+    }
+
+    // call command in requested exec mode, for Any prefer sync if service support it
+    pub async fn call_with_mode<T, R>(
+        &self,
+        request: T,
+        exec_mode: ExecMode,
+    ) -> connectors::Result<CallReply<R>>
+    where
+        T: ser::Serialize,
+        T: traits::ObjectType,
+        R: for<'de> de::Deserialize<'de>,
+        R: traits::ObjectType,
+    {
+        let command = self.rt.get_command(T::get_type_name())?;
+        match CommandExecutor::get_exec_mode(&command, exec_mode)? {
+            ExecMode::Async => Ok(CallReply::Async(self.send_async(&command, request).await?)),
+            _ => Ok(CallReply::Sync(self.send_sync(&command, request).await?)),
+        }
+    }
+
+    fn get_exec_mode(
+        command: &entities::route::CommandRoute,
+        exec_mode: ExecMode,
+    ) -> connectors::Result<ExecMode> {
+        let supported = match command.exec_mode.parse::<ExecMode>() {
+            Ok(m) => m,
+            Err(_) => {
+                error!(
+                    "command {} has bad exec mode {}",
+                    command.object_type, command.exec_mode
+                );
+                return Err(errors::ErrorKind::UnsupportedExecMode.into());
+            }
+        };
+        match (exec_mode, supported) {
+            (ExecMode::Any, ExecMode::Async) => Ok(ExecMode::Async),
+            (ExecMode::Any, _) => Ok(ExecMode::Sync),
+            (ExecMode::Sync, ExecMode::Async) | (ExecMode::Async, ExecMode::Sync) => {
+                Err(errors::ErrorKind::UnsupportedExecMode.into())
+            }
+            (m, _) => Ok(m),
+        }
+    }
+
+    async fn send_sync<T, R>(
+        &self,
+        command: &entities::route::CommandRoute,
+        request: T,
+    ) -> connectors::Result<R>
+    where
+        T: ser::Serialize,
+        T: traits::ObjectType,
+        R: for<'de> de::Deserialize<'de>,
     {
         let cid = Uuid::new_v4().to_hyphenated().to_string();
+        let exec_mode = ExecMode::Sync.to_string();
         let mut prop = HashMap::<&str, &str>::new();
         prop.insert("correlation_id", &cid);
         prop.insert("object_type", T::get_type_name());
-        let command = self.rt.get_command(T::get_type_name())?;
+        prop.insert("exec_mode", &exec_mode);
+        let response = self
+            .execute(
+                command,
+                prop,
+                Body::from(serde_json::to_string(&request).unwrap()),
+            )
+            .await?;
+        let reader = hyper::body::aggregate(response).await?.reader();
+        let reply: Option<R> = serde_json::from_reader(reader).unwrap_or(None);
+        if reply.is_some() {
+            Ok(reply.unwrap())
+        } else {
+            Err(errors::ErrorKind::BadReplyCommand.into())
+        }
+    }
+
+    async fn execute(
+        &self,
+        command: &entities::route::CommandRoute,
+        prop: HashMap<&str, &str>,
+        body: Body,
+    ) -> connectors::Result<Body> {
+        let token = self
+            .ac
+            .get_client_basic_authorization_token(command.service_name.as_ref().unwrap())?;
         if command
             .path
             .contains_key(&providers::Proto::Http.to_string())
         {
-            let token = self
-                .ac
-                .get_client_basic_authorization_token(command.service_name.as_ref().unwrap())?;
-            let response = self
-                .hp
+            self.hp
                 .execute(
                     &command
                         .path
@@ -197,34 +319,154 @@ impl CommandExecutor {
                         .unwrap(),
                     prop,
                     token,
-                    Body::from(serde_json::to_string(&request).unwrap()),
+                    body,
                 )
-                .await?;
-            let reader = hyper::body::aggregate(response).await?.reader();
-            let reply: Option<R> = serde_json::from_reader(reader).unwrap_or(None);
-            if reply.is_some() {
-                Ok(reply.unwrap())
-            } else {
-                Err(errors::BadReplyCommandError.into())
-            }
+                .await
         } else if command.path.contains_key(&providers::Proto::Mq.to_string()) {
-            let response = self
-                .mp
+            self.mp
                 .execute(
                     &command.path.get(&providers::Proto::Mq.to_string()).unwrap(),
                     prop,
-                    Body::from(serde_json::to_string(&request).unwrap()),
+                    token,
+                    body,
                 )
-                .await?;
-            let reader = hyper::body::aggregate(response).await?.reader();
-            let reply: Option<R> = serde_json::from_reader(reader).unwrap_or(None);
-            if reply.is_some() {
-                Ok(reply.unwrap())
-            } else {
-                Err(errors::BadReplyCommandError.into())
+                .await
+        } else {
+            Err(errors::ErrorKind::UnsupportedProto.into())
+        }
+    }
+
+    // register sended async command and return its id, reply comes later on reply_to path
+    pub async fn call_async<T>(&self, request: T) -> connectors::Result<String>
+    where
+        T: ser::Serialize,
+        T: traits::ObjectType,
+    {
+        let command = self.rt.get_command(T::get_type_name())?;
+        CommandExecutor::get_exec_mode(&command, ExecMode::Async)?;
+        self.send_async(&command, request).await
+    }
+
+    async fn send_async<T>(
+        &self,
+        command: &entities::route::CommandRoute,
+        request: T,
+    ) -> connectors::Result<String>
+    where
+        T: ser::Serialize,
+        T: traits::ObjectType,
+    {
+        let proto = if command
+            .path
+            .contains_key(&providers::Proto::Http.to_string())
+        {
+            providers::Proto::Http
+        } else if command.path.contains_key(&providers::Proto::Mq.to_string()) {
+            providers::Proto::Mq
+        } else {
+            return Err(errors::ErrorKind::UnsupportedProto.into());
+        };
+        let reply_to = self.rt.get_local_service_path(proto)?.reply_to;
+        let cid = Uuid::new_v4().to_hyphenated().to_string();
+        let now = chrono::offset::Utc::now();
+        let (result, _) = self
+            .dc
+            .sended_async_command
+            .add(vec![entities::executor::SendedAsyncCommand {
+                id: cid.clone(),
+                object_type: T::get_type_name().to_string(),
+                service_name: command.service_name.clone().unwrap_or_default(),
+                state: entities::executor::CommandSystemState::Initial.to_string(),
+                change_state_event: 0,
+                added_at: now,
+                state_changed_at: now,
+                reply_body: None,
+                history: None,
+            }])
+            .await?;
+        if result != errors::ErrorCode::ReplyOk {
+            return Err(errors::ErrorKind::Database.into());
+        }
+        let exec_mode = ExecMode::Async.to_string();
+        let mut prop = HashMap::<&str, &str>::new();
+        prop.insert("correlation_id", &cid);
+        prop.insert("object_type", T::get_type_name());
+        prop.insert("exec_mode", &exec_mode);
+        prop.insert("reply_to", &reply_to);
+        prop.insert("service_name", &self.service_name);
+        let reply: Option<replies::common::StandardReply> = match self
+            .execute(
+                command,
+                prop,
+                Body::from(serde_json::to_string(&request).unwrap()),
+            )
+            .await
+        {
+            Ok(response) => {
+                let reader = hyper::body::aggregate(response).await?.reader();
+                serde_json::from_reader(reader).unwrap_or(None)
             }
+            Err(e) => {
+                error!("call_async_command_executor: {}", e);
+                None
+            }
+        };
+        if reply.is_some() && reply.as_ref().unwrap().error_code == errors::ErrorCode::AsyncOk {
+            Ok(cid)
+        } else {
+            self.dc.sended_async_command.remove(vec![cid]).await?;
+            Err(errors::ErrorKind::BadReplyCommand.into())
+        }
+    }
+
+    // send reply of completed received async command to caller reply_to path
+    pub async fn send_async_reply(
+        &self,
+        command: &entities::executor::ReceivedAsyncCommand,
+    ) -> connectors::Result<()> {
+        let reply_type = match self.rt.get_command(&command.object_type) {
+            Ok(c) => c.reply_type,
+            Err(_) => replies::common::StandardReply::get_type_name().to_string(),
+        };
+        let mut prop = HashMap::<&str, &str>::new();
+        prop.insert("correlation_id", &command.id);
+        prop.insert("async_command_id", &command.id);
+        prop.insert("object_type", &reply_type);
+        let response = if command.proto == providers::Proto::Http.to_string() {
+            let token = self
+                .ac
+                .get_client_basic_authorization_token(&command.service_name)?;
+            self.hp
+                .execute(
+                    &command.reply_to,
+                    prop,
+                    token,
+                    Body::from(command.reply_body.clone()),
+                )
+                .await?
+        } else if command.proto == providers::Proto::Mq.to_string() {
+            prop.insert(providers::MQ_PATH, routes::path::REPLY);
+            let token = self
+                .ac
+                .get_client_basic_authorization_token(&command.service_name)?;
+            self.mp
+                .execute(
+                    &command.reply_to,
+                    prop,
+                    token,
+                    Body::from(command.reply_body.clone()),
+                )
+                .await?
+        } else {
+            return Err(errors::ErrorKind::UnsupportedProto.into());
+        };
+        let reader = hyper::body::aggregate(response).await?.reader();
+        let reply: Option<replies::common::StandardReply> =
+            serde_json::from_reader(reader).unwrap_or(None);
+        if reply.is_some() && reply.as_ref().unwrap().is_ok() {
+            Ok({})
         } else {
-            Err(errors::UnsupportedProtoError.into())
+            Err(errors::ErrorKind::BadReplyCommand.into())
         }
     }
 }
@@ -0,0 +1,776 @@
+// Filter expression language for Get* commands, compiled to parameterised sql.
+//
+// filter     := or_exp
+// or_exp     := and_exp { "or" and_exp }
+// and_exp    := term { "and" term }
+// term       := "(" or_exp ")" | field op value
+// op         := "=" | "<>" | "!=" | "<" | "<=" | ">" | ">=" | "like"
+// value      := 'string' | integer
+//
+// Keywords are case insensitive, quote inside string is doubled ('it''s'),
+// datetime fields are compared with rfc3339 string ('2020-01-31T00:00:00Z').
+// Only fields declared by collection are allowed, "like" only for string fields.
+// "like" is case insensitive on each backend, compiled as LOWER(column) LIKE LOWER(value)
+// since postgres is case sensitive and mysql or sqlite are not; sqlite lowers ascii only.
+// Example: service_name like 'car%' and (priority > 0 or exec_mode = 'Async')
+//
+// Parsed condition and order are kept by column too, so memory storage can filter and page rows.
+use super::{commands, errors};
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+
+const MAX_FILTER_LENGTH: usize = 1024;
+const MAX_FILTER_DEPTH: usize = 16;
+const MAX_PAGE_LIMIT: i64 = 1000;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum FieldType {
+    Int,
+    Str,
+    DateTime,
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub enum FilterValue {
+    Int(i64),
+    Str(String),
+    DateTime(DateTime<Utc>),
+}
+
+// field name in filter, column name in sql, field type
+pub type FilterField = (&'static str, &'static str, FieldType);
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub exp: String,              //sql condition with placeholders
+    pub params: Vec<FilterValue>, //values in placeholder order
+    pub condition: Condition,     //same condition for evaluation on row
+}
+
+#[derive(Debug, Clone)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Compare(&'static str, &'static str, FilterValue), //column, sql op, value
+}
+
+impl Condition {
+    // evaluate on row, value returns column value of row, none is sql null and never matches
+    pub fn matches<F>(&self, value: &F) -> bool
+    where
+        F: Fn(&str) -> Option<FilterValue>,
+    {
+        match self {
+            Condition::And(l, r) => l.matches(value) && r.matches(value),
+            Condition::Or(l, r) => l.matches(value) || r.matches(value),
+            Condition::Compare(column, op, v) => match value(column) {
+                Some(FilterValue::Str(s)) if *op == "LIKE" => match v {
+                    FilterValue::Str(p) => is_like(
+                        &s.to_lowercase().chars().collect::<Vec<char>>(),
+                        &p.to_lowercase().chars().collect::<Vec<char>>(),
+                    ),
+                    _ => false,
+                },
+                Some(c) => match c.partial_cmp(v) {
+                    Some(o) => match *op {
+                        "=" => o == Ordering::Equal,
+                        "<>" => o != Ordering::Equal,
+                        "<" => o == Ordering::Less,
+                        "<=" => o != Ordering::Greater,
+                        ">" => o == Ordering::Greater,
+                        ">=" => o != Ordering::Less,
+                        _ => false,
+                    },
+                    None => false,
+                },
+                None => false,
+            },
+        }
+    }
+}
+
+// sql like, % is any sequence and _ is any single char, on mismatch pattern goes back
+// to last % which takes one more char, so check is linear for pattern without %
+fn is_like(s: &[char], p: &[char]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    let mut last: Option<(usize, usize)> = None; //pattern after last %, string taken by it
+    while i < s.len() {
+        if j < p.len() && p[j] == '%' {
+            j += 1;
+            last = Some((j, i));
+        } else if j < p.len() && (p[j] == '_' || p[j] == s[i]) {
+            i += 1;
+            j += 1;
+        } else if let Some((lj, li)) = last {
+            j = lj;
+            i = li + 1;
+            last = Some((lj, i));
+        } else {
+            return false;
+        }
+    }
+    p[j..].iter().all(|c| *c == '%')
+}
+
+impl Filter {
+    // parse optional filter, first_param is number of first placeholder in query
+    pub fn parse(
+        filter: Option<String>,
+        fields: &[FilterField],
+        first_param: usize,
+    ) -> Result<Option<Filter>, errors::Error> {
+        match filter {
+            Some(f) if f.trim().len() != 0 => {
+                if f.len() > MAX_FILTER_LENGTH {
+                    return Err(errors::ErrorKind::BadFilter.into());
+                }
+                let mut parser = Parser {
+                    tokens: tokenize(&f)?,
+                    pos: 0,
+                    fields: fields,
+                    first_param: first_param,
+                    params: Vec::<FilterValue>::new(),
+                };
+                let (exp, condition) = parser.parse_or(0)?;
+                if parser.pos != parser.tokens.len() {
+                    return Err(errors::ErrorKind::BadFilter.into());
+                }
+                Ok(Some(Filter {
+                    exp: exp,
+                    params: parser.params,
+                    condition: condition,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Paging {
+    pub order_exp: String,          //sql order by list of known columns
+    pub order: Vec<(String, bool)>, //same list as column and is descending
+    pub limit: Option<i64>,
+    pub offset: i64,
+}
+
+impl Paging {
+    // parse paging params, none if command has no paging, default order is key columns
+    pub fn parse(
+        page: &commands::common::Page,
+        fields: &[FilterField],
+        key_columns: &[&str],
+    ) -> Result<Option<Paging>, errors::Error> {
+        if page.is_none() {
+            return Ok(None);
+        }
+        let offset = page.offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(errors::Error::new(errors::ErrorKind::BadPage).with_field("offset"));
+        }
+        if let Some(l) = page.limit {
+            if l <= 0 || l > MAX_PAGE_LIMIT {
+                return Err(errors::Error::new(errors::ErrorKind::BadPage).with_field("limit"));
+            }
+        }
+        let mut order = Vec::<(String, bool)>::new();
+        if let Some(o) = &page.order_by {
+            for item in o.split(',') {
+                let parts: Vec<&str> = item.split_whitespace().collect();
+                let field = match parts.get(0) {
+                    Some(name) => match fields.iter().find(|f| f.0 == *name) {
+                        Some(f) => f,
+                        None => {
+                            return Err(
+                                errors::Error::new(errors::ErrorKind::BadPage).with_field(name)
+                            )
+                        }
+                    },
+                    None => {
+                        return Err(
+                            errors::Error::new(errors::ErrorKind::BadPage).with_field("order_by")
+                        )
+                    }
+                };
+                let desc = match parts.get(1) {
+                    None => false,
+                    Some(d) if d.eq_ignore_ascii_case("asc") => false,
+                    Some(d) if d.eq_ignore_ascii_case("desc") => true,
+                    _ => {
+                        return Err(
+                            errors::Error::new(errors::ErrorKind::BadPage).with_field("order_by")
+                        )
+                    }
+                };
+                if parts.len() > 2 {
+                    return Err(
+                        errors::Error::new(errors::ErrorKind::BadPage).with_field("order_by")
+                    );
+                }
+                order.push((field.1.to_string(), desc));
+            }
+        }
+        //key columns last for stable order between pages
+        for key_column in key_columns {
+            if !order.iter().any(|o| o.0 == *key_column) {
+                order.push((key_column.to_string(), false));
+            }
+        }
+        Ok(Some(Paging {
+            order_exp: order
+                .iter()
+                .map(|o| format!("{} {}", o.0, if o.1 { "DESC" } else { "ASC" }))
+                .collect::<Vec<String>>()
+                .join(", "),
+            order: order,
+            limit: page.limit,
+            offset: offset,
+        }))
+    }
+
+    // total count and offset of next page, offset is none on last page
+    pub fn get_page_info(paging: &Option<Paging>, count: usize, total: i64) -> PageInfo {
+        let next_offset = match paging {
+            Some(p) if p.limit.is_some() && p.offset + (count as i64) < total => {
+                Some(p.offset + count as i64)
+            }
+            _ => None,
+        };
+        PageInfo {
+            total: total,
+            next_offset: next_offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PageInfo {
+    pub total: i64,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+fn tokenize(filter: &str) -> Result<Vec<Token>, errors::Error> {
+    let chars: Vec<char> = filter.chars().collect();
+    let mut tokens = Vec::<Token>::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if c == '\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                if i >= chars.len() {
+                    return Err(errors::ErrorKind::BadFilter.into());
+                }
+                if chars[i] == '\'' {
+                    if i + 1 < chars.len() && chars[i + 1] == '\'' {
+                        s.push('\'');
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                s.push(chars[i]);
+                i += 1;
+            }
+            tokens.push(Token::Str(s));
+        } else if c.is_ascii_digit()
+            || (c == '-' && i + 1 < chars.len() && chars[i + 1].is_ascii_digit())
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let s: String = chars[start..i].iter().collect();
+            tokens
+                .push(Token::Int(s.parse::<i64>().map_err(|_| {
+                    errors::Error::new(errors::ErrorKind::BadFilter)
+                })?));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let s: String = chars[start..i].iter().collect();
+            tokens.push(Token::Ident(s));
+        } else {
+            let next = if i + 1 < chars.len() {
+                chars[i + 1]
+            } else {
+                ' '
+            };
+            let op = match (c, next) {
+                ('<', '>') => "<>",
+                ('!', '=') => "<>",
+                ('<', '=') => "<=",
+                ('>', '=') => ">=",
+                ('<', _) => "<",
+                ('>', _) => ">",
+                ('=', _) => "=",
+                _ => return Err(errors::ErrorKind::BadFilter.into()),
+            };
+            i += if op.len() == 2 { 2 } else { 1 };
+            tokens.push(Token::Op(op));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    fields: &'a [FilterField],
+    first_param: usize,
+    params: Vec<FilterValue>,
+}
+
+impl<'a> Parser<'a> {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(Token::Ident(s)) => s.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self, depth: usize) -> Result<(String, Condition), errors::Error> {
+        if depth > MAX_FILTER_DEPTH {
+            return Err(errors::ErrorKind::BadFilter.into());
+        }
+        let (mut exp, mut condition) = self.parse_and(depth)?;
+        while self.is_keyword("or") {
+            self.pos += 1;
+            let (e, c) = self.parse_and(depth)?;
+            exp = format!("{} OR {}", exp, e);
+            condition = Condition::Or(Box::new(condition), Box::new(c));
+        }
+        Ok((exp, condition))
+    }
+
+    fn parse_and(&mut self, depth: usize) -> Result<(String, Condition), errors::Error> {
+        let (mut exp, mut condition) = self.parse_term(depth)?;
+        while self.is_keyword("and") {
+            self.pos += 1;
+            let (e, c) = self.parse_term(depth)?;
+            exp = format!("{} AND {}", exp, e);
+            condition = Condition::And(Box::new(condition), Box::new(c));
+        }
+        Ok((exp, condition))
+    }
+
+    fn parse_term(&mut self, depth: usize) -> Result<(String, Condition), errors::Error> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(Token::Open) => {
+                self.pos += 1;
+                let (exp, condition) = self.parse_or(depth + 1)?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    return Err(errors::ErrorKind::BadFilter.into());
+                }
+                self.pos += 1;
+                Ok((format!("({})", exp), condition))
+            }
+            Some(Token::Ident(name)) => {
+                let field = match self.fields.iter().find(|f| f.0 == name) {
+                    Some(f) => *f,
+                    None => {
+                        return Err(
+                            errors::Error::new(errors::ErrorKind::BadFilter).with_field(&name)
+                        )
+                    }
+                };
+                self.pos += 1;
+                let op = match self.tokens.get(self.pos) {
+                    Some(Token::Op(op)) => *op,
+                    Some(Token::Ident(s)) if s.eq_ignore_ascii_case("like") => {
+                        if field.2 != FieldType::Str {
+                            return Err(errors::ErrorKind::BadFilter.into());
+                        }
+                        "LIKE"
+                    }
+                    _ => return Err(errors::ErrorKind::BadFilter.into()),
+                };
+                self.pos += 1;
+                let value = match (self.tokens.get(self.pos), field.2) {
+                    (Some(Token::Int(v)), FieldType::Int) => FilterValue::Int(*v),
+                    (Some(Token::Str(v)), FieldType::Str) => FilterValue::Str(v.clone()),
+                    (Some(Token::Str(v)), FieldType::DateTime) => FilterValue::DateTime(
+                        DateTime::parse_from_rfc3339(v)
+                            .map_err(|_| errors::Error::new(errors::ErrorKind::BadFilter))?
+                            .with_timezone(&Utc),
+                    ),
+                    _ => return Err(errors::ErrorKind::BadFilter.into()),
+                };
+                self.pos += 1;
+                self.params.push(value.clone());
+                let placeholder = get_placeholder(self.first_param + self.params.len() - 1);
+                Ok((
+                    if op == "LIKE" {
+                        format!("LOWER({}) LIKE LOWER({})", field.1, placeholder)
+                    } else {
+                        format!("{} {} {}", field.1, op, placeholder)
+                    },
+                    Condition::Compare(field.1, op, value),
+                ))
+            }
+            _ => Err(errors::ErrorKind::BadFilter.into()),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+fn get_placeholder(n: usize) -> String {
+    format!("${}", n)
+}
+
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+fn get_placeholder(_n: usize) -> String {
+    "?".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn like(s: &str, p: &str) -> bool {
+        is_like(
+            &s.chars().collect::<Vec<char>>(),
+            &p.chars().collect::<Vec<char>>(),
+        )
+    }
+
+    #[test]
+    fn like_wildcards() {
+        assert!(like("car", "car"));
+        assert!(like("car", "c_r"));
+        assert!(like("car", "%"));
+        assert!(like("", "%%"));
+        assert!(like("service car", "%car"));
+        assert!(like("car service", "car%"));
+        assert!(like("a car b car", "%car%car"));
+        assert!(like("abcbc", "%bc"));
+        assert!(!like("car", "ca"));
+        assert!(!like("car", "car_"));
+        assert!(!like("", "_"));
+        assert!(!like("car", "Car"));
+        assert!(!like("abcbd", "%bc"));
+    }
+
+    const FIELDS: &[FilterField] = &[
+        ("id", "id", FieldType::Int),
+        ("service_name", "name", FieldType::Str),
+        ("added_at", "added_at", FieldType::DateTime),
+    ];
+
+    fn parse(filter: &str) -> Result<Option<Filter>, errors::Error> {
+        Filter::parse(Some(filter.to_string()), FIELDS, 1)
+    }
+
+    fn is_bad_filter(filter: &str) -> bool {
+        match parse(filter) {
+            Err(e) => e.kind == errors::ErrorKind::BadFilter,
+            Ok(_) => false,
+        }
+    }
+
+    fn matches(filter: &str, id: i64, name: &str) -> bool {
+        parse(filter)
+            .unwrap()
+            .unwrap()
+            .condition
+            .matches(&|column: &str| match column {
+                "id" => Some(FilterValue::Int(id)),
+                "name" => Some(FilterValue::Str(name.to_string())),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn tokenize_filter() {
+        assert_eq!(
+            tokenize("id>=-5 AND(x<>'a b')!=").unwrap(),
+            vec![
+                Token::Ident("id".to_string()),
+                Token::Op(">="),
+                Token::Int(-5),
+                Token::Ident("AND".to_string()),
+                Token::Open,
+                Token::Ident("x".to_string()),
+                Token::Op("<>"),
+                Token::Str("a b".to_string()),
+                Token::Close,
+                Token::Op("<>"),
+            ]
+        );
+        assert!(tokenize("id = 'open").is_err());
+        assert!(tokenize("id ; 1").is_err());
+        assert!(tokenize("id = 99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn empty_filter_is_none() {
+        assert!(Filter::parse(None, FIELDS, 1).unwrap().is_none());
+        assert!(parse("  ").unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_to_sql_by_column() {
+        let f = parse("service_name LIKE 'car%' and (id > 0 Or added_at < '2020-01-31T00:00:00Z')")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            f.exp,
+            format!(
+                "LOWER(name) LIKE LOWER({}) AND (id > {} OR added_at < {})",
+                get_placeholder(1),
+                get_placeholder(2),
+                get_placeholder(3)
+            )
+        );
+        assert_eq!(
+            f.params,
+            vec![
+                FilterValue::Str("car%".to_string()),
+                FilterValue::Int(0),
+                FilterValue::DateTime(
+                    DateTime::parse_from_rfc3339("2020-01-31T00:00:00Z")
+                        .unwrap()
+                        .with_timezone(&Utc)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn string_is_bound_not_inlined() {
+        let f = parse("service_name = 'x'' OR 1=1 --' or id = 1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            f.exp,
+            format!(
+                "name = {} OR id = {}",
+                get_placeholder(1),
+                get_placeholder(2)
+            )
+        );
+        assert_eq!(f.params[0], FilterValue::Str("x' OR 1=1 --".to_string()));
+        assert!(is_bad_filter("service_name = 'x'; DROP TABLE car"));
+        assert!(is_bad_filter("id = 1 or 1 = 1"));
+        assert!(is_bad_filter("id = 1 --"));
+        assert!(is_bad_filter("name = 'x'"));
+    }
+
+    #[test]
+    fn unknown_field_and_type_mismatch() {
+        match parse("car_name = 'x'") {
+            Err(e) => assert_eq!(e.field.as_deref(), Some("car_name")),
+            Ok(_) => panic!("unknown field is parsed"),
+        }
+        assert!(is_bad_filter("id = 'x'"));
+        assert!(is_bad_filter("service_name = 1"));
+        assert!(is_bad_filter("id like '1%'"));
+        assert!(is_bad_filter("added_at > '31.01.2020'"));
+        assert!(is_bad_filter("added_at > 1"));
+    }
+
+    #[test]
+    fn bad_syntax() {
+        for f in &[
+            "id",
+            "id =",
+            "id = 1 and",
+            "id = 1 id = 2",
+            "(id = 1",
+            "id = 1)",
+            "()",
+            "= 1",
+        ] {
+            assert!(is_bad_filter(f), "{}", f);
+        }
+    }
+
+    #[test]
+    fn depth_and_length_limits() {
+        let nested = |depth: usize| format!("{}id = 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_FILTER_DEPTH)).is_ok());
+        assert!(is_bad_filter(&nested(MAX_FILTER_DEPTH + 1)));
+        let long = format!("service_name = '{}'", "a".repeat(MAX_FILTER_LENGTH));
+        assert!(is_bad_filter(&long));
+    }
+
+    #[test]
+    fn placeholder_follows_first_param() {
+        let f = Filter::parse(Some("id = 1 or id = 2".to_string()), FIELDS, 3)
+            .unwrap()
+            .unwrap();
+        #[cfg(feature = "postgres")]
+        assert_eq!(f.exp, "id = $3 OR id = $4");
+        #[cfg(any(feature = "mysql", feature = "sqlite"))]
+        assert_eq!(f.exp, "id = ? OR id = ?");
+    }
+
+    #[test]
+    fn condition_matches_row() {
+        assert!(matches("id = 1 and service_name = 'car'", 1, "car"));
+        assert!(!matches("id = 1 and service_name = 'car'", 1, "bus"));
+        assert!(matches("id = 2 or service_name <> 'bus'", 1, "car"));
+        assert!(matches(
+            "id >= 1 and id <= 1 and id < 2 and id > 0",
+            1,
+            "car"
+        ));
+        // like is case insensitive as in sql
+        assert!(matches("service_name like 'CAR%'", 1, "Car service"));
+        // unknown column is null and never matches
+        assert!(!parse("added_at > '2020-01-31T00:00:00Z'")
+            .unwrap()
+            .unwrap()
+            .condition
+            .matches(&|_: &str| None));
+    }
+
+    fn get_page(
+        limit: Option<i64>,
+        offset: Option<i64>,
+        order_by: Option<&str>,
+    ) -> commands::common::Page {
+        commands::common::Page {
+            limit: limit,
+            offset: offset,
+            order_by: order_by.map(|o| o.to_string()),
+        }
+    }
+
+    fn bad_page_field(page: &commands::common::Page) -> Option<String> {
+        match Paging::parse(page, FIELDS, &["id"]) {
+            Err(e) if e.kind == errors::ErrorKind::BadPage => e.field,
+            _ => panic!("page is parsed"),
+        }
+    }
+
+    #[test]
+    fn no_page_is_none() {
+        assert!(
+            Paging::parse(&commands::common::Page::default(), FIELDS, &["id"])
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn order_by_columns_with_key_last() {
+        let p = Paging::parse(
+            &get_page(Some(10), None, Some("service_name DESC, added_at")),
+            FIELDS,
+            &["id"],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(p.order_exp, "name DESC, added_at ASC, id ASC");
+        assert_eq!(p.offset, 0);
+        let p = Paging::parse(&get_page(None, None, Some("id desc")), FIELDS, &["id"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.order_exp, "id DESC");
+        // not unique key is completed by tiebreaker
+        let p = Paging::parse(&get_page(Some(10), None, None), FIELDS, &["name", "id"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.order_exp, "name ASC, id ASC");
+        let p = Paging::parse(
+            &get_page(None, None, Some("id desc")),
+            FIELDS,
+            &["name", "id"],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(p.order_exp, "id DESC, name ASC");
+    }
+
+    #[test]
+    fn offset_of_page() {
+        let p = Paging::parse(&get_page(Some(10), Some(5), None), FIELDS, &["id"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.offset, 5);
+    }
+
+    #[test]
+    fn bad_page() {
+        assert_eq!(
+            bad_page_field(&get_page(None, Some(-1), None)).as_deref(),
+            Some("offset")
+        );
+        assert_eq!(
+            bad_page_field(&get_page(Some(0), None, None)).as_deref(),
+            Some("limit")
+        );
+        assert_eq!(
+            bad_page_field(&get_page(Some(MAX_PAGE_LIMIT + 1), None, None)).as_deref(),
+            Some("limit")
+        );
+        assert_eq!(
+            bad_page_field(&get_page(None, None, Some("name"))).as_deref(),
+            Some("name")
+        );
+        assert_eq!(
+            bad_page_field(&get_page(None, None, Some("id up"))).as_deref(),
+            Some("order_by")
+        );
+        assert_eq!(
+            bad_page_field(&get_page(None, None, Some("id desc; DROP TABLE car"))).as_deref(),
+            Some("order_by")
+        );
+        assert_eq!(
+            bad_page_field(&get_page(None, None, Some("id,"))).as_deref(),
+            Some("order_by")
+        );
+    }
+
+    #[test]
+    fn offset_of_next_page() {
+        let paging = |offset: Option<i64>| {
+            Paging::parse(&get_page(Some(2), offset, None), FIELDS, &["id"]).unwrap()
+        };
+        let page_info = Paging::get_page_info(&paging(None), 2, 5);
+        assert_eq!(page_info.total, 5);
+        assert_eq!(page_info.next_offset, Some(2));
+        let page_info = Paging::get_page_info(&paging(Some(2)), 2, 5);
+        assert_eq!(page_info.next_offset, Some(4));
+        // last page has no next offset
+        let page_info = Paging::get_page_info(&paging(Some(4)), 1, 5);
+        assert!(page_info.next_offset.is_none());
+        let page_info = Paging::get_page_info(&paging(Some(4)), 0, 4);
+        assert!(page_info.next_offset.is_none());
+        // without limit everything is on one page
+        let p = Paging::parse(&get_page(None, Some(1), None), FIELDS, &["id"]).unwrap();
+        assert!(Paging::get_page_info(&p, 2, 5).next_offset.is_none());
+        assert!(Paging::get_page_info(&None, 5, 5).next_offset.is_none());
+    }
+
+    #[test]
+    fn like_is_not_exponential() {
+        let s: String = std::iter::repeat('a').take(200).collect();
+        let p = format!("{}b", "%a".repeat(20));
+        assert!(!like(&s, &p));
+    }
+}
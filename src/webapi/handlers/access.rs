@@ -0,0 +1,22 @@
+use super::super::{access, commands, connectors, errors, registry, replies, routes::path};
+use std::sync::Arc;
+
+pub async fn reload(
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    _cmd: commands::access::ReloadAccess,
+) -> connectors::Result<replies::common::StandardReply> {
+    ac.reload(dc).await?;
+    Ok(get_ok_reply!())
+}
+
+pub fn register(
+    hr: &mut registry::HandlerRegistry,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+) {
+    hr.register_command_at(path::ACCESS_RELOAD, move |cmd, _| {
+        let (dc, ac) = (dc.clone(), ac.clone());
+        async move { reload(&dc, &ac, cmd).await }
+    });
+}
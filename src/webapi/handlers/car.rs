@@ -1,23 +1,34 @@
-use super::super::{commands, connectors, errors, executors, replies};
+use super::super::{commands, connectors, errors, registry, replies, routes::path};
+use std::sync::Arc;
 
 pub async fn get(
     dc: &connectors::DataConnector,
     cmd: commands::car::GetCar,
 ) -> connectors::Result<replies::car::GetCarReply> {
-    match dc.car.get(cmd.ids).await {
-        Ok(r) => Ok(replies::car::GetCarReply {
+    match dc.car.get(cmd.ids, cmd.filter, &cmd.page).await {
+        Ok((r, page_info)) => Ok(replies::car::GetCarReply {
             error_code: errors::ErrorCode::ReplyOk,
             error_name: None,
             url: None,
+            total: Some(page_info.total),
+            next_offset: page_info.next_offset,
             items: Some(r),
         }),
         Err(e) => {
-            error!("get_car handler get car collection: {}", e);
-            let ec = errors::ErrorCode::DatabaseError;
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_car handler get car collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
             Ok(replies::car::GetCarReply {
                 error_code: ec.clone(),
-                error_name: Some(dc.error.get(&ec.to_string()).unwrap().clone()),
+                error_name: Some(dc.error.get_name(&ec, None)),
                 url: None,
+                total: None,
+                next_offset: None,
                 items: None,
             })
         }
@@ -38,7 +49,6 @@ pub async fn add(
 
 pub async fn change(
     dc: &connectors::DataConnector,
-    ce: &executors::CommandExecutor,
     cmd: commands::car::ChangeCar,
 ) -> connectors::Result<replies::common::StandardReply> {
     let result: errors::ErrorCode = dc.car.change(cmd.items).await?;
@@ -67,3 +77,31 @@ pub async fn reserve(
 ) -> connectors::Result<replies::common::StandardReply> {
     Ok(get_ok_reply!())
 }
+
+// car commands are built-in commands of registry on their own paths
+pub fn register(hr: &mut registry::HandlerRegistry, dc: Arc<connectors::DataConnector>) {
+    let get_dc = dc.clone();
+    let add_dc = dc.clone();
+    let change_dc = dc.clone();
+    let remove_dc = dc.clone();
+    hr.register_command_at(path::CAR_GET, move |cmd, _| {
+        let dc = get_dc.clone();
+        async move { get(&dc, cmd).await }
+    })
+    .register_command_at(path::CAR_ADD, move |cmd, _| {
+        let dc = add_dc.clone();
+        async move { add(&dc, cmd).await }
+    })
+    .register_command_at(path::CAR_CHANGE, move |cmd, _| {
+        let dc = change_dc.clone();
+        async move { change(&dc, cmd).await }
+    })
+    .register_command_at(path::CAR_REMOVE, move |cmd, _| {
+        let dc = remove_dc.clone();
+        async move { remove(&dc, cmd).await }
+    })
+    .register_command_at(path::CAR_RESERVE, move |cmd, _| {
+        let dc = dc.clone();
+        async move { reserve(&dc, cmd).await }
+    });
+}
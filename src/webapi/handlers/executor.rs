@@ -1,4 +1,8 @@
-use super::super::{connectors, errors, events, replies, router};
+use super::super::{
+    connectors, entities, errors, events, executors, registry, replies, router, routes::path,
+    workers,
+};
+use std::sync::Arc;
 
 pub async fn on_async_command_state_change(
     dc: &connectors::DataConnector,
@@ -9,22 +13,93 @@ pub async fn on_async_command_state_change(
     Ok(get_ok_reply!())
 }
 
-pub async fn add_sended_async_command() -> connectors::Result<replies::common::StandardReply> {
-    //todo:
-    Ok(get_ok_reply!())
+pub async fn add_sended_async_command(
+    dc: &connectors::DataConnector,
+    items: Vec<entities::executor::SendedAsyncCommand>,
+) -> connectors::Result<replies::common::StandardReply> {
+    let (result, _) = dc.sended_async_command.add(items).await?;
+    if result == errors::ErrorCode::ReplyOk {
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
 }
 
-pub async fn remove_sended_async_command() -> connectors::Result<replies::common::StandardReply> {
-    //todo:
-    Ok(get_ok_reply!())
+pub async fn complete_sended_async_command(
+    dc: &connectors::DataConnector,
+    id: &str,
+    reply_body: String,
+) -> connectors::Result<replies::common::StandardReply> {
+    let result = dc
+        .sended_async_command
+        .complete(id.to_string(), reply_body)
+        .await?;
+    if result == errors::ErrorCode::ReplyOk {
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
 }
 
-pub async fn add_received_async_command() -> connectors::Result<replies::common::StandardReply> {
-    //todo:
-    Ok(get_ok_reply!())
+pub async fn remove_sended_async_command(
+    dc: &connectors::DataConnector,
+    ids: Vec<String>,
+) -> connectors::Result<replies::common::StandardReply> {
+    let result = dc.sended_async_command.remove(ids).await?;
+    if result == errors::ErrorCode::ReplyOk {
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
 }
 
-pub async fn remove_received_async_command() -> connectors::Result<replies::common::StandardReply> {
-    //todo:
-    Ok(get_ok_reply!())
+pub async fn add_received_async_command(
+    dc: &connectors::DataConnector,
+    ce: &executors::CommandExecutor,
+    items: Vec<entities::executor::ReceivedAsyncCommand>,
+) -> connectors::Result<replies::common::StandardReply> {
+    // queue length is checked by storage in same transaction as insert
+    let (result, _) = dc
+        .received_async_command
+        .add_within_limit(items, ce.get_max_async_queue_length() as i64)
+        .await?;
+    if result == errors::ErrorCode::TooManyRequestsError {
+        warn!(
+            "received async command queue is full: {}",
+            ce.get_max_async_queue_length()
+        );
+    }
+    if result == errors::ErrorCode::ReplyOk {
+        ce.send_signal(workers::SignalCode::ReceivedAsyncCommand)
+            .await?;
+        Ok(get_async_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
+}
+
+pub async fn remove_received_async_command(
+    dc: &connectors::DataConnector,
+    ids: Vec<String>,
+) -> connectors::Result<replies::common::StandardReply> {
+    let result = dc.received_async_command.remove(ids).await?;
+    if result == errors::ErrorCode::ReplyOk {
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
+}
+
+pub fn register(
+    hr: &mut registry::HandlerRegistry,
+    dc: Arc<connectors::DataConnector>,
+    rt: Arc<router::Router>,
+) {
+    hr.register_event_at(
+        path::EVENT_ON_ASYNC_COMMAND_STATE_CHANGE,
+        move |events, _| {
+            let (dc, rt) = (dc.clone(), rt.clone());
+            async move { on_async_command_state_change(&dc, &rt, events).await }
+        },
+    );
 }
@@ -1,7 +1,34 @@
 #[macro_use]
 mod macros;
 
+pub mod access;
 pub mod car;
 pub mod executor;
+pub mod publisher;
 pub mod route;
 pub mod usr;
+
+use super::access::AccessChecker;
+use super::{connectors, publishers, registry, router};
+use std::sync::Arc;
+
+// commands of service domain, registered before route of service is added to router
+pub fn register(hr: &mut registry::HandlerRegistry, dc: Arc<connectors::DataConnector>) {
+    car::register(hr, dc);
+}
+
+// built-in commands and events of usr, access, router, publisher and executor,
+// registry of service domain is merged over them
+pub fn register_service(
+    hr: &mut registry::HandlerRegistry,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<AccessChecker>,
+    rt: Arc<router::Router>,
+    ep: Arc<publishers::EventPublisher>,
+) {
+    usr::register(hr, dc.clone(), ac.clone());
+    access::register(hr, dc.clone(), ac);
+    route::register(hr, dc.clone(), rt.clone(), ep.clone());
+    publisher::register(hr, dc.clone(), ep);
+    executor::register(hr, dc, rt);
+}
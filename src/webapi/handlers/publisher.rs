@@ -0,0 +1,73 @@
+use super::super::{
+    commands, connectors, errors, publishers, registry, replies, routes::path, workers,
+};
+use std::sync::Arc;
+
+pub async fn get_dead_letter(
+    dc: &connectors::DataConnector,
+    cmd: commands::publisher::GetDeadLetterEvent,
+) -> connectors::Result<replies::publisher::GetDeadLetterEventReply> {
+    match dc
+        .dead_letter_event
+        .get(cmd.ids, cmd.filter, &cmd.page)
+        .await
+    {
+        Ok((r, page_info)) => Ok(replies::publisher::GetDeadLetterEventReply {
+            error_code: errors::ErrorCode::ReplyOk,
+            error_name: None,
+            url: None,
+            total: Some(page_info.total),
+            next_offset: page_info.next_offset,
+            items: Some(r),
+        }),
+        Err(e) => {
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_dead_letter handler get dead letter collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
+            Ok(replies::publisher::GetDeadLetterEventReply {
+                error_code: ec.clone(),
+                error_name: Some(dc.error.get_name(&ec, None)),
+                url: None,
+                total: None,
+                next_offset: None,
+                items: None,
+            })
+        }
+    }
+}
+
+pub async fn replay_dead_letter(
+    dc: &connectors::DataConnector,
+    ep: &publishers::EventPublisher,
+    cmd: commands::publisher::ReplayDeadLetterEvent,
+) -> connectors::Result<replies::common::StandardReply> {
+    let result = dc.dead_letter_event.replay(cmd.ids).await?;
+    if result == errors::ErrorCode::ReplyOk {
+        ep.send_signal(workers::SignalCode::UndeliveredEvent)
+            .await?;
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
+}
+
+pub fn register(
+    hr: &mut registry::HandlerRegistry,
+    dc: Arc<connectors::DataConnector>,
+    ep: Arc<publishers::EventPublisher>,
+) {
+    let get_dc = dc.clone();
+    hr.register_command_at(path::ROUTER_DEAD_LETTER_GET, move |cmd, _| {
+        let dc = get_dc.clone();
+        async move { get_dead_letter(&dc, cmd).await }
+    })
+    .register_command_at(path::ROUTER_DEAD_LETTER_REPLAY, move |cmd, _| {
+        let (dc, ep) = (dc.clone(), ep.clone());
+        async move { replay_dead_letter(&dc, &ep, cmd).await }
+    });
+}
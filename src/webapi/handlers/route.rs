@@ -1,23 +1,37 @@
-use super::super::{commands, connectors, entities, errors, events, replies, router};
+use super::super::{
+    commands, connectors, entities, errors, events, publishers, registry, replies, router,
+    routes::path, workers,
+};
+use std::sync::Arc;
 
 pub async fn get(
     dc: &connectors::DataConnector,
     cmd: commands::route::GetRoute,
 ) -> connectors::Result<replies::route::GetRouteReply> {
-    match dc.route.get(cmd.services).await {
-        Ok(r) => Ok(replies::route::GetRouteReply {
+    match dc.route.get(cmd.services, cmd.filter, &cmd.page).await {
+        Ok((r, page_info)) => Ok(replies::route::GetRouteReply {
             error_code: errors::ErrorCode::ReplyOk,
             error_name: None,
             url: None,
+            total: Some(page_info.total),
+            next_offset: page_info.next_offset,
             items: Some(r),
         }),
         Err(e) => {
-            error!("get_route handler get route collection: {}", e);
-            let ec = errors::ErrorCode::DatabaseError;
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_route handler get route collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
             Ok(replies::route::GetRouteReply {
                 error_code: ec.clone(),
-                error_name: Some(dc.error.get(&ec.to_string()).unwrap().clone()),
+                error_name: Some(dc.error.get_name(&ec, None)),
                 url: None,
+                total: None,
+                next_offset: None,
                 items: None,
             })
         }
@@ -28,20 +42,34 @@ pub async fn get_command(
     dc: &connectors::DataConnector,
     cmd: commands::route::GetServiceCommand,
 ) -> connectors::Result<replies::route::GetServiceCommandReply> {
-    match dc.route.get_command(cmd.services).await {
-        Ok(r) => Ok(replies::route::GetServiceCommandReply {
+    match dc
+        .route
+        .get_command(cmd.services, cmd.filter, &cmd.page)
+        .await
+    {
+        Ok((r, page_info)) => Ok(replies::route::GetServiceCommandReply {
             error_code: errors::ErrorCode::ReplyOk,
             error_name: None,
             url: None,
+            total: Some(page_info.total),
+            next_offset: page_info.next_offset,
             items: Some(r),
         }),
         Err(e) => {
-            error!("get_route_command handler get route collection: {}", e);
-            let ec = errors::ErrorCode::DatabaseError;
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_route_command handler get route collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
             Ok(replies::route::GetServiceCommandReply {
                 error_code: ec.clone(),
-                error_name: Some(dc.error.get(&ec.to_string()).unwrap().clone()),
+                error_name: Some(dc.error.get_name(&ec, None)),
                 url: None,
+                total: None,
+                next_offset: None,
                 items: None,
             })
         }
@@ -52,20 +80,34 @@ pub async fn get_event(
     dc: &connectors::DataConnector,
     cmd: commands::route::GetServiceEvent,
 ) -> connectors::Result<replies::route::GetServiceEventReply> {
-    match dc.route.get_event(cmd.services).await {
-        Ok(r) => Ok(replies::route::GetServiceEventReply {
+    match dc
+        .route
+        .get_event(cmd.services, cmd.filter, &cmd.page)
+        .await
+    {
+        Ok((r, page_info)) => Ok(replies::route::GetServiceEventReply {
             error_code: errors::ErrorCode::ReplyOk,
             error_name: None,
             url: None,
+            total: Some(page_info.total),
+            next_offset: page_info.next_offset,
             items: Some(r),
         }),
         Err(e) => {
-            error!("get_route_event handler get route collection: {}", e);
-            let ec = errors::ErrorCode::DatabaseError;
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_route_event handler get route collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
             Ok(replies::route::GetServiceEventReply {
                 error_code: ec.clone(),
-                error_name: Some(dc.error.get(&ec.to_string()).unwrap().clone()),
+                error_name: Some(dc.error.get_name(&ec, None)),
                 url: None,
+                total: None,
+                next_offset: None,
                 items: None,
             })
         }
@@ -76,20 +118,34 @@ pub async fn get_subscription(
     dc: &connectors::DataConnector,
     cmd: commands::route::GetServiceSubscription,
 ) -> connectors::Result<replies::route::GetServiceSubscriptionReply> {
-    match dc.route.get_subscription(cmd.services).await {
-        Ok(r) => Ok(replies::route::GetServiceSubscriptionReply {
+    match dc
+        .route
+        .get_subscription(cmd.services, cmd.filter, &cmd.page)
+        .await
+    {
+        Ok((r, page_info)) => Ok(replies::route::GetServiceSubscriptionReply {
             error_code: errors::ErrorCode::ReplyOk,
             error_name: None,
             url: None,
+            total: Some(page_info.total),
+            next_offset: page_info.next_offset,
             items: Some(r),
         }),
         Err(e) => {
-            error!("get_route_subscription handler get route collection: {}", e);
-            let ec = errors::ErrorCode::DatabaseError;
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_route_subscription handler get route collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
             Ok(replies::route::GetServiceSubscriptionReply {
                 error_code: ec.clone(),
-                error_name: Some(dc.error.get(&ec.to_string()).unwrap().clone()),
+                error_name: Some(dc.error.get_name(&ec, None)),
                 url: None,
+                total: None,
+                next_offset: None,
                 items: None,
             })
         }
@@ -105,23 +161,34 @@ pub fn get_helth() -> connectors::Result<entities::route::ServiceHelth> {
 pub fn get_error(
     dc: &connectors::DataConnector,
     error_code: &str,
+    locale: &str,
 ) -> connectors::Result<entities::error::Error> {
-    if dc.error.contains_key(error_code) {
-        Ok(entities::error::Error {
+    match dc.error.get(error_code, locale) {
+        Some(n) => Ok(entities::error::Error {
             error_code: error_code.to_string(),
-            error_name: dc.error.get(error_code).unwrap().clone(),
-        })
-    } else {
-        Err(errors::UnknownErrorCodeError.into())
+            locale: dc.error.get_locale_of(error_code, locale).to_string(),
+            error_name: n.clone(),
+        }),
+        None => Err(errors::Error::new(errors::ErrorKind::UnknownErrorCode)
+            .with_field("error_code")
+            .into()),
     }
 }
 
+// catalogue for other services, names of unsupported locale are in default one
+pub fn get_errors(
+    dc: &connectors::DataConnector,
+    locale: &str,
+) -> connectors::Result<Vec<entities::error::Error>> {
+    Ok(dc.error.get_all(locale))
+}
+
 pub async fn get_service(
     dc: &connectors::DataConnector,
     cmd: commands::route::GetService,
 ) -> connectors::Result<replies::route::GetServiceReply> {
-    match dc.route.get_service(cmd.names).await {
-        Ok(mut items) => {
+    match dc.route.get_service(cmd.names, cmd.filter, &cmd.page).await {
+        Ok((mut items, page_info)) => {
             for mut item in &mut items {
                 //todo: remote call helth service
                 item.state = entities::route::ServiceState::Alive.to_string();
@@ -130,16 +197,26 @@ pub async fn get_service(
                 error_code: errors::ErrorCode::ReplyOk,
                 error_name: None,
                 url: None,
+                total: Some(page_info.total),
+                next_offset: page_info.next_offset,
                 items: Some(items),
             })
         }
         Err(e) => {
-            error!("get_service handler get service collection: {}", e);
-            let ec = errors::ErrorCode::DatabaseError;
+            let ec = match errors::Error::get_kind(&*e) {
+                errors::ErrorKind::BadFilter => errors::ErrorCode::BadFilterError,
+                errors::ErrorKind::BadPage => errors::ErrorCode::BadPageError,
+                _ => {
+                    error!("get_service handler get service collection: {}", e);
+                    errors::ErrorCode::DatabaseError
+                }
+            };
             Ok(replies::route::GetServiceReply {
                 error_code: ec.clone(),
-                error_name: Some(dc.error.get(&ec.to_string()).unwrap().clone()),
+                error_name: Some(dc.error.get_name(&ec, None)),
                 url: None,
+                total: None,
+                next_offset: None,
                 items: None,
             })
         }
@@ -148,19 +225,31 @@ pub async fn get_service(
 
 pub async fn add(
     dc: &connectors::DataConnector,
+    correlation_id: &str,
     cmd: commands::route::AddRoute,
 ) -> connectors::Result<(
     replies::common::AddStrIdsReply,
     Option<Vec<events::route::OnRouteUpdate>>,
 )> {
-    let (result, ids) = dc.route.add(cmd.items).await?;
+    let events = vec![events::route::OnRouteUpdate {
+        services: cmd
+            .items
+            .iter()
+            .filter_map(|i| i.service_name.clone())
+            .collect(),
+    }];
+    let (result, ids) = dc
+        .route
+        .add(
+            cmd.items,
+            vec![publishers::EventPublisher::get_outbox_event(
+                correlation_id,
+                &events,
+            )],
+        )
+        .await?;
     if result == errors::ErrorCode::ReplyOk {
-        let v1 = ids.unwrap();
-        let v2 = v1.clone();
-        Ok(get_ok_add_str_ids_reply_events!(
-            v1,
-            Some(vec![events::route::OnRouteUpdate { services: v2 }])
-        ))
+        Ok(get_ok_add_str_ids_reply_events!(ids.unwrap(), Some(events)))
     } else {
         Ok(get_error_add_str_ids_reply!(&result, dc.error))
     }
@@ -168,25 +257,35 @@ pub async fn add(
 
 pub async fn remove(
     dc: &connectors::DataConnector,
+    correlation_id: &str,
     cmd: commands::route::RemoveRoute,
 ) -> connectors::Result<(
     replies::common::StandardReply,
     Option<Vec<events::route::OnRouteUpdate>>,
 )> {
-    let result: errors::ErrorCode = dc.route.remove(cmd.services.clone()).await?;
+    let events = vec![events::route::OnRouteUpdate {
+        services: cmd.services.clone(),
+    }];
+    let result: errors::ErrorCode = dc
+        .route
+        .remove(
+            cmd.services,
+            vec![publishers::EventPublisher::get_outbox_event(
+                correlation_id,
+                &events,
+            )],
+        )
+        .await?;
     if result == errors::ErrorCode::ReplyOk {
-        Ok(get_ok_reply_events!(Some(vec![
-            events::route::OnRouteUpdate {
-                services: cmd.services
-            }
-        ])))
+        Ok(get_ok_reply_events!(Some(events)))
     } else {
         Ok(get_error_reply_events!(&result, dc.error))
     }
 }
 
 pub async fn on_service_unavailable(
-    _dc: &connectors::DataConnector,
+    dc: &connectors::DataConnector,
+    correlation_id: &str,
     _rt: &router::Router,
     items: Vec<events::route::OnServiceUnavailable>,
 ) -> connectors::Result<(
@@ -194,16 +293,23 @@ pub async fn on_service_unavailable(
     Option<Vec<events::route::OnRouteUpdate>>,
 )> {
     //collect service unavailable info
-    if true {
-        let mut events = Vec::<events::route::OnRouteUpdate>::new();
-        for item in &items {
-            events.push(events::route::OnRouteUpdate {
-                services: item.services.clone(),
-            });
-        }
+    let mut events = Vec::<events::route::OnRouteUpdate>::new();
+    for item in &items {
+        events.push(events::route::OnRouteUpdate {
+            services: item.services.clone(),
+        });
+    }
+    let result = dc
+        .event_outbox
+        .add(vec![publishers::EventPublisher::get_outbox_event(
+            correlation_id,
+            &events,
+        )])
+        .await?;
+    if result == errors::ErrorCode::ReplyOk {
         Ok(get_ok_reply_events!(Some(events)))
     } else {
-        Ok(get_ok_reply_events!(None))
+        Ok(get_error_reply_events!(&result, dc.error))
     }
 }
 
@@ -214,11 +320,167 @@ pub async fn on_route_update(
 ) -> connectors::Result<replies::common::StandardReply> {
     if rt.is_local {
         let p = dc.route.get_service_path(None).await?;
-        let c = dc.route.get_command(None).await?;
-        let s = dc.route.get_subscription(None).await?;
+        let (c, _) = dc
+            .route
+            .get_command(None, None, &commands::common::Page::default())
+            .await?;
+        let (s, _) = dc
+            .route
+            .get_subscription(None, None, &commands::common::Page::default())
+            .await?;
         rt.update(p, c, s).await?;
     } else {
         //todo: get from remote route
     }
     Ok(get_ok_reply!())
 }
+
+// events are already committed to outbox, worker drains it by timer anyway
+async fn signal_event_outbox(ep: &publishers::EventPublisher) {
+    if let Err(e) = ep.send_signal(workers::SignalCode::EventOutbox).await {
+        warn!("event publisher: {}", e);
+    }
+}
+
+fn get_correlation_id(params: &registry::Params) -> String {
+    params.get("correlation_id").cloned().unwrap_or_default()
+}
+
+pub fn register(
+    hr: &mut registry::HandlerRegistry,
+    dc: Arc<connectors::DataConnector>,
+    rt: Arc<router::Router>,
+    ep: Arc<publishers::EventPublisher>,
+) {
+    let get_dc = dc.clone();
+    let get_command_dc = dc.clone();
+    let get_event_dc = dc.clone();
+    let get_subscription_dc = dc.clone();
+    let get_service_dc = dc.clone();
+    let (add_dc, add_ep) = (dc.clone(), ep.clone());
+    let (remove_dc, remove_ep) = (dc.clone(), ep.clone());
+    let (unavailable_dc, unavailable_rt) = (dc.clone(), rt.clone());
+    hr.register_command_at(path::ROUTER_ROUTE_GET, move |cmd, _| {
+        let dc = get_dc.clone();
+        async move { get(&dc, cmd).await }
+    })
+    .register_command_at(path::ROUTER_COMMAND_GET, move |cmd, _| {
+        let dc = get_command_dc.clone();
+        async move { get_command(&dc, cmd).await }
+    })
+    .register_command_at(path::ROUTER_EVENT_GET, move |cmd, _| {
+        let dc = get_event_dc.clone();
+        async move { get_event(&dc, cmd).await }
+    })
+    .register_command_at(path::ROUTER_SUBSCIBTION_GET, move |cmd, _| {
+        let dc = get_subscription_dc.clone();
+        async move { get_subscription(&dc, cmd).await }
+    })
+    .register_command_at(path::ROUTER_SERVICE_GET, move |cmd, _| {
+        let dc = get_service_dc.clone();
+        async move { get_service(&dc, cmd).await }
+    })
+    .register_command_at(path::ROUTER_ROUTE_ADD, move |cmd, params| {
+        let (dc, ep) = (add_dc.clone(), add_ep.clone());
+        async move {
+            let (reply, events) = add(&dc, &get_correlation_id(&params), cmd).await?;
+            if reply.is_ok() && events.is_some() {
+                signal_event_outbox(&ep).await;
+            }
+            Ok(reply)
+        }
+    })
+    .register_command_at(path::ROUTER_ROUTE_REMOVE, move |cmd, params| {
+        let (dc, ep) = (remove_dc.clone(), remove_ep.clone());
+        async move {
+            let (reply, events) = remove(&dc, &get_correlation_id(&params), cmd).await?;
+            if reply.is_ok() && events.is_some() {
+                signal_event_outbox(&ep).await;
+            }
+            Ok(reply)
+        }
+    })
+    .register_event_at(
+        path::ROUTER_EVENT_ON_SERVICE_UNAVAILABLE,
+        move |items, params| {
+            let (dc, rt, ep) = (unavailable_dc.clone(), unavailable_rt.clone(), ep.clone());
+            async move {
+                let (reply, events) =
+                    on_service_unavailable(&dc, &get_correlation_id(&params), &rt, items).await?;
+                if reply.is_ok() && events.is_some() {
+                    signal_event_outbox(&ep).await;
+                }
+                Ok(reply)
+            }
+        },
+    )
+    .register_event_at(path::EVENT_ON_ROUTE_UPDATE, move |items, _| {
+        let (dc, rt) = (dc.clone(), rt.clone());
+        async move { on_route_update(&dc, &rt, items).await }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{access, providers, settings, tests};
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+    use tokio::time::Duration;
+
+    async fn get_router(dc: Arc<connectors::DataConnector>) -> router::Router {
+        let (sender, _) = mpsc::channel(5);
+        let ac = access::AccessChecker::from_data_connector(
+            &dc,
+            &settings::Authentication {
+                server: HashMap::new(),
+                client: vec![],
+            },
+            sender,
+        )
+        .await
+        .unwrap();
+        let hp = providers::HttpProvider::new(Duration::from_secs(1))
+            .await
+            .unwrap();
+        let mp = providers::MqProvider::new(None, None, Duration::from_secs(1))
+            .await
+            .unwrap();
+        router::Router::new(
+            dc,
+            Arc::new(ac),
+            hp,
+            mp,
+            None,
+            HashMap::new(),
+            HashMap::new(),
+            "localhost",
+            "localhost",
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn on_service_unavailable_writes_outbox() {
+        let dc = Arc::new(tests::get_memory_data_connector().await);
+        let rt = get_router(dc.clone()).await;
+        let (reply, events) = on_service_unavailable(
+            &dc,
+            "1",
+            &rt,
+            vec![events::route::OnServiceUnavailable {
+                services: vec!["test".to_string()],
+            }],
+        )
+        .await
+        .unwrap();
+        assert!(reply.is_ok());
+        assert_eq!(events.unwrap()[0].services, vec!["test"]);
+        let items = dc.event_outbox.get(10).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].correlation_id, "1");
+        assert_eq!(items[0].object_type, "OnRouteUpdate");
+    }
+}
@@ -1,4 +1,11 @@
-use super::super::{connectors, entities::usr, errors, replies};
+use super::super::{
+    access, commands, connectors,
+    entities::usr,
+    errors, registry, replies,
+    routes::{path, service},
+    workers,
+};
+use std::sync::Arc;
 
 pub async fn get(
     dc: &connectors::DataConnector,
@@ -7,14 +14,284 @@ pub async fn get(
     Ok(dc.usr.get(ids).await?)
 }
 
+// server users of access checker are reloaded by worker after change
+async fn reload_access(ac: &access::AccessChecker) {
+    if let Err(e) = ac.send_signal(workers::SignalCode::AccessReload).await {
+        error!("reload access: {}", e);
+    }
+}
+
+// usr is found, not disabled and password matches hash
+async fn is_valid_credentials(
+    dc: &connectors::DataConnector,
+    usr_name: &str,
+    usr_password: &str,
+) -> connectors::Result<bool> {
+    let hash = dc
+        .usr
+        .get_by_name(usr_name)
+        .await?
+        .filter(|u| !u.is_disabled)
+        .map(|u| u.usr_password);
+    Ok(access::verify_password_async(usr_password.to_string(), hash).await)
+}
+
 pub async fn signin(
-    _dc: &connectors::DataConnector,
+    dc: &connectors::DataConnector,
+    cmd: commands::usr::SignIn,
 ) -> connectors::Result<replies::common::StandardReply> {
-    Ok(get_ok_reply!())
+    if is_valid_credentials(dc, &cmd.usr_name, &cmd.usr_password).await? {
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(
+            &errors::ErrorCode::UnauthorizedError,
+            dc.error
+        ))
+    }
 }
 
 pub async fn signup(
-    _dc: &connectors::DataConnector,
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    cmd: commands::usr::SignUp,
+) -> connectors::Result<replies::common::AddIntIdsReply> {
+    let usr_password = access::hash_password_async(cmd.usr_password).await?;
+    let (result, ids) = dc
+        .usr
+        .add(vec![usr::Usr {
+            id: 0,
+            usr_name: cmd.usr_name,
+            usr_password: usr_password,
+            is_disabled: false,
+            is_admin: false,
+        }])
+        .await?;
+    if result == errors::ErrorCode::ReplyOk {
+        reload_access(ac).await;
+        Ok(get_ok_add_int_ids_reply!(ids.unwrap()))
+    } else {
+        Ok(get_error_add_int_ids_reply!(&result, dc.error))
+    }
+}
+
+pub async fn change_password(
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    cmd: commands::usr::ChangeUsrPassword,
 ) -> connectors::Result<replies::common::StandardReply> {
-    Ok(get_ok_reply!())
+    if !is_valid_credentials(dc, &cmd.usr_name, &cmd.usr_password).await? {
+        return Ok(get_error_reply!(
+            &errors::ErrorCode::UnauthorizedError,
+            dc.error
+        ));
+    }
+    let usr_password = access::hash_password_async(cmd.new_usr_password).await?;
+    let result = dc.usr.change_password(&cmd.usr_name, &usr_password).await?;
+    if result == errors::ErrorCode::ReplyOk {
+        reload_access(ac).await;
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
+}
+
+// authorized usr may disable own account only
+pub async fn disable(
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    authorized_usr_name: Option<&str>,
+    cmd: commands::usr::DisableUsr,
+) -> connectors::Result<replies::common::StandardReply> {
+    if cmd.usr_names.is_empty()
+        || cmd
+            .usr_names
+            .iter()
+            .any(|n| Some(n.as_str()) != authorized_usr_name)
+    {
+        return Ok(get_error_reply!(
+            &errors::ErrorCode::UnauthorizedError,
+            dc.error
+        ));
+    }
+    let result = dc.usr.disable(cmd.usr_names).await?;
+    if result == errors::ErrorCode::ReplyOk {
+        reload_access(ac).await;
+        Ok(get_ok_reply!())
+    } else {
+        Ok(get_error_reply!(&result, dc.error))
+    }
+}
+
+// usr commands are built-in commands of registry on their own paths
+pub fn register(
+    hr: &mut registry::HandlerRegistry,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+) {
+    let signin_dc = dc.clone();
+    let (signup_dc, signup_ac) = (dc.clone(), ac.clone());
+    let (change_password_dc, change_password_ac) = (dc.clone(), ac.clone());
+    hr.register_command_at(path::USR_SIGHN_IN, move |cmd, _| {
+        let dc = signin_dc.clone();
+        async move { signin(&dc, cmd).await }
+    })
+    .register_command_at(path::USR_SIGHN_UP, move |cmd, _| {
+        let (dc, ac) = (signup_dc.clone(), signup_ac.clone());
+        async move { signup(&dc, &ac, cmd).await }
+    })
+    .register_command_at(path::USR_CHANGE_PASSWORD, move |cmd, _| {
+        let (dc, ac) = (change_password_dc.clone(), change_password_ac.clone());
+        async move { change_password(&dc, &ac, cmd).await }
+    })
+    .register_command_at(path::USR_DISABLE, move |cmd, params: registry::Params| {
+        let (dc, ac) = (dc.clone(), ac.clone());
+        async move {
+            let authorized_usr_name = params.get(service::AUTHORIZED_USR_PARAM);
+            disable(&dc, &ac, authorized_usr_name.map(|n| n.as_str()), cmd).await
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{settings, tests};
+    use super::*;
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    async fn get_access_checker(
+        dc: &connectors::DataConnector,
+    ) -> (access::AccessChecker, mpsc::Receiver<workers::SignalCode>) {
+        let (sender, receiver) = mpsc::channel::<workers::SignalCode>(5);
+        let ac = access::AccessChecker::from_data_connector(
+            dc,
+            &settings::Authentication {
+                server: HashMap::new(),
+                client: vec![],
+            },
+            sender,
+        )
+        .await
+        .unwrap();
+        (ac, receiver)
+    }
+
+    async fn signin_code(
+        dc: &connectors::DataConnector,
+        usr_name: &str,
+        usr_password: &str,
+    ) -> errors::ErrorCode {
+        signin(
+            dc,
+            commands::usr::SignIn {
+                usr_name: usr_name.to_string(),
+                usr_password: usr_password.to_string(),
+            },
+        )
+        .await
+        .unwrap()
+        .error_code
+    }
+
+    #[tokio::test]
+    async fn signup_and_signin() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, mut receiver) = get_access_checker(&dc).await;
+        let reply = signup(
+            &dc,
+            &ac,
+            commands::usr::SignUp {
+                usr_name: "user2".to_string(),
+                usr_password: "pass2".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(workers::SignalCode::AccessReload)
+        ));
+        let item = dc.usr.get_by_name("user2").await.unwrap().unwrap();
+        assert!(access::is_password_hash(&item.usr_password));
+        assert_eq!(
+            signin_code(&dc, "user2", "pass2").await,
+            errors::ErrorCode::ReplyOk
+        );
+        assert_eq!(
+            signin_code(&dc, "user2", "pass1").await,
+            errors::ErrorCode::UnauthorizedError
+        );
+        assert_eq!(
+            signin_code(&dc, "nobody", "pass2").await,
+            errors::ErrorCode::UnauthorizedError
+        );
+        // usr name is unique
+        let reply = signup(
+            &dc,
+            &ac,
+            commands::usr::SignUp {
+                usr_name: "user2".to_string(),
+                usr_password: "pass3".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply.error_code, errors::ErrorCode::DatabaseError);
+    }
+
+    #[tokio::test]
+    async fn change_password_requires_current_one() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        let change = |usr_password: &str| commands::usr::ChangeUsrPassword {
+            usr_name: "user1".to_string(),
+            usr_password: usr_password.to_string(),
+            new_usr_password: "new".to_string(),
+        };
+        let reply = change_password(&dc, &ac, change("wrong")).await.unwrap();
+        assert_eq!(reply.error_code, errors::ErrorCode::UnauthorizedError);
+        let reply = change_password(&dc, &ac, change("pass1")).await.unwrap();
+        assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+        assert_eq!(
+            signin_code(&dc, "user1", "pass1").await,
+            errors::ErrorCode::UnauthorizedError
+        );
+        assert_eq!(
+            signin_code(&dc, "user1", "new").await,
+            errors::ErrorCode::ReplyOk
+        );
+    }
+
+    #[tokio::test]
+    async fn disable_own_usr_only() {
+        let dc = tests::get_memory_data_connector().await;
+        let (ac, _receiver) = get_access_checker(&dc).await;
+        let cmd = |usr_names: &[&str]| commands::usr::DisableUsr {
+            usr_names: usr_names.iter().map(|n| n.to_string()).collect(),
+        };
+        for (authorized, usr_names) in &[
+            (Some("user1"), vec!["test"]),
+            (Some("user1"), vec!["user1", "test"]),
+            (Some("user1"), vec![]),
+            (None, vec!["user1"]),
+        ] {
+            let reply = disable(&dc, &ac, *authorized, cmd(usr_names))
+                .await
+                .unwrap();
+            assert_eq!(reply.error_code, errors::ErrorCode::UnauthorizedError);
+        }
+        assert_eq!(
+            signin_code(&dc, "test", "1234567890").await,
+            errors::ErrorCode::ReplyOk
+        );
+        let reply = disable(&dc, &ac, Some("user1"), cmd(&["user1"]))
+            .await
+            .unwrap();
+        assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+        assert_eq!(
+            signin_code(&dc, "user1", "pass1").await,
+            errors::ErrorCode::UnauthorizedError
+        );
+    }
 }
@@ -0,0 +1,234 @@
+use super::super::{
+    commands, connectors,
+    entities::car::{self, CAR_FILTER},
+    errors,
+    filters::{self, FilterValue},
+    providers, traits,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub struct CarCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl CarCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> CarCollection {
+        CarCollection {
+            data_provider: data_provider,
+        }
+    }
+
+    fn get_value(item: &car::Car, column: &str) -> Option<FilterValue> {
+        match column {
+            "id" => item.id.map(|v| FilterValue::Int(v as i64)),
+            "car_name" => Some(FilterValue::Str(item.car_name.clone())),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::CarStorage for CarCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<i32>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<car::Car>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, CAR_FILTER, 1)?;
+        let paging = filters::Paging::parse(page, CAR_FILTER, &["id"])?;
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<car::Car> = db
+            .car
+            .iter()
+            .filter(|item| {
+                ids.as_ref()
+                    .map_or(true, |ids| ids.contains(&item.id.unwrap()))
+            })
+            .cloned()
+            .collect();
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &[],
+            CarCollection::get_value,
+        ))
+    }
+
+    async fn add(
+        &self,
+        items: Vec<car::Car>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        let mut db = self.data_provider.db.lock().await;
+        let mut ids = Vec::<i32>::new();
+        for item in items {
+            let id = db.next_id("car");
+            db.car.push(car::Car {
+                id: Some(id),
+                car_name: item.car_name,
+            });
+            ids.push(id);
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    async fn change(&self, items: Vec<car::Car>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = items
+            .iter()
+            .filter(|item| db.car.iter().any(|c| c.id == Some(item.id.unwrap_or(0))))
+            .count();
+        if items.len() != count {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        for item in items {
+            for c in db.car.iter_mut().filter(|c| c.id == item.id) {
+                c.car_name = item.car_name.clone();
+            }
+        }
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .car
+            .iter()
+            .filter(|c| ids.contains(&c.id.unwrap()))
+            .count();
+        if ids.len() != count {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        db.car.retain(|c| !ids.contains(&c.id.unwrap()));
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use traits::CarStorage;
+
+    async fn get_collection(names: &[&str]) -> CarCollection {
+        let c = CarCollection::new(Arc::new(providers::MemoryDbProvider::new()));
+        c.add(
+            names
+                .iter()
+                .map(|n| car::Car {
+                    id: None,
+                    car_name: n.to_string(),
+                })
+                .collect(),
+        )
+        .await
+        .unwrap();
+        c
+    }
+
+    fn get_page(limit: Option<i64>, order_by: Option<&str>) -> commands::common::Page {
+        commands::common::Page {
+            limit: limit,
+            order_by: order_by.map(|o| o.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn add_change_remove() {
+        let c = get_collection(&["audi", "bmw"]).await;
+        let (items, _) = c
+            .get(Some(vec![2]), None, &get_page(None, None))
+            .await
+            .unwrap();
+        assert_eq!(items[0].car_name, "bmw");
+        let result = c
+            .change(vec![car::Car {
+                id: Some(2),
+                car_name: "volvo".to_string(),
+            }])
+            .await
+            .unwrap();
+        assert_eq!(result, errors::ErrorCode::ReplyOk);
+        let (items, _) = c
+            .get(Some(vec![2]), None, &get_page(None, None))
+            .await
+            .unwrap();
+        assert_eq!(items[0].car_name, "volvo");
+        assert_eq!(
+            c.remove(vec![1, 3]).await.unwrap(),
+            errors::ErrorCode::NotFoundError
+        );
+        assert_eq!(c.remove(vec![1]).await.unwrap(), errors::ErrorCode::ReplyOk);
+        let (items, page_info) = c.get(None, None, &get_page(None, None)).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(page_info.total, 1);
+    }
+
+    #[tokio::test]
+    async fn change_unknown_id_changes_nothing() {
+        let c = get_collection(&["audi"]).await;
+        let result = c
+            .change(vec![
+                car::Car {
+                    id: Some(1),
+                    car_name: "bmw".to_string(),
+                },
+                car::Car {
+                    id: Some(5),
+                    car_name: "volvo".to_string(),
+                },
+            ])
+            .await
+            .unwrap();
+        assert_eq!(result, errors::ErrorCode::NotFoundError);
+        let (items, _) = c.get(None, None, &get_page(None, None)).await.unwrap();
+        assert_eq!(items[0].car_name, "audi");
+    }
+
+    #[tokio::test]
+    async fn walk_pages_by_offset() {
+        let c = get_collection(&["a", "b", "c", "d", "e"]).await;
+        let mut offset: Option<i64> = None;
+        let mut names = Vec::<String>::new();
+        loop {
+            let page = commands::common::Page {
+                offset: offset,
+                ..get_page(Some(2), Some("car_name desc"))
+            };
+            let (items, page_info) = c.get(None, None, &page).await.unwrap();
+            assert_eq!(page_info.total, 5);
+            names.extend(items.into_iter().map(|i| i.car_name));
+            offset = page_info.next_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+        assert_eq!(names, vec!["e", "d", "c", "b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn filter_order_and_page() {
+        let c = get_collection(&["audi", "bmw", "alfa romeo", "aston martin"]).await;
+        let (items, page_info) = c
+            .get(
+                None,
+                Some("car_name like 'a%' and id > 1".to_string()),
+                &get_page(Some(1), Some("car_name desc")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page_info.total, 2);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].car_name, "aston martin");
+        assert!(c
+            .get(
+                None,
+                Some("name = 'bmw'".to_string()),
+                &get_page(None, None)
+            )
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,453 @@
+use super::super::{
+    commands, connectors,
+    entities::executor::{self, RECEIVED_ASYNC_COMMAND_ORDER, SENDED_ASYNC_COMMAND_ORDER},
+    errors,
+    filters::{self, FilterValue},
+    providers, traits,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+// commands added before are expired if not completed or expired yet
+fn is_expired(state: &str, added_at: &DateTime<Utc>, before: &DateTime<Utc>) -> bool {
+    state != executor::CommandSystemState::Completed.to_string()
+        && state != executor::CommandSystemState::Expired.to_string()
+        && added_at < before
+}
+
+pub struct SendedAsyncCommandCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl SendedAsyncCommandCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> SendedAsyncCommandCollection {
+        SendedAsyncCommandCollection {
+            data_provider: data_provider,
+        }
+    }
+
+    fn get_value(item: &executor::SendedAsyncCommand, column: &str) -> Option<FilterValue> {
+        match column {
+            "id" => Some(FilterValue::Str(item.id.clone())),
+            "object_type" => Some(FilterValue::Str(item.object_type.clone())),
+            "service_name" => Some(FilterValue::Str(item.service_name.clone())),
+            "state" => Some(FilterValue::Str(item.state.clone())),
+            "added_at" => Some(FilterValue::DateTime(item.added_at)),
+            "state_changed_at" => Some(FilterValue::DateTime(item.state_changed_at)),
+            _ => None,
+        }
+    }
+
+    // state history has one row by state as sql primary key
+    fn add_history(
+        item: &mut executor::SendedAsyncCommand,
+        state: &str,
+        added_at: DateTime<Utc>,
+    ) -> bool {
+        let history = item.history.get_or_insert_with(Vec::new);
+        if history.iter().any(|h| h.state == state) {
+            return false;
+        }
+        history.push(executor::SendedAsyncCommandHistory {
+            command_id: None,
+            state: state.to_string(),
+            added_at: added_at,
+        });
+        true
+    }
+}
+
+#[async_trait]
+impl traits::SendedAsyncCommandStorage for SendedAsyncCommandCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<String>>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::SendedAsyncCommand>, filters::PageInfo)> {
+        let paging = filters::Paging::parse(page, SENDED_ASYNC_COMMAND_ORDER, &["id"])?;
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<executor::SendedAsyncCommand> = db
+            .sended_async_command
+            .iter()
+            .filter(|item| ids.as_ref().map_or(true, |ids| ids.contains(&item.id)))
+            .map(|item| executor::SendedAsyncCommand {
+                history: item.history.clone().filter(|h| h.len() > 0),
+                ..item.clone()
+            })
+            .collect();
+        Ok(super::select(
+            items,
+            &None,
+            &paging,
+            &["added_at", "object_type"],
+            SendedAsyncCommandCollection::get_value,
+        ))
+    }
+
+    async fn add(
+        &self,
+        items: Vec<executor::SendedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        let mut db = self.data_provider.db.lock().await;
+        let mut ids = Vec::<String>::new();
+        for item in &items {
+            if ids.contains(&item.id) || db.sended_async_command.iter().any(|c| c.id == item.id) {
+                error!(
+                    "add_sended_async_commands memory insert: duplicate {}",
+                    item.id
+                );
+                return Ok((errors::ErrorCode::DatabaseError, None));
+            }
+            ids.push(item.id.clone());
+        }
+        let now = Utc::now();
+        for item in items {
+            db.sended_async_command.push(executor::SendedAsyncCommand {
+                added_at: now,
+                state_changed_at: now,
+                reply_body: None,
+                ..item
+            });
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    async fn complete(
+        &self,
+        id: String,
+        reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let state = executor::CommandSystemState::Completed.to_string();
+        let mut db = self.data_provider.db.lock().await;
+        match db
+            .sended_async_command
+            .iter_mut()
+            .find(|c| c.id == id && c.state != state)
+        {
+            Some(item) => {
+                let now = Utc::now();
+                if !SendedAsyncCommandCollection::add_history(item, &state, now) {
+                    error!(
+                        "complete_sended_async_commands memory history insert: duplicate {}",
+                        id
+                    );
+                    return Ok(errors::ErrorCode::DatabaseError);
+                }
+                item.state = state;
+                item.reply_body = Some(reply_body);
+                item.state_changed_at = now;
+                Ok(errors::ErrorCode::ReplyOk)
+            }
+            None => Ok(errors::ErrorCode::NotFoundError),
+        }
+    }
+
+    // mark commands not completed in time as expired
+    async fn expire(&self, wait_in_hours: u16) -> connectors::Result<errors::ErrorCode> {
+        let expired = executor::CommandSystemState::Expired.to_string();
+        let now = Utc::now();
+        let before = now - Duration::hours(wait_in_hours as i64);
+        let mut db = self.data_provider.db.lock().await;
+        let mut count = 0;
+        for item in db
+            .sended_async_command
+            .iter_mut()
+            .filter(|c| is_expired(&c.state, &c.added_at, &before))
+        {
+            SendedAsyncCommandCollection::add_history(item, &expired, now);
+            item.state = expired.clone();
+            item.state_changed_at = now;
+            count += 1;
+        }
+        if count > 0 {
+            warn!("expire_sended_async_commands: {} expired", count);
+        }
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .sended_async_command
+            .iter()
+            .filter(|c| ids.contains(&c.id))
+            .count();
+        if ids.len() != count {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        db.sended_async_command.retain(|c| !ids.contains(&c.id));
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
+
+pub struct ReceivedAsyncCommandCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl ReceivedAsyncCommandCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> ReceivedAsyncCommandCollection {
+        ReceivedAsyncCommandCollection {
+            data_provider: data_provider,
+        }
+    }
+
+    fn get_value(item: &executor::ReceivedAsyncCommand, column: &str) -> Option<FilterValue> {
+        match column {
+            "id" => Some(FilterValue::Str(item.id.clone())),
+            "object_type" => Some(FilterValue::Str(item.object_type.clone())),
+            "service_name" => Some(FilterValue::Str(item.service_name.clone())),
+            "state" => Some(FilterValue::Str(item.state.clone())),
+            "proto" => Some(FilterValue::Str(item.proto.clone())),
+            "added_at" => Some(FilterValue::DateTime(item.added_at)),
+            "state_changed_at" => Some(FilterValue::DateTime(item.state_changed_at)),
+            _ => None,
+        }
+    }
+
+    // state history has one row by state as sql primary key
+    fn add_history(
+        item: &mut executor::ReceivedAsyncCommand,
+        state: &str,
+        added_at: DateTime<Utc>,
+    ) -> bool {
+        let history = item.history.get_or_insert_with(Vec::new);
+        if history.iter().any(|h| h.state == state) {
+            return false;
+        }
+        history.push(executor::ReceivedAsyncCommandHistory {
+            command_id: None,
+            state: state.to_string(),
+            added_at: added_at,
+        });
+        true
+    }
+
+    // db lock is held from count to insert, with max_pending none queue is not limited
+    async fn insert(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+        max_pending: Option<i64>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        let mut db = self.data_provider.db.lock().await;
+        if let Some(max) = max_pending {
+            let completed = executor::CommandSystemState::Completed.to_string();
+            let expired = executor::CommandSystemState::Expired.to_string();
+            let pending = db
+                .received_async_command
+                .iter()
+                .filter(|c| c.state != completed && c.state != expired)
+                .count();
+            if (pending + items.len()) as i64 > max {
+                return Ok((errors::ErrorCode::TooManyRequestsError, None));
+            }
+        }
+        let mut ids = Vec::<String>::new();
+        for item in &items {
+            if ids.contains(&item.id) || db.received_async_command.iter().any(|c| c.id == item.id) {
+                error!(
+                    "add_received_async_commands memory insert: duplicate {}",
+                    item.id
+                );
+                return Ok((errors::ErrorCode::DatabaseError, None));
+            }
+            ids.push(item.id.clone());
+        }
+        let now = Utc::now();
+        for item in items {
+            db.received_async_command
+                .push(executor::ReceivedAsyncCommand {
+                    added_at: now,
+                    state_changed_at: now,
+                    ..item
+                });
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+}
+
+#[async_trait]
+impl traits::ReceivedAsyncCommandStorage for ReceivedAsyncCommandCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<String>>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::ReceivedAsyncCommand>, filters::PageInfo)> {
+        let paging = filters::Paging::parse(page, RECEIVED_ASYNC_COMMAND_ORDER, &["id"])?;
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<executor::ReceivedAsyncCommand> = db
+            .received_async_command
+            .iter()
+            .filter(|item| ids.as_ref().map_or(true, |ids| ids.contains(&item.id)))
+            .map(|item| executor::ReceivedAsyncCommand {
+                history: item.history.clone().filter(|h| h.len() > 0),
+                ..item.clone()
+            })
+            .collect();
+        Ok(super::select(
+            items,
+            &None,
+            &paging,
+            &["added_at", "object_type"],
+            ReceivedAsyncCommandCollection::get_value,
+        ))
+    }
+
+    async fn add(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        self.insert(items, None).await
+    }
+
+    async fn add_within_limit(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+        max_pending: i64,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        self.insert(items, Some(max_pending)).await
+    }
+
+    // set state of commands and add state history, state already set is not changed
+    async fn change_state(
+        &self,
+        state: String,
+        ids: Vec<String>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<executor::AsyncCommandState>>)> {
+        if state == executor::CommandSystemState::Initial.to_string()
+            || state == executor::CommandSystemState::Completed.to_string()
+            || state == executor::CommandSystemState::Expired.to_string()
+        {
+            return Err(errors::ErrorKind::UnknownAsyncCommandState.into());
+        }
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .received_async_command
+            .iter()
+            .filter(|c| ids.contains(&c.id))
+            .count();
+        if ids.len() != count {
+            return Ok((errors::ErrorCode::NotFoundError, None));
+        }
+        if db.received_async_command.iter().any(|c| {
+            ids.contains(&c.id)
+                && c.state != state
+                && c.history
+                    .as_ref()
+                    .map_or(false, |h| h.iter().any(|h| h.state == state))
+        }) {
+            error!("change_state_received_async_commands memory history insert: duplicate");
+            return Ok((errors::ErrorCode::DatabaseError, None));
+        }
+        let now = Utc::now();
+        let mut items = Vec::<executor::AsyncCommandState>::new();
+        for item in db
+            .received_async_command
+            .iter_mut()
+            .filter(|c| ids.contains(&c.id))
+        {
+            if item.state != state {
+                ReceivedAsyncCommandCollection::add_history(item, &state, now);
+                item.state = state.clone();
+                item.state_changed_at = now;
+            }
+            items.push(executor::AsyncCommandState {
+                id: item.id.clone(),
+                state: item.state.clone(),
+                state_changed_at: item.state_changed_at,
+            });
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(items)))
+    }
+
+    async fn complete(
+        &self,
+        id: String,
+        reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let state = executor::CommandSystemState::Completed.to_string();
+        let mut db = self.data_provider.db.lock().await;
+        match db
+            .received_async_command
+            .iter_mut()
+            .find(|c| c.id == id && c.state != state)
+        {
+            Some(item) => {
+                let now = Utc::now();
+                if !ReceivedAsyncCommandCollection::add_history(item, &state, now) {
+                    error!(
+                        "complete_received_async_commands memory history insert: duplicate {}",
+                        id
+                    );
+                    return Ok(errors::ErrorCode::DatabaseError);
+                }
+                item.state = state;
+                item.reply_body = reply_body;
+                item.state_changed_at = now;
+                Ok(errors::ErrorCode::ReplyOk)
+            }
+            None => Ok(errors::ErrorCode::NotFoundError),
+        }
+    }
+
+    async fn get_by_state(
+        &self,
+        state: String,
+    ) -> connectors::Result<Vec<executor::ReceivedAsyncCommand>> {
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<executor::ReceivedAsyncCommand> = db
+            .received_async_command
+            .iter()
+            .filter(|c| c.state == state)
+            .map(|c| executor::ReceivedAsyncCommand {
+                history: None,
+                ..c.clone()
+            })
+            .collect();
+        Ok(super::select(
+            items,
+            &None,
+            &None,
+            &["added_at", "object_type"],
+            ReceivedAsyncCommandCollection::get_value,
+        )
+        .0)
+    }
+
+    // mark commands not completed in time as expired
+    async fn expire(&self, wait_in_hours: u16) -> connectors::Result<errors::ErrorCode> {
+        let expired = executor::CommandSystemState::Expired.to_string();
+        let now = Utc::now();
+        let before = now - Duration::hours(wait_in_hours as i64);
+        let mut db = self.data_provider.db.lock().await;
+        let mut count = 0;
+        for item in db
+            .received_async_command
+            .iter_mut()
+            .filter(|c| is_expired(&c.state, &c.added_at, &before))
+        {
+            ReceivedAsyncCommandCollection::add_history(item, &expired, now);
+            item.state = expired.clone();
+            item.state_changed_at = now;
+            count += 1;
+        }
+        if count > 0 {
+            warn!("expire_received_async_commands: {} expired", count);
+        }
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .received_async_command
+            .iter()
+            .filter(|c| ids.contains(&c.id))
+            .count();
+        if ids.len() != count {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        db.received_async_command.retain(|c| !ids.contains(&c.id));
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
@@ -0,0 +1,113 @@
+// In-memory storage with same semantics as sql collections, to run router or demo service
+// without database. Rows are filtered, ordered and paged by column names of sql views,
+// so filter fields and page order of collections are reused as is.
+use super::filters;
+use std::cmp::Ordering;
+
+pub mod car;
+pub mod executor;
+pub mod publisher;
+pub mod route;
+pub mod usr;
+
+// filter, order and page rows as sql select does, view_order is order of sql view without page
+pub fn select<T, F>(
+    items: Vec<T>,
+    filter: &Option<filters::Filter>,
+    paging: &Option<filters::Paging>,
+    view_order: &[&str],
+    value: F,
+) -> (Vec<T>, filters::PageInfo)
+where
+    F: Fn(&T, &str) -> Option<filters::FilterValue>,
+{
+    let mut items: Vec<T> = match filter {
+        Some(f) => items
+            .into_iter()
+            .filter(|item| f.condition.matches(&|column: &str| value(item, column)))
+            .collect(),
+        None => items,
+    };
+    let order: Vec<(String, bool)> = match paging {
+        Some(p) => p.order.clone(),
+        None => view_order.iter().map(|c| (c.to_string(), false)).collect(),
+    };
+    items.sort_by(|a, b| {
+        for (column, desc) in &order {
+            let o = compare(&value(a, column), &value(b, column));
+            if o != Ordering::Equal {
+                return if *desc { o.reverse() } else { o };
+            }
+        }
+        Ordering::Equal
+    });
+    let total = items.len() as i64;
+    if let Some(p) = paging {
+        items = items
+            .into_iter()
+            .skip(p.offset as usize)
+            .take(p.limit.map_or(usize::MAX, |l| l as usize))
+            .collect();
+    }
+    let page_info = filters::Paging::get_page_info(paging, items.len(), total);
+    (items, page_info)
+}
+
+// nulls last, as in postgres
+fn compare(a: &Option<filters::FilterValue>, b: &Option<filters::FilterValue>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::commands;
+    use super::*;
+    use filters::{FieldType, FilterValue};
+
+    const FIELDS: &[filters::FilterField] = &[
+        ("id", "id", FieldType::Int),
+        ("name", "name", FieldType::Str),
+    ];
+
+    fn get_value(item: &(i64, Option<&str>), column: &str) -> Option<FilterValue> {
+        match column {
+            "id" => Some(FilterValue::Int(item.0)),
+            "name" => item.1.map(|n| FilterValue::Str(n.to_string())),
+            _ => None,
+        }
+    }
+
+    fn get_items() -> Vec<(i64, Option<&'static str>)> {
+        vec![(1, Some("b")), (2, None), (3, Some("a")), (4, Some("c"))]
+    }
+
+    #[test]
+    fn select_in_view_order_without_page() {
+        let (items, page_info) = select(get_items(), &None, &None, &["name", "id"], get_value);
+        let ids: Vec<i64> = items.iter().map(|i| i.0).collect();
+        assert_eq!(ids, vec![3, 1, 4, 2]);
+        assert_eq!(page_info.total, 4);
+    }
+
+    #[test]
+    fn select_filtered_page() {
+        let filter = filters::Filter::parse(Some("name <> 'c'".to_string()), FIELDS, 1).unwrap();
+        let page = commands::common::Page {
+            limit: Some(1),
+            offset: Some(1),
+            order_by: Some("id desc".to_string()),
+            ..Default::default()
+        };
+        let paging = filters::Paging::parse(&page, FIELDS, &["id"]).unwrap();
+        let (items, page_info) = select(get_items(), &filter, &paging, &[], get_value);
+        // null never matches, so row 2 is filtered out
+        assert_eq!(page_info.total, 2);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].0, 1);
+    }
+}
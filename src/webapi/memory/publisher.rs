@@ -0,0 +1,381 @@
+use super::super::{
+    commands, connectors,
+    entities::publisher::{self, DEAD_LETTER_EVENT_FILTER},
+    errors,
+    filters::{self, FilterValue},
+    providers, traits,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+// write events in same lock as entity change, drained later by event publisher
+pub fn add_outbox_events(db: &mut providers::MemoryDb, items: &Vec<publisher::OutboxEvent>) {
+    let now = Utc::now();
+    for item in items {
+        let id = db.next_id("event_outbox");
+        db.event_outbox.push(publisher::OutboxEvent {
+            id: Some(id),
+            added_at: now,
+            ..item.clone()
+        });
+    }
+}
+
+pub struct EventOutboxCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl EventOutboxCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> EventOutboxCollection {
+        EventOutboxCollection {
+            data_provider: data_provider,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::EventOutboxStorage for EventOutboxCollection {
+    // oldest first
+    async fn get(&self, limit: i64) -> connectors::Result<Vec<publisher::OutboxEvent>> {
+        let db = self.data_provider.db.lock().await;
+        Ok(db
+            .event_outbox
+            .iter()
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    // events without entity change
+    async fn add(
+        &self,
+        items: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        add_outbox_events(&mut db, &items);
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .event_outbox
+            .iter()
+            .filter(|e| ids.contains(&e.id.unwrap()))
+            .count();
+        db.event_outbox.retain(|e| !ids.contains(&e.id.unwrap()));
+        if ids.len() == count {
+            Ok(errors::ErrorCode::ReplyOk)
+        } else {
+            Ok(errors::ErrorCode::NotFoundError)
+        }
+    }
+}
+
+pub struct UndeliveredEventCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl UndeliveredEventCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> UndeliveredEventCollection {
+        UndeliveredEventCollection {
+            data_provider: data_provider,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::UndeliveredEventStorage for UndeliveredEventCollection {
+    // due for next attempt, earliest first
+    async fn get_due(&self, limit: i64) -> connectors::Result<Vec<publisher::UndeliveredEvent>> {
+        let now = Utc::now();
+        let db = self.data_provider.db.lock().await;
+        let mut items: Vec<publisher::UndeliveredEvent> = db
+            .undelivered_event
+            .iter()
+            .filter(|e| e.next_attempt_at <= now)
+            .cloned()
+            .collect();
+        items.sort_by_key(|e| e.next_attempt_at);
+        items.truncate(limit as usize);
+        Ok(items)
+    }
+
+    async fn add(
+        &self,
+        items: Vec<publisher::UndeliveredEvent>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        let mut db = self.data_provider.db.lock().await;
+        let mut ids = Vec::<i32>::new();
+        let now = Utc::now();
+        for item in items {
+            let id = db.next_id("undelivered_event");
+            db.undelivered_event.push(publisher::UndeliveredEvent {
+                id: Some(id),
+                added_at: now,
+                ..item
+            });
+            ids.push(id);
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    async fn postpone(
+        &self,
+        id: i32,
+        last_error: String,
+        next_attempt_at: DateTime<Utc>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        match db.undelivered_event.iter_mut().find(|e| e.id == Some(id)) {
+            Some(item) => {
+                item.attempt += 1;
+                item.last_error = last_error;
+                item.next_attempt_at = next_attempt_at;
+                Ok(errors::ErrorCode::ReplyOk)
+            }
+            None => Ok(errors::ErrorCode::NotFoundError),
+        }
+    }
+
+    async fn move_to_dead_letter(
+        &self,
+        id: i32,
+        last_error: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        match db.undelivered_event.iter().position(|e| e.id == Some(id)) {
+            Some(i) => {
+                if db.dead_letter_event.iter().any(|e| e.id == Some(id)) {
+                    error!(
+                        "move_undelivered_events memory dead letter insert: duplicate {}",
+                        id
+                    );
+                    return Ok(errors::ErrorCode::DatabaseError);
+                }
+                let item = db.undelivered_event.remove(i);
+                db.dead_letter_event.push(publisher::UndeliveredEvent {
+                    attempt: item.attempt + 1,
+                    last_error: last_error,
+                    next_attempt_at: Utc::now(),
+                    ..item
+                });
+                Ok(errors::ErrorCode::ReplyOk)
+            }
+            None => Ok(errors::ErrorCode::NotFoundError),
+        }
+    }
+
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .undelivered_event
+            .iter()
+            .filter(|e| ids.contains(&e.id.unwrap()))
+            .count();
+        db.undelivered_event
+            .retain(|e| !ids.contains(&e.id.unwrap()));
+        if ids.len() == count {
+            Ok(errors::ErrorCode::ReplyOk)
+        } else {
+            Ok(errors::ErrorCode::NotFoundError)
+        }
+    }
+}
+
+pub struct DeadLetterEventCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl DeadLetterEventCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> DeadLetterEventCollection {
+        DeadLetterEventCollection {
+            data_provider: data_provider,
+        }
+    }
+
+    fn get_value(item: &publisher::UndeliveredEvent, column: &str) -> Option<FilterValue> {
+        match column {
+            "id" => item.id.map(|v| FilterValue::Int(v as i64)),
+            "correlation_id" => Some(FilterValue::Str(item.correlation_id.clone())),
+            "object_type" => Some(FilterValue::Str(item.object_type.clone())),
+            "service_name" => Some(FilterValue::Str(item.service_name.clone())),
+            "proto" => Some(FilterValue::Str(item.proto.clone())),
+            "attempt" => Some(FilterValue::Int(item.attempt as i64)),
+            "added_at" => Some(FilterValue::DateTime(item.added_at)),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::DeadLetterEventStorage for DeadLetterEventCollection {
+    async fn get(
+        &self,
+        ids: Option<Vec<i32>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<publisher::UndeliveredEvent>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, DEAD_LETTER_EVENT_FILTER, 1)?;
+        let paging = filters::Paging::parse(page, DEAD_LETTER_EVENT_FILTER, &["id"])?;
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<publisher::UndeliveredEvent> = db
+            .dead_letter_event
+            .iter()
+            .filter(|e| {
+                ids.as_ref()
+                    .map_or(true, |ids| ids.contains(&e.id.unwrap()))
+            })
+            .cloned()
+            .collect();
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &[],
+            DeadLetterEventCollection::get_value,
+        ))
+    }
+
+    // move back to undelivered events for new attempts
+    async fn replay(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .dead_letter_event
+            .iter()
+            .filter(|e| ids.contains(&e.id.unwrap()))
+            .count();
+        if ids.len() != count {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        if db
+            .undelivered_event
+            .iter()
+            .any(|e| ids.contains(&e.id.unwrap()))
+        {
+            error!("replay_dead_letter_events memory undelivered insert: duplicate id");
+            return Ok(errors::ErrorCode::DatabaseError);
+        }
+        let now = Utc::now();
+        let (items, rest): (
+            Vec<publisher::UndeliveredEvent>,
+            Vec<publisher::UndeliveredEvent>,
+        ) = db
+            .dead_letter_event
+            .drain(..)
+            .partition(|e| ids.contains(&e.id.unwrap()));
+        db.dead_letter_event = rest;
+        for item in items {
+            db.undelivered_event.push(publisher::UndeliveredEvent {
+                attempt: 0,
+                next_attempt_at: now,
+                ..item
+            });
+        }
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use traits::{DeadLetterEventStorage, EventOutboxStorage, UndeliveredEventStorage};
+
+    fn get_event(next_attempt_at: DateTime<Utc>) -> publisher::UndeliveredEvent {
+        publisher::UndeliveredEvent {
+            id: None,
+            correlation_id: "1".to_string(),
+            object_type: "OnCarChange".to_string(),
+            service_name: "car".to_string(),
+            proto: "http".to_string(),
+            path: "/event/on-car-change".to_string(),
+            body: "[]".to_string(),
+            attempt: 1,
+            last_error: "error".to_string(),
+            next_attempt_at: next_attempt_at,
+            added_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn outbox_events_in_added_order() {
+        let dp = Arc::new(providers::MemoryDbProvider::new());
+        let c = EventOutboxCollection::new(dp.clone());
+        {
+            let mut db = dp.db.lock().await;
+            let items: Vec<publisher::OutboxEvent> = (0..3)
+                .map(|i| publisher::OutboxEvent {
+                    id: None,
+                    correlation_id: i.to_string(),
+                    object_type: "OnCarChange".to_string(),
+                    body: "[]".to_string(),
+                    added_at: Utc::now(),
+                })
+                .collect();
+            add_outbox_events(&mut db, &items);
+        }
+        let items = c.get(2).await.unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, Some(1));
+        assert_eq!(
+            c.remove(vec![1, 2]).await.unwrap(),
+            errors::ErrorCode::ReplyOk
+        );
+        let items = c.get(10).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].correlation_id, "2");
+    }
+
+    #[tokio::test]
+    async fn undelivered_event_lifecycle() {
+        let dp = Arc::new(providers::MemoryDbProvider::new());
+        let undelivered = UndeliveredEventCollection::new(dp.clone());
+        let dead_letter = DeadLetterEventCollection::new(dp.clone());
+        let later = Utc::now() + chrono::Duration::seconds(60);
+        let (_, ids) = undelivered
+            .add(vec![get_event(Utc::now()), get_event(later)])
+            .await
+            .unwrap();
+        assert_eq!(ids, Some(vec![1, 2]));
+        let items = undelivered.get_due(10).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, Some(1));
+        // postponed event is not due until next attempt
+        undelivered
+            .postpone(1, "timeout".to_string(), later)
+            .await
+            .unwrap();
+        assert!(undelivered.get_due(10).await.unwrap().is_empty());
+        assert_eq!(
+            undelivered
+                .move_to_dead_letter(1, "refused".to_string())
+                .await
+                .unwrap(),
+            errors::ErrorCode::ReplyOk
+        );
+        let (items, _) = dead_letter
+            .get(None, None, &commands::common::Page::default())
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].attempt, 3);
+        assert_eq!(items[0].last_error, "refused");
+        // replayed event is due at once with attempts from start
+        assert_eq!(
+            dead_letter.replay(vec![1]).await.unwrap(),
+            errors::ErrorCode::ReplyOk
+        );
+        let items = undelivered.get_due(10).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].attempt, 0);
+        assert_eq!(
+            dead_letter.replay(vec![1]).await.unwrap(),
+            errors::ErrorCode::NotFoundError
+        );
+        assert_eq!(
+            undelivered.remove(vec![1, 2]).await.unwrap(),
+            errors::ErrorCode::ReplyOk
+        );
+    }
+}
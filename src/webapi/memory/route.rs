@@ -0,0 +1,400 @@
+use super::super::{
+    commands, connectors,
+    entities::{
+        publisher,
+        route::{
+            self, ROUTE_FILTER, SERVICE_COMMAND_FILTER, SERVICE_EVENT_FILTER, SERVICE_FILTER,
+            SERVICE_SUBSCRIPTION_FILTER,
+        },
+    },
+    errors,
+    filters::{self, FilterValue},
+    providers, traits,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct RouteCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl RouteCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> RouteCollection {
+        RouteCollection {
+            data_provider: data_provider,
+        }
+    }
+
+    fn is_selected(services: &Option<Vec<String>>, item: &route::Route) -> bool {
+        services
+            .as_ref()
+            .map_or(true, |s| s.contains(item.service_name.as_ref().unwrap()))
+    }
+
+    fn get_route_value(item: &route::Route, column: &str) -> Option<FilterValue> {
+        match column {
+            "name" => item.service_name.clone().map(FilterValue::Str),
+            "description" => Some(FilterValue::Str(item.description.clone())),
+            "priority" => Some(FilterValue::Int(item.priority as i64)),
+            _ => None,
+        }
+    }
+
+    fn get_service_value(item: &route::Service, column: &str) -> Option<FilterValue> {
+        match column {
+            "name" => Some(FilterValue::Str(item.name.clone())),
+            "description" => Some(FilterValue::Str(item.description.clone())),
+            "priority" => Some(FilterValue::Int(item.priority as i64)),
+            "state" => Some(FilterValue::Str(item.state.clone())),
+            "added_at" => Some(FilterValue::DateTime(item.added_at)),
+            _ => None,
+        }
+    }
+
+    fn get_command_value(item: &route::ServiceCommand, column: &str) -> Option<FilterValue> {
+        match column {
+            "service_name" => item.service_name.clone().map(FilterValue::Str),
+            "priority" => item.priority.map(|v| FilterValue::Int(v as i64)),
+            "object_type" => Some(FilterValue::Str(item.object_type.clone())),
+            "description" => Some(FilterValue::Str(item.description.clone())),
+            "exec_mode" => Some(FilterValue::Str(item.exec_mode.clone())),
+            "reply_type" => Some(FilterValue::Str(item.reply_type.clone())),
+            _ => None,
+        }
+    }
+
+    fn get_event_value(item: &route::ServiceEvent, column: &str) -> Option<FilterValue> {
+        match column {
+            "service_name" => item.service_name.clone().map(FilterValue::Str),
+            "object_type" => Some(FilterValue::Str(item.object_type.clone())),
+            "description" => Some(FilterValue::Str(item.description.clone())),
+            _ => None,
+        }
+    }
+
+    fn get_subscription_value(
+        item: &route::ServiceSubscription,
+        column: &str,
+    ) -> Option<FilterValue> {
+        match column {
+            "service_name" => item.service_name.clone().map(FilterValue::Str),
+            "object_type" => Some(FilterValue::Str(item.object_type.clone())),
+            _ => None,
+        }
+    }
+
+    fn get_path_value(item: &route::ServicePath, column: &str) -> Option<FilterValue> {
+        match column {
+            "service_name" => item.service_name.clone().map(FilterValue::Str),
+            "proto" => item.proto.clone().map(FilterValue::Str),
+            _ => None,
+        }
+    }
+
+    // route as stored in service tables, without values which are not stored
+    fn to_row(item: route::Route) -> route::Route {
+        let mut path = HashMap::<String, route::ServicePath>::new();
+        for (proto, p) in item.path.unwrap_or_default() {
+            path.insert(
+                proto.clone(),
+                route::ServicePath {
+                    service_name: None,
+                    proto: Some(proto),
+                    request: None,
+                    event: None,
+                    ..p
+                },
+            );
+        }
+        route::Route {
+            service_name: item.service_name,
+            description: item.description,
+            priority: item.priority,
+            command: item
+                .command
+                .into_iter()
+                .map(|c| route::ServiceCommand {
+                    service_name: None,
+                    priority: None,
+                    path: Some(c.path.unwrap_or_default()),
+                    state: c.state.filter(|s| s.len() > 0),
+                    ..c
+                })
+                .collect(),
+            event: item
+                .event
+                .into_iter()
+                .map(|e| route::ServiceEvent {
+                    service_name: None,
+                    ..e
+                })
+                .collect(),
+            subscription: item
+                .subscription
+                .into_iter()
+                .map(|s| route::ServiceSubscription {
+                    service_name: None,
+                    path: Some(s.path.unwrap_or_default()),
+                    ..s
+                })
+                .collect(),
+            path: Some(path),
+        }
+    }
+
+    // primary keys of service tables
+    fn has_duplicate(rows: &Vec<&route::Route>) -> bool {
+        let mut keys = Vec::<(&str, &str, &String)>::new();
+        for row in rows {
+            let name = row.service_name.as_ref().unwrap();
+            keys.push(("service", "", name));
+            for c in &row.command {
+                keys.push(("command", &c.object_type, name));
+            }
+            for e in &row.event {
+                keys.push(("event", &e.object_type, name));
+            }
+            for s in &row.subscription {
+                keys.push(("subscription", &s.object_type, name));
+            }
+        }
+        let count = keys.len();
+        keys.sort();
+        keys.dedup();
+        keys.len() != count
+    }
+}
+
+#[async_trait]
+impl traits::RouteStorage for RouteCollection {
+    async fn get_command(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceCommand>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, SERVICE_COMMAND_FILTER, 1)?;
+        let paging = filters::Paging::parse(
+            page,
+            SERVICE_COMMAND_FILTER,
+            &["service_name", "object_type"],
+        )?;
+        let db = self.data_provider.db.lock().await;
+        let mut items = Vec::<route::ServiceCommand>::new();
+        for (item, _) in db
+            .service
+            .iter()
+            .filter(|r| RouteCollection::is_selected(&services, &r.0))
+        {
+            for command in &item.command {
+                items.push(route::ServiceCommand {
+                    service_name: item.service_name.clone(),
+                    priority: Some(item.priority),
+                    ..command.clone()
+                });
+            }
+        }
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &["object_type", "priority"],
+            RouteCollection::get_command_value,
+        ))
+    }
+
+    async fn get_event(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceEvent>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, SERVICE_EVENT_FILTER, 1)?;
+        let paging =
+            filters::Paging::parse(page, SERVICE_EVENT_FILTER, &["service_name", "object_type"])?;
+        let db = self.data_provider.db.lock().await;
+        let mut items = Vec::<route::ServiceEvent>::new();
+        for (item, _) in db
+            .service
+            .iter()
+            .filter(|r| RouteCollection::is_selected(&services, &r.0))
+        {
+            for event in &item.event {
+                items.push(route::ServiceEvent {
+                    service_name: item.service_name.clone(),
+                    ..event.clone()
+                });
+            }
+        }
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &[],
+            RouteCollection::get_event_value,
+        ))
+    }
+
+    async fn get_subscription(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceSubscription>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, SERVICE_SUBSCRIPTION_FILTER, 1)?;
+        let paging = filters::Paging::parse(
+            page,
+            SERVICE_SUBSCRIPTION_FILTER,
+            &["service_name", "object_type"],
+        )?;
+        let db = self.data_provider.db.lock().await;
+        let mut items = Vec::<route::ServiceSubscription>::new();
+        for (item, _) in db
+            .service
+            .iter()
+            .filter(|r| RouteCollection::is_selected(&services, &r.0))
+        {
+            for subscription in &item.subscription {
+                items.push(route::ServiceSubscription {
+                    service_name: item.service_name.clone(),
+                    ..subscription.clone()
+                });
+            }
+        }
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &["object_type"],
+            RouteCollection::get_subscription_value,
+        ))
+    }
+
+    async fn get_service(
+        &self,
+        names: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Service>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, SERVICE_FILTER, 1)?;
+        let paging = filters::Paging::parse(page, SERVICE_FILTER, &["name"])?;
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<route::Service> = db
+            .service
+            .iter()
+            .filter(|r| RouteCollection::is_selected(&names, &r.0))
+            .map(|(item, added_at)| route::Service {
+                name: item.service_name.clone().unwrap(),
+                description: item.description.clone(),
+                priority: item.priority,
+                state: route::ServiceState::Unavailable.to_string(),
+                added_at: *added_at,
+            })
+            .collect();
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &["name"],
+            RouteCollection::get_service_value,
+        ))
+    }
+
+    async fn get_service_path(
+        &self,
+        services: Option<Vec<String>>,
+    ) -> connectors::Result<Vec<route::ServicePath>> {
+        let db = self.data_provider.db.lock().await;
+        let mut items = Vec::<route::ServicePath>::new();
+        for (item, _) in db
+            .service
+            .iter()
+            .filter(|r| RouteCollection::is_selected(&services, &r.0))
+        {
+            for path in item.path.as_ref().unwrap().values() {
+                items.push(route::ServicePath {
+                    service_name: item.service_name.clone(),
+                    ..path.clone()
+                });
+            }
+        }
+        Ok(super::select(
+            items,
+            &None,
+            &None,
+            &["service_name", "proto"],
+            RouteCollection::get_path_value,
+        )
+        .0)
+    }
+
+    async fn get(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Route>, filters::PageInfo)> {
+        let filter = filters::Filter::parse(filter, ROUTE_FILTER, 1)?;
+        let paging = filters::Paging::parse(page, ROUTE_FILTER, &["name"])?;
+        let db = self.data_provider.db.lock().await;
+        let items: Vec<route::Route> = db
+            .service
+            .iter()
+            .filter(|r| RouteCollection::is_selected(&services, &r.0))
+            .map(|r| r.0.clone())
+            .collect();
+        Ok(super::select(
+            items,
+            &filter,
+            &paging,
+            &["name"],
+            RouteCollection::get_route_value,
+        ))
+    }
+
+    async fn add(
+        &self,
+        items: Vec<route::Route>,
+        events: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        let mut db = self.data_provider.db.lock().await;
+        let rows: Vec<route::Route> = items.into_iter().map(RouteCollection::to_row).collect();
+        let mut all: Vec<&route::Route> = db.service.iter().map(|r| &r.0).collect();
+        all.extend(rows.iter());
+        if RouteCollection::has_duplicate(&all) {
+            error!("add_routes memory insert: duplicate key");
+            return Ok((errors::ErrorCode::DatabaseError, None));
+        }
+        super::publisher::add_outbox_events(&mut db, &events);
+        let ids: Vec<String> = rows
+            .iter()
+            .map(|r| r.service_name.clone().unwrap())
+            .collect();
+        let now = chrono::offset::Utc::now();
+        for row in rows {
+            db.service.push((row, now));
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    async fn remove(
+        &self,
+        services: Vec<String>,
+        events: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        let count = db
+            .service
+            .iter()
+            .filter(|r| services.contains(r.0.service_name.as_ref().unwrap()))
+            .count();
+        if services.len() != count {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        super::publisher::add_outbox_events(&mut db, &events);
+        db.service
+            .retain(|r| !services.contains(r.0.service_name.as_ref().unwrap()));
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
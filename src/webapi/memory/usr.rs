@@ -0,0 +1,157 @@
+use super::super::{connectors, entities::usr, errors, providers, traits};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub struct UsrCollection {
+    data_provider: Arc<providers::MemoryDbProvider>,
+}
+
+impl UsrCollection {
+    pub fn new(data_provider: Arc<providers::MemoryDbProvider>) -> UsrCollection {
+        UsrCollection {
+            data_provider: data_provider,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::UsrStorage for UsrCollection {
+    async fn get(&self, ids: Option<Vec<i32>>) -> connectors::Result<Vec<usr::Usr>> {
+        let db = self.data_provider.db.lock().await;
+        Ok(db
+            .usr
+            .iter()
+            .filter(|item| ids.as_ref().map_or(true, |ids| ids.contains(&item.id)))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_by_name(&self, usr_name: &str) -> connectors::Result<Option<usr::Usr>> {
+        let db = self.data_provider.db.lock().await;
+        Ok(db
+            .usr
+            .iter()
+            .find(|item| item.usr_name == usr_name)
+            .cloned())
+    }
+
+    async fn add(
+        &self,
+        items: Vec<usr::Usr>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        let mut db = self.data_provider.db.lock().await;
+        for (i, item) in items.iter().enumerate() {
+            if db.usr.iter().any(|u| u.usr_name == item.usr_name)
+                || items[..i].iter().any(|u| u.usr_name == item.usr_name)
+            {
+                error!("add_usrs db insert: duplicate usr_name {}", item.usr_name);
+                return Ok((errors::ErrorCode::DatabaseError, None));
+            }
+        }
+        let mut ids = Vec::<i32>::new();
+        for mut item in items {
+            item.id = db.next_id("usr");
+            ids.push(item.id);
+            db.usr.push(item);
+        }
+        Ok((errors::ErrorCode::ReplyOk, Some(ids)))
+    }
+
+    async fn change_password(
+        &self,
+        usr_name: &str,
+        usr_password: &str,
+    ) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        match db.usr.iter_mut().find(|item| item.usr_name == usr_name) {
+            Some(item) => {
+                item.usr_password = usr_password.to_string();
+                Ok(errors::ErrorCode::ReplyOk)
+            }
+            None => Ok(errors::ErrorCode::NotFoundError),
+        }
+    }
+
+    async fn disable(&self, usr_names: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        let mut db = self.data_provider.db.lock().await;
+        if !usr_names
+            .iter()
+            .all(|n| db.usr.iter().any(|item| &item.usr_name == n))
+        {
+            return Ok(errors::ErrorCode::NotFoundError);
+        }
+        for item in db.usr.iter_mut() {
+            if usr_names.contains(&item.usr_name) {
+                item.is_disabled = true;
+            }
+        }
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::access;
+    use super::*;
+    use traits::UsrStorage;
+
+    fn get_usr(usr_name: &str) -> usr::Usr {
+        usr::Usr {
+            id: 0,
+            usr_name: usr_name.to_string(),
+            usr_password: String::new(),
+            is_disabled: false,
+            is_admin: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn seed_passwords_are_hashed() {
+        let c = UsrCollection::new(Arc::new(providers::MemoryDbProvider::new()));
+        let item = c.get_by_name("user1").await.unwrap().unwrap();
+        assert!(access::is_password_hash(&item.usr_password));
+        assert!(access::verify_password("pass1", &item.usr_password));
+    }
+
+    #[tokio::test]
+    async fn add_rejects_duplicate_name() {
+        let c = UsrCollection::new(Arc::new(providers::MemoryDbProvider::new()));
+        let (result, _) = c.add(vec![get_usr("user1")]).await.unwrap();
+        assert_eq!(result, errors::ErrorCode::DatabaseError);
+        let (result, _) = c
+            .add(vec![get_usr("user2"), get_usr("user2")])
+            .await
+            .unwrap();
+        assert_eq!(result, errors::ErrorCode::DatabaseError);
+        let (result, ids) = c.add(vec![get_usr("user2")]).await.unwrap();
+        assert_eq!(result, errors::ErrorCode::ReplyOk);
+        assert_eq!(ids, Some(vec![3]));
+    }
+
+    #[tokio::test]
+    async fn change_password_and_disable() {
+        let c = UsrCollection::new(Arc::new(providers::MemoryDbProvider::new()));
+        assert_eq!(
+            c.change_password("nobody", "hash").await.unwrap(),
+            errors::ErrorCode::NotFoundError
+        );
+        assert_eq!(
+            c.change_password("test", "hash").await.unwrap(),
+            errors::ErrorCode::ReplyOk
+        );
+        assert_eq!(
+            c.disable(vec!["test".to_string(), "nobody".to_string()])
+                .await
+                .unwrap(),
+            errors::ErrorCode::NotFoundError
+        );
+        assert!(!c.get_by_name("test").await.unwrap().unwrap().is_disabled);
+        assert_eq!(
+            c.disable(vec!["test".to_string()]).await.unwrap(),
+            errors::ErrorCode::ReplyOk
+        );
+        let item = c.get_by_name("test").await.unwrap().unwrap();
+        assert!(item.is_disabled);
+        assert_eq!(item.usr_password, "hash");
+    }
+}
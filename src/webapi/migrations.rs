@@ -0,0 +1,260 @@
+// Versioned schema migrations embedded in binary, applied on start by SqlDbProvider.
+// Each migration is applied once in own transaction and recorded in webapi.schema_migration,
+// statements of migration file are separated by line with single "/".
+// New migration is next numbered file in each backend folder plus entry in MIGRATIONS,
+// applied migration must not be changed. Note that mysql commits ddl implicitly.
+// Instances started together are serialized by lock of database session, sqlite is
+// used by single instance.
+use super::{connectors, errors};
+use sqlx::{pool::PoolConnection, Connection, Row};
+
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub script: &'static str,
+}
+
+#[cfg(feature = "postgres")]
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        script: include_str!("migrations/postgres/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "error",
+        script: include_str!("migrations/postgres/0002_error.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "usr",
+        script: include_str!("migrations/postgres/0003_usr.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "received_async_command",
+        script: include_str!("migrations/postgres/0004_received_async_command.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "usr_admin",
+        script: include_str!("migrations/postgres/0005_usr_admin.sql"),
+    },
+];
+
+#[cfg(feature = "mysql")]
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        script: include_str!("migrations/mysql/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "error",
+        script: include_str!("migrations/mysql/0002_error.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "usr",
+        script: include_str!("migrations/mysql/0003_usr.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "received_async_command",
+        script: include_str!("migrations/mysql/0004_received_async_command.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "usr_admin",
+        script: include_str!("migrations/mysql/0005_usr_admin.sql"),
+    },
+];
+
+#[cfg(feature = "sqlite")]
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        script: include_str!("migrations/sqlite/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "error",
+        script: include_str!("migrations/sqlite/0002_error.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "usr",
+        script: include_str!("migrations/sqlite/0003_usr.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "received_async_command",
+        script: include_str!("migrations/sqlite/0004_received_async_command.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "usr_admin",
+        script: include_str!("migrations/sqlite/0005_usr_admin.sql"),
+    },
+];
+
+// dev users and wildcard client access, applied on every start only if asked for
+#[cfg(feature = "postgres")]
+const DEV_SEED: &str = include_str!("migrations/postgres/dev_seed.sql");
+
+#[cfg(feature = "mysql")]
+const DEV_SEED: &str = include_str!("migrations/mysql/dev_seed.sql");
+
+#[cfg(feature = "sqlite")]
+const DEV_SEED: &str = include_str!("migrations/sqlite/dev_seed.sql");
+
+#[cfg(feature = "postgres")]
+const CREATE_TRACKING: &[&str] = &[
+    "CREATE SCHEMA IF NOT EXISTS webapi",
+    r#"CREATE TABLE IF NOT EXISTS webapi.schema_migration (
+        "version" int4 NOT NULL,
+        "name" text NOT NULL,
+        applied_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        CONSTRAINT schema_migration_pk PRIMARY KEY ("version")
+    )"#,
+];
+
+#[cfg(feature = "mysql")]
+const CREATE_TRACKING: &[&str] = &[r#"CREATE TABLE IF NOT EXISTS webapi.schema_migration (
+        `version` int NOT NULL,
+        `name` varchar(100) NOT NULL,
+        `applied_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        CONSTRAINT schema_migration_pk PRIMARY KEY (`version`)
+    ) ENGINE=InnoDB"#];
+
+#[cfg(feature = "sqlite")]
+const CREATE_TRACKING: &[&str] = &[r#"CREATE TABLE IF NOT EXISTS webapi.schema_migration (
+        "version" int4 NOT NULL,
+        "name" text NOT NULL,
+        applied_at datetime NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        CONSTRAINT schema_migration_pk PRIMARY KEY ("version")
+    )"#];
+
+#[cfg(feature = "postgres")]
+const INSERT_MIGRATION: &str =
+    r#"INSERT INTO webapi.schema_migration ( "version", "name" ) VALUES ( $1, $2 )"#;
+
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+const INSERT_MIGRATION: &str =
+    r#"INSERT INTO webapi.schema_migration ( `version`, `name` ) VALUES ( ?, ? )"#;
+
+// names of code are the source of truth, so changed names are updated on start
+#[cfg(feature = "postgres")]
+const UPSERT_ERROR: &str = r#"INSERT INTO webapi.error ( error_code, locale, error_name ) VALUES ( $1, $2, $3 )
+    ON CONFLICT ( error_code, locale ) DO UPDATE SET error_name = EXCLUDED.error_name"#;
+
+#[cfg(feature = "mysql")]
+const UPSERT_ERROR: &str = r#"INSERT INTO webapi.error ( error_code, locale, error_name ) VALUES ( ?, ?, ? )
+    ON DUPLICATE KEY UPDATE error_name = VALUES(error_name)"#;
+
+#[cfg(feature = "sqlite")]
+const UPSERT_ERROR: &str = r#"INSERT INTO webapi.error ( error_code, locale, error_name ) VALUES ( ?, ?, ? )
+    ON CONFLICT ( error_code, locale ) DO UPDATE SET error_name = excluded.error_name"#;
+
+#[cfg(feature = "postgres")]
+const LOCK: &str = "SELECT pg_advisory_lock(hashtext('webapi.schema_migration'))";
+#[cfg(feature = "postgres")]
+const UNLOCK: &str = "SELECT pg_advisory_unlock(hashtext('webapi.schema_migration'))";
+
+#[cfg(feature = "mysql")]
+const LOCK: &str = "SELECT GET_LOCK('webapi.schema_migration', 600)";
+#[cfg(feature = "mysql")]
+const UNLOCK: &str = "SELECT RELEASE_LOCK('webapi.schema_migration')";
+
+// apply not yet applied migrations in version order, returns count of applied
+pub async fn apply(pool: &connectors::DbPool) -> connectors::Result<usize> {
+    // lock belongs to session, so it is taken and released on same connection
+    let mut conn = pool.acquire().await?;
+    #[cfg(feature = "postgres")]
+    sqlx::query(LOCK).execute(&mut *conn).await?;
+    #[cfg(feature = "mysql")]
+    {
+        let locked: Option<i64> = sqlx::query(LOCK).fetch_one(&mut *conn).await?.get(0);
+        if locked != Some(1) {
+            error!("migration lock is not taken");
+            return Err(errors::ErrorKind::Database.into());
+        }
+    }
+    let result = apply_locked(&mut conn).await.map_err(|e| e.to_string());
+    #[cfg(any(feature = "postgres", feature = "mysql"))]
+    sqlx::query(UNLOCK).execute(&mut *conn).await?;
+    match result {
+        Ok(count) => Ok(count),
+        Err(e) => Err(errors::Error::new(errors::ErrorKind::Database)
+            .with_source(e)
+            .into()),
+    }
+}
+
+async fn apply_locked(conn: &mut PoolConnection<connectors::Db>) -> connectors::Result<usize> {
+    for statement in CREATE_TRACKING {
+        sqlx::query(statement).execute(&mut **conn).await?;
+    }
+    let applied: Vec<i32> = sqlx::query("SELECT version FROM webapi.schema_migration")
+        .fetch_all(&mut **conn)
+        .await?
+        .iter()
+        .map(|rec| rec.get(0))
+        .collect();
+    let mut count: usize = 0;
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+        info!("apply migration {} {}", migration.version, migration.name);
+        let mut tx = conn.begin().await?;
+        for statement in migration.script.split("\n/\n") {
+            if statement.trim().len() != 0 {
+                if let Err(e) = sqlx::query(statement).execute(&mut *tx).await {
+                    error!("migration {} db op: {}", migration.version, e);
+                    tx.rollback().await?;
+                    return Err(e.into());
+                }
+            }
+        }
+        sqlx::query(INSERT_MIGRATION)
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+// error table is seeded from error names of service after migrations
+pub async fn seed_errors(pool: &connectors::DbPool) -> connectors::Result<()> {
+    let mut tx = pool.begin().await?;
+    for item in errors::get_error_names() {
+        sqlx::query(UPSERT_ERROR)
+            .bind(&item.error_code)
+            .bind(&item.locale)
+            .bind(&item.error_name)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok({})
+}
+
+// local development and test fixture, inserts are skipped for existing rows
+pub async fn seed_dev(pool: &connectors::DbPool) -> connectors::Result<()> {
+    warn!("seed development users, not for production database");
+    let mut tx = pool.begin().await?;
+    for statement in DEV_SEED.split("\n/\n") {
+        if statement.trim().len() != 0 {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+    }
+    tx.commit().await?;
+    Ok({})
+}
@@ -0,0 +1,279 @@
+-- TABLES
+CREATE TABLE `car` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `car_name` varchar(100) NOT NULL,
+  CONSTRAINT car_pk PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+/
+CREATE UNIQUE INDEX car_car_name_idx USING BTREE ON `car` (car_name);
+/
+CREATE TABLE `usr` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `usr_name` varchar(100) NOT NULL,
+  `usr_password` varchar(100) NOT NULL,
+  CONSTRAINT usr_pk PRIMARY KEY (`id`),
+  CONSTRAINT usr_usr_name_key UNIQUE (usr_name)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `client_access` (
+  `source_service_name` varchar(100) NOT NULL,
+  `destination_service_name` varchar(100) NOT NULL,
+  `usr_name` varchar(100) NOT NULL,
+  `usr_password` varchar(100) NOT NULL,
+  CONSTRAINT client_access_pk PRIMARY KEY (source_service_name, destination_service_name)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `sended_async_command` (
+  `id` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `service_name` varchar(100) NOT NULL,
+  `state` varchar(100) NOT NULL,
+  `change_state_event` int NOT NULL,
+  `reply_body` text NULL,
+  `added_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  `state_changed_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT sended_async_command_pk PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+/
+CREATE INDEX sended_async_command_added_at_idx USING BTREE ON `sended_async_command` (added_at, object_type);
+/
+CREATE TABLE `sended_async_command_state_history` (
+  `command_id` varchar(100) NOT NULL,
+  `state` varchar(100) NOT NULL,
+  `added_at` timestamp NULL,
+  CONSTRAINT sended_async_command_state_history_pk PRIMARY KEY (command_id, `state`)
+) ENGINE=InnoDB;
+/
+CREATE INDEX sended_async_command_state_history_added_at_idx USING BTREE
+  ON `sended_async_command_state_history` (command_id, added_at);
+/
+CREATE TABLE `received_async_command` (
+  `id` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `service_name` varchar(100) NOT NULL,
+  `request_body` text NOT NULL,
+  `state` varchar(100) NOT NULL,
+  `change_state_event` int NOT NULL,
+  `reply_body` text NOT NULL,
+  `proto` varchar(100) NOT NULL,
+  `reply_to` varchar(1000) NOT NULL,
+  `added_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  `state_changed_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT received_async_command_pk PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+/
+CREATE INDEX received_async_command_added_at_idx USING BTREE ON `received_async_command` (added_at, object_type);
+/
+CREATE TABLE `received_async_command_state_history` (
+  `command_id` varchar(100) NOT NULL,
+  `state` varchar(100) NOT NULL,
+  `added_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT received_async_command_state_history_pk PRIMARY KEY (command_id, `state`)
+) ENGINE=InnoDB;
+/
+CREATE INDEX received_async_command_state_history_added_at_idx USING BTREE
+  ON `received_async_command_state_history` (command_id, added_at);
+/
+CREATE TABLE `event_outbox` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `correlation_id` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `body` text NOT NULL,
+  `added_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT event_outbox_pk PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `undelivered_event` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `correlation_id` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `service_name` varchar(100) NOT NULL,
+  `proto` varchar(100) NOT NULL,
+  `path` varchar(1000) NOT NULL,
+  `body` text NOT NULL,
+  `attempt` int NOT NULL,
+  `last_error` text NOT NULL,
+  `next_attempt_at` timestamp NOT NULL,
+  `added_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT undelivered_event_pk PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+/
+CREATE INDEX undelivered_event_next_attempt_at_idx USING BTREE ON `undelivered_event` (next_attempt_at);
+/
+CREATE TABLE `dead_letter_event` (
+  `id` int NOT NULL,
+  `correlation_id` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `service_name` varchar(100) NOT NULL,
+  `proto` varchar(100) NOT NULL,
+  `path` varchar(1000) NOT NULL,
+  `body` text NOT NULL,
+  `attempt` int NOT NULL,
+  `last_error` text NOT NULL,
+  `next_attempt_at` timestamp NOT NULL,
+  `added_at` timestamp NOT NULL,
+  CONSTRAINT dead_letter_event_pk PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service` (
+  `name` varchar(100) NOT NULL,
+  `description` text NOT NULL,
+  `priority` int NOT NULL,
+  `added_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT service_pk PRIMARY KEY (`name`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_path` (
+  `service_name` varchar(100) NOT NULL,
+  `proto` varchar(100) NOT NULL,
+  `helth` varchar(1000) NOT NULL,
+  `schema` varchar(1000) NOT NULL,
+  `reply_to` varchar(1000) NOT NULL,
+  `state` varchar(100) NOT NULL,
+  `error` varchar(1000) NOT NULL,
+  CONSTRAINT service_path_pk PRIMARY KEY (`service_name`, proto),
+  CONSTRAINT service_path_service_name_fk FOREIGN KEY (`service_name`) REFERENCES `service` (`name`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_command` (
+  `service_name` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `description` text NOT NULL,
+  `exec_mode` varchar(100) NOT NULL,
+  `reply_type` varchar(100) NOT NULL,
+  CONSTRAINT service_command_pk PRIMARY KEY (`service_name`, object_type),
+  CONSTRAINT service_command_service_name_fk FOREIGN KEY (`service_name`) REFERENCES `service` (`name`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_command_path` (
+  `service_name` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `proto` varchar(100) NOT NULL,
+  `to` varchar(1000) NOT NULL,
+  CONSTRAINT service_command_path_pk PRIMARY KEY (`service_name`, object_type, proto),
+  CONSTRAINT service_command_path_sn_ot_fk FOREIGN KEY (`service_name`, object_type)
+    REFERENCES `service_command` (`service_name`, object_type)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_command_state` (
+  `service_name` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `state` varchar(100) NOT NULL,
+  `description` text NOT NULL,
+  CONSTRAINT service_command_state_pk PRIMARY KEY (`service_name`, object_type, `state`),
+  CONSTRAINT service_command_state_sn_ot_fk FOREIGN KEY (`service_name`, object_type)
+    REFERENCES `service_command` (`service_name`, object_type)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_event` (
+  `service_name` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `description` text NOT NULL,
+  CONSTRAINT service_event_pk PRIMARY KEY (`service_name`, object_type),
+  CONSTRAINT service_event_service_name_fk FOREIGN KEY (`service_name`) REFERENCES `service` (`name`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_subscription` (
+  `service_name` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  CONSTRAINT service_subscription_pk PRIMARY KEY (`service_name`, object_type),
+  CONSTRAINT service_subscription_service_name_fk FOREIGN KEY (`service_name`) REFERENCES `service` (`name`)
+) ENGINE=InnoDB;
+/
+CREATE TABLE `service_subscription_path` (
+  `service_name` varchar(100) NOT NULL,
+  `object_type` varchar(100) NOT NULL,
+  `proto` varchar(100) NOT NULL,
+  `to` varchar(1000) NOT NULL,
+  CONSTRAINT service_subscription_path_pk PRIMARY KEY (`service_name`, object_type, proto),
+  CONSTRAINT service_subscription_path_sn_ot_fk FOREIGN KEY (`service_name`, object_type)
+    REFERENCES `service_subscription` (`service_name`, object_type)
+) ENGINE=InnoDB;
+/
+-- VIEWS
+CREATE OR REPLACE VIEW v_sended_async_command
+AS SELECT id,
+  object_type,
+  `service_name`,
+  `state`,
+  change_state_event,
+  added_at,
+  state_changed_at,
+  reply_body
+    FROM webapi.sended_async_command
+      ORDER BY added_at, object_type;
+/
+CREATE OR REPLACE VIEW v_sended_async_command_state_history
+AS SELECT command_id, `state`, added_at
+    FROM webapi.sended_async_command_state_history
+      ORDER BY command_id, added_at;
+/
+CREATE OR REPLACE VIEW v_received_async_command
+AS SELECT id,
+  object_type,
+  `service_name`,
+  request_body,
+  `state`,
+  change_state_event,
+  reply_body,
+  proto,
+  added_at,
+  state_changed_at,
+  reply_to
+    FROM webapi.received_async_command
+      ORDER BY added_at, object_type;
+/
+CREATE OR REPLACE VIEW v_received_async_command_state_history
+AS SELECT command_id, `state`, added_at
+    FROM webapi.received_async_command_state_history
+      ORDER BY command_id, added_at;
+/
+CREATE OR REPLACE VIEW v_service
+AS SELECT s.`name`, s.`description`, s.priority, 'Unavailable' as `state`, s.added_at
+  FROM webapi.service s
+    ORDER BY s.`name`;
+/
+CREATE OR REPLACE VIEW v_service_command
+AS SELECT c.service_name,
+  s.priority,
+  c.object_type,
+  c.description,
+  c.exec_mode,
+  c.reply_type
+  FROM webapi.service_command c
+    JOIN webapi.service s ON s.name = c.service_name
+      ORDER BY c.object_type, s.priority;
+/
+CREATE OR REPLACE VIEW v_service_path
+AS SELECT p.`service_name`, p.proto, p.helth, p.`schema`, p.reply_to, p.`state`, p.`error`
+  FROM webapi.service_path p
+    ORDER BY p.`service_name`, p.proto;
+/
+CREATE OR REPLACE VIEW v_service_command_path
+AS SELECT p.`service_name`, p.object_type, p.proto, p.`to`
+  FROM webapi.service_command_path p
+    ORDER BY p.proto;
+/
+CREATE OR REPLACE VIEW v_service_command_state
+AS SELECT p.`service_name`, p.object_type, p.`state`, p.description
+  FROM webapi.service_command_state p
+    ORDER BY p.`state`;
+/
+CREATE OR REPLACE VIEW v_service_event
+AS SELECT e.service_name,
+  e.object_type,
+  e.description
+  FROM webapi.service_event e;
+/
+CREATE OR REPLACE VIEW v_service_subscription
+AS SELECT ss.service_name,
+  ss.object_type
+  FROM webapi.service_subscription ss
+    JOIN webapi.service sv ON sv.name = ss.service_name
+      ORDER BY ss.object_type;
+/
+CREATE OR REPLACE VIEW v_service_subscription_path
+AS SELECT p.`service_name`, p.object_type, p.proto, p.`to`
+  FROM webapi.service_subscription_path p
+    ORDER BY p.proto;
+/
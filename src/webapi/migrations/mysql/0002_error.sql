@@ -0,0 +1,8 @@
+-- rows are seeded from errors::ERROR_NAMES on every start
+CREATE TABLE `error` (
+  `error_code` varchar(100) NOT NULL,
+  `locale` varchar(35) NOT NULL,
+  `error_name` varchar(500) NOT NULL,
+  CONSTRAINT error_pk PRIMARY KEY (`error_code`, `locale`)
+) ENGINE=InnoDB;
+/
@@ -0,0 +1,5 @@
+-- plain passwords of existing users are replaced by hash on service start
+ALTER TABLE webapi.`usr` MODIFY `usr_password` varchar(255) NOT NULL;
+/
+ALTER TABLE webapi.`usr` ADD COLUMN `is_disabled` bool NOT NULL DEFAULT false;
+/
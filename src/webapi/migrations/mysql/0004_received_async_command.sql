@@ -0,0 +1,22 @@
+-- async command is executed later by worker on behalf of caller, with locale of caller
+ALTER TABLE webapi.`received_async_command` ADD COLUMN `authorized_usr_name` varchar(100) NULL;
+/
+ALTER TABLE webapi.`received_async_command` ADD COLUMN `locale` varchar(100) NULL;
+/
+CREATE OR REPLACE VIEW webapi.v_received_async_command
+AS SELECT id,
+  object_type,
+  `service_name`,
+  request_body,
+  `state`,
+  change_state_event,
+  reply_body,
+  proto,
+  added_at,
+  state_changed_at,
+  reply_to,
+  authorized_usr_name,
+  locale
+    FROM webapi.received_async_command
+      ORDER BY added_at, object_type;
+/
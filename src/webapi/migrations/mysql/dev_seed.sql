@@ -0,0 +1,16 @@
+-- users of local development and tests only, never applied to production database,
+-- usr passwords are argon2 hashes of pass1 and 1234567890, test is admin as account of service
+INSERT IGNORE INTO webapi.`usr`
+(usr_name, usr_password)
+VALUES('user1', '$argon2id$v=19$m=19456,t=2,p=1$IdX3FlU9R5kyrXRdZPAINw$pCgPicfjtWuZfLloRd8vIf+KuL+KynEGg8IGJTT0d1A');
+/
+INSERT IGNORE INTO webapi.`usr`
+(usr_name, usr_password)
+VALUES('test', '$argon2id$v=19$m=19456,t=2,p=1$L1+6qKjv492ST/dzVXC6mw$FcM9lyRlMotGwC9RvBnfq0pG76k0pcgPzr/gkmjhXNw');
+/
+INSERT IGNORE INTO webapi.`client_access`
+(source_service_name, destination_service_name, usr_name, usr_password)
+VALUES('*', '*', 'test', '1234567890');
+/
+UPDATE webapi.`usr` SET is_admin = true WHERE usr_name = 'test';
+/
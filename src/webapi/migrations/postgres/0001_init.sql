@@ -1,8 +1,4 @@
-DROP SCHEMA IF EXISTS webapi CASCADE;
-/
-CREATE SCHEMA webapi AUTHORIZATION postgres;
-/
-SET search_path = webapi;
+SET LOCAL search_path = webapi;
 /
 -- TABLES
 CREATE TABLE car (
@@ -21,14 +17,6 @@ CREATE TABLE usr (
 	CONSTRAINT usr_usr_name_key UNIQUE (usr_name)
 );
 /
-INSERT INTO usr
-(usr_name, usr_password)
-VALUES('user1', 'pass1');
-/
-INSERT INTO usr
-(usr_name, usr_password)
-VALUES('test', '1234567890');
-/
 CREATE TABLE client_access (
 	source_service_name text NOT NULL,
 	destination_service_name text NOT NULL,
@@ -37,16 +25,13 @@ CREATE TABLE client_access (
 	CONSTRAINT client_access_pk PRIMARY KEY (source_service_name, destination_service_name)
 );
 /
-INSERT INTO client_access
-(source_service_name, destination_service_name, usr_name, usr_password)
-VALUES('*', '*', 'test', '1234567890');
-/
 CREATE TABLE sended_async_command (
 	id text NOT NULL,
 	object_type text NOT NULL,
 	"service_name" text NOT NULL,
 	"state" text NOT NULL,
 	change_state_event int4 NOT NULL,
+	reply_body text NULL,
 	added_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
 	state_changed_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
 	CONSTRAINT sended_async_command_pk PRIMARY KEY (id)
@@ -58,7 +43,7 @@ CREATE TABLE sended_async_command_state_history (
 	command_id text NOT NULL,
 	"state" text NOT NULL,
 	added_at TIMESTAMPTZ,
-	CONSTRAINT sended_async_command_state_history_pk PRIMARY KEY (command_id)
+	CONSTRAINT sended_async_command_state_history_pk PRIMARY KEY (command_id, "state")
 );
 /
 CREATE INDEX sended_async_command_state_history_added_at_idx ON sended_async_command_state_history 
@@ -73,6 +58,7 @@ CREATE TABLE received_async_command (
 	change_state_event int4 NOT NULL,
 	reply_body text NOT NULL,
 	proto text NOT NULL,
+	reply_to text NOT NULL,
 	added_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
 	state_changed_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
 	CONSTRAINT received_async_command_pk PRIMARY KEY (id)
@@ -84,12 +70,53 @@ CREATE TABLE received_async_command_state_history (
 	command_id text NOT NULL,
 	"state" text NOT NULL,
 	added_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-	CONSTRAINT received_async_command_state_history_pk PRIMARY KEY (command_id)
+	CONSTRAINT received_async_command_state_history_pk PRIMARY KEY (command_id, "state")
 );
 /
 CREATE INDEX received_async_command_state_history_added_at_idx ON received_async_command_state_history 
 	USING btree (command_id, added_at);
 /
+CREATE TABLE event_outbox (
+	id int4 NOT NULL GENERATED ALWAYS AS IDENTITY,
+	correlation_id text NOT NULL,
+	object_type text NOT NULL,
+	body text NOT NULL,
+	added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT event_outbox_pk PRIMARY KEY (id)
+);
+/
+CREATE TABLE undelivered_event (
+	id int4 NOT NULL GENERATED BY DEFAULT AS IDENTITY,
+	correlation_id text NOT NULL,
+	object_type text NOT NULL,
+	"service_name" text NOT NULL,
+	proto text NOT NULL,
+	"path" text NOT NULL,
+	body text NOT NULL,
+	attempt int4 NOT NULL,
+	last_error text NOT NULL,
+	next_attempt_at TIMESTAMPTZ NOT NULL,
+	added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT undelivered_event_pk PRIMARY KEY (id)
+);
+/
+CREATE INDEX undelivered_event_next_attempt_at_idx ON undelivered_event USING btree (next_attempt_at);
+/
+CREATE TABLE dead_letter_event (
+	id int4 NOT NULL,
+	correlation_id text NOT NULL,
+	object_type text NOT NULL,
+	"service_name" text NOT NULL,
+	proto text NOT NULL,
+	"path" text NOT NULL,
+	body text NOT NULL,
+	attempt int4 NOT NULL,
+	last_error text NOT NULL,
+	next_attempt_at TIMESTAMPTZ NOT NULL,
+	added_at TIMESTAMPTZ NOT NULL,
+	CONSTRAINT dead_letter_event_pk PRIMARY KEY (id)
+);
+/
 CREATE table "service" (
 	"name" text NOT NULL,
 	"description" text NOT NULL,
@@ -173,7 +200,8 @@ AS SELECT id,
 	"state",
 	change_state_event,
 	added_at,
-	state_changed_at
+	state_changed_at,
+	reply_body
 		FROM webapi.sended_async_command
 			ORDER BY added_at, object_type;
 /
@@ -192,7 +220,8 @@ AS SELECT id,
 	reply_body,
 	proto,
 	added_at,
-	state_changed_at
+	state_changed_at,
+	reply_to
 		FROM webapi.received_async_command
 			ORDER BY added_at, object_type;
 /
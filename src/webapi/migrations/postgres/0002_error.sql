@@ -0,0 +1,10 @@
+-- rows are seeded from errors::ERROR_NAMES on every start
+SET LOCAL search_path = webapi;
+/
+CREATE TABLE error (
+	error_code text NOT NULL,
+	locale text NOT NULL,
+	error_name text NOT NULL,
+	CONSTRAINT error_pk PRIMARY KEY (error_code, locale)
+);
+/
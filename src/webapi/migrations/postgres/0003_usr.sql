@@ -0,0 +1,5 @@
+SET LOCAL search_path = webapi;
+/
+-- plain passwords of existing users are replaced by hash on service start
+ALTER TABLE usr ADD COLUMN is_disabled bool NOT NULL DEFAULT false;
+/
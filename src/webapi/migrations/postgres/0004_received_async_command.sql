@@ -0,0 +1,24 @@
+SET LOCAL search_path = webapi;
+/
+-- async command is executed later by worker on behalf of caller, with locale of caller
+ALTER TABLE received_async_command ADD COLUMN authorized_usr_name text NULL;
+/
+ALTER TABLE received_async_command ADD COLUMN locale text NULL;
+/
+CREATE OR REPLACE VIEW v_received_async_command
+AS SELECT id,
+	object_type,
+	"service_name",
+	request_body,
+	"state",
+	change_state_event,
+	reply_body,
+	proto,
+	added_at,
+	state_changed_at,
+	reply_to,
+	authorized_usr_name,
+	locale
+		FROM webapi.received_async_command
+			ORDER BY added_at, object_type;
+/
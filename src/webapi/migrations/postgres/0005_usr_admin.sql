@@ -0,0 +1,5 @@
+SET LOCAL search_path = webapi;
+/
+-- admin usr may sign up users, reload access and replay dead letters
+ALTER TABLE usr ADD COLUMN is_admin bool NOT NULL DEFAULT false;
+/
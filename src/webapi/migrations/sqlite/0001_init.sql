@@ -0,0 +1,275 @@
+-- TABLES
+CREATE TABLE IF NOT EXISTS webapi.car (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	car_name text NOT NULL
+);
+/
+CREATE UNIQUE INDEX IF NOT EXISTS webapi.car_car_name_idx ON car (car_name);
+/
+CREATE TABLE IF NOT EXISTS webapi.usr (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	usr_name text NOT NULL,
+	usr_password text NOT NULL,
+	CONSTRAINT usr_usr_name_key UNIQUE (usr_name)
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.client_access (
+	source_service_name text NOT NULL,
+	destination_service_name text NOT NULL,
+	usr_name text NOT NULL,
+	usr_password text NOT NULL,
+	CONSTRAINT client_access_pk PRIMARY KEY (source_service_name, destination_service_name)
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.sended_async_command (
+	id text NOT NULL,
+	object_type text NOT NULL,
+	"service_name" text NOT NULL,
+	"state" text NOT NULL,
+	change_state_event int4 NOT NULL,
+	reply_body text NULL,
+	added_at datetime DEFAULT CURRENT_TIMESTAMP,
+	state_changed_at datetime DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT sended_async_command_pk PRIMARY KEY (id)
+);
+/
+CREATE INDEX IF NOT EXISTS webapi.sended_async_command_added_at_idx ON sended_async_command (added_at, object_type);
+/
+CREATE TABLE IF NOT EXISTS webapi.sended_async_command_state_history (
+	command_id text NOT NULL,
+	"state" text NOT NULL,
+	added_at datetime,
+	CONSTRAINT sended_async_command_state_history_pk PRIMARY KEY (command_id, "state")
+);
+/
+CREATE INDEX IF NOT EXISTS webapi.sended_async_command_state_history_added_at_idx
+	ON sended_async_command_state_history (command_id, added_at);
+/
+CREATE TABLE IF NOT EXISTS webapi.received_async_command (
+	id text NOT NULL,
+	object_type text NOT NULL,
+	"service_name" text NOT NULL,
+	request_body text NOT NULL,
+	"state" text NOT NULL,
+	change_state_event int4 NOT NULL,
+	reply_body text NOT NULL,
+	proto text NOT NULL,
+	reply_to text NOT NULL,
+	added_at datetime DEFAULT CURRENT_TIMESTAMP,
+	state_changed_at datetime DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT received_async_command_pk PRIMARY KEY (id)
+);
+/
+CREATE INDEX IF NOT EXISTS webapi.received_async_command_added_at_idx ON received_async_command (added_at, object_type);
+/
+CREATE TABLE IF NOT EXISTS webapi.received_async_command_state_history (
+	command_id text NOT NULL,
+	"state" text NOT NULL,
+	added_at datetime DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT received_async_command_state_history_pk PRIMARY KEY (command_id, "state")
+);
+/
+CREATE INDEX IF NOT EXISTS webapi.received_async_command_state_history_added_at_idx
+	ON received_async_command_state_history (command_id, added_at);
+/
+CREATE TABLE IF NOT EXISTS webapi.event_outbox (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	correlation_id text NOT NULL,
+	object_type text NOT NULL,
+	body text NOT NULL,
+	added_at datetime NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.undelivered_event (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	correlation_id text NOT NULL,
+	object_type text NOT NULL,
+	"service_name" text NOT NULL,
+	proto text NOT NULL,
+	"path" text NOT NULL,
+	body text NOT NULL,
+	attempt int4 NOT NULL,
+	last_error text NOT NULL,
+	next_attempt_at datetime NOT NULL,
+	added_at datetime NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+/
+CREATE INDEX IF NOT EXISTS webapi.undelivered_event_next_attempt_at_idx ON undelivered_event (next_attempt_at);
+/
+CREATE TABLE IF NOT EXISTS webapi.dead_letter_event (
+	id int4 NOT NULL,
+	correlation_id text NOT NULL,
+	object_type text NOT NULL,
+	"service_name" text NOT NULL,
+	proto text NOT NULL,
+	"path" text NOT NULL,
+	body text NOT NULL,
+	attempt int4 NOT NULL,
+	last_error text NOT NULL,
+	next_attempt_at datetime NOT NULL,
+	added_at datetime NOT NULL,
+	CONSTRAINT dead_letter_event_pk PRIMARY KEY (id)
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service (
+	"name" text NOT NULL,
+	"description" text NOT NULL,
+	"priority" int4 NOT NULL,
+	added_at datetime DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT service_pk PRIMARY KEY ("name")
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_path (
+	"service_name" text NOT NULL,
+	proto text NOT NULL,
+	helth text NOT NULL,
+	"schema" text NOT NULL,
+	"reply_to" text NOT NULL,
+	"state" text NOT NULL,
+	"error" text NOT NULL,
+	CONSTRAINT service_path_pk PRIMARY KEY ("service_name", proto),
+	CONSTRAINT service_path_service_name_fk FOREIGN KEY ("service_name") REFERENCES service("name")
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_command (
+	"service_name" text NOT NULL,
+	object_type text NOT NULL,
+	"description" text NOT NULL,
+	exec_mode text NOT NULL,
+	reply_type text NOT NULL,
+	CONSTRAINT service_command_pk PRIMARY KEY ("service_name", object_type),
+	CONSTRAINT service_command_service_name_fk FOREIGN KEY ("service_name") REFERENCES service("name")
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_command_path (
+	"service_name" text NOT NULL,
+	object_type text NOT NULL,
+	proto text NOT NULL,
+	"to" text NOT NULL,
+	CONSTRAINT service_command_path_pk PRIMARY KEY ("service_name", object_type, proto),
+	CONSTRAINT service_command_path_sn_ot_fk FOREIGN KEY ("service_name", object_type)
+		REFERENCES service_command("service_name", object_type)
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_command_state (
+	"service_name" text NOT NULL,
+	object_type text NOT NULL,
+	"state" text NOT NULL,
+	"description" text NOT NULL,
+	CONSTRAINT service_command_state_pk PRIMARY KEY ("service_name", object_type, "state"),
+	CONSTRAINT service_command_state_sn_ot_fk FOREIGN KEY ("service_name", object_type)
+		REFERENCES service_command("service_name", object_type)
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_event (
+	"service_name" text NOT NULL,
+	object_type text NOT NULL,
+	"description" text NOT NULL,
+	CONSTRAINT service_event_pk PRIMARY KEY ("service_name", object_type),
+	CONSTRAINT service_event_service_name_fk FOREIGN KEY ("service_name") REFERENCES service("name")
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_subscription (
+	"service_name" text NOT NULL,
+	object_type text NOT NULL,
+	CONSTRAINT service_subscription_pk PRIMARY KEY ("service_name", object_type),
+	CONSTRAINT service_subscription_service_name_fk FOREIGN KEY ("service_name") REFERENCES service("name")
+);
+/
+CREATE TABLE IF NOT EXISTS webapi.service_subscription_path (
+	"service_name" text NOT NULL,
+	object_type text NOT NULL,
+	proto text NOT NULL,
+	"to" text NOT NULL,
+	CONSTRAINT service_subscription_path_pk PRIMARY KEY ("service_name", object_type, proto),
+	CONSTRAINT service_subscription_path_sn_ot_fk FOREIGN KEY ("service_name", object_type)
+		REFERENCES service_subscription("service_name", object_type)
+);
+/
+-- VIEWS, objects of attached database are referenced without schema
+CREATE VIEW IF NOT EXISTS webapi.v_sended_async_command
+AS SELECT id,
+	object_type,
+	"service_name",
+	"state",
+	change_state_event,
+	added_at,
+	state_changed_at,
+	reply_body
+		FROM sended_async_command
+			ORDER BY added_at, object_type;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_sended_async_command_state_history
+AS SELECT command_id, "state", added_at
+		FROM sended_async_command_state_history
+			ORDER BY command_id, added_at;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_received_async_command
+AS SELECT id,
+	object_type,
+	"service_name",
+	request_body,
+	"state",
+	change_state_event,
+	reply_body,
+	proto,
+	added_at,
+	state_changed_at,
+	reply_to
+		FROM received_async_command
+			ORDER BY added_at, object_type;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_received_async_command_state_history
+AS SELECT command_id, "state", added_at
+		FROM received_async_command_state_history
+			ORDER BY command_id, added_at;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service
+AS SELECT s."name", s."description", s.priority, 'Unavailable' as "state", s.added_at
+	FROM service s
+		ORDER BY s."name";
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_command
+AS SELECT c.service_name,
+    s.priority,
+    c.object_type,
+	c.description,
+	c.exec_mode,
+	c.reply_type
+   FROM service_command c
+     JOIN service s ON s.name = c.service_name
+	 	ORDER BY c.object_type, s.priority;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_path
+AS SELECT p."service_name", p.proto, p.helth, p."schema", p.reply_to, p."state", p."error"
+   FROM service_path p
+	 	ORDER BY p."service_name", p.proto;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_command_path
+AS SELECT p."service_name", p.object_type, p.proto, p."to"
+   FROM service_command_path p
+	 	ORDER BY p.proto;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_command_state
+AS SELECT p."service_name", p.object_type, p."state", p.description
+   FROM service_command_state p
+	 	ORDER BY p."state";
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_event
+AS SELECT e.service_name,
+    e.object_type,
+	e.description
+   FROM service_event e;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_subscription
+AS SELECT ss.service_name,
+    ss.object_type
+   FROM service_subscription ss
+     JOIN service sv ON sv.name = ss.service_name
+	 	ORDER BY ss.object_type;
+/
+CREATE VIEW IF NOT EXISTS webapi.v_service_subscription_path
+AS SELECT p."service_name", p.object_type, p.proto, p."to"
+   FROM service_subscription_path p
+	 	ORDER BY p.proto;
+/
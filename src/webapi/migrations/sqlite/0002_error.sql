@@ -0,0 +1,8 @@
+-- rows are seeded from errors::ERROR_NAMES on every start
+CREATE TABLE IF NOT EXISTS webapi.error (
+	error_code text NOT NULL,
+	locale text NOT NULL,
+	error_name text NOT NULL,
+	CONSTRAINT error_pk PRIMARY KEY (error_code, locale)
+);
+/
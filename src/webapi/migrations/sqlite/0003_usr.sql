@@ -0,0 +1,3 @@
+-- plain passwords of existing users are replaced by hash on service start
+ALTER TABLE webapi.usr ADD COLUMN is_disabled boolean NOT NULL DEFAULT 0;
+/
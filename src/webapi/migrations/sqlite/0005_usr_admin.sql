@@ -0,0 +1,3 @@
+-- admin usr may sign up users, reload access and replay dead letters
+ALTER TABLE webapi.usr ADD COLUMN is_admin boolean NOT NULL DEFAULT 0;
+/
@@ -10,13 +10,20 @@ pub mod schema;
 pub mod settings;
 pub mod traits;
 
-#[cfg(not(test))]
+// postgres queries are checked against database at compile time, so tests of
+// postgres build run on memory storages, mysql and sqlite tests run on sql storages
+#[cfg(any(not(test), not(feature = "postgres")))]
 pub mod collections;
 
 pub mod connectors;
 pub mod executors;
+pub mod filters;
+pub mod memory;
+#[cfg(any(not(test), not(feature = "postgres")))]
+pub mod migrations;
 pub mod providers;
 pub mod publishers;
+pub mod registry;
 pub mod router;
 pub mod workers;
 
@@ -1,13 +1,35 @@
-use super::{connectors, entities, errors};
+#[cfg(any(not(test), not(feature = "postgres")))]
+use super::migrations;
+#[cfg(test)]
+pub use super::tests::fakes::mq::{MqReceiver, MqSession};
+use super::{access, connectors, entities, errors};
+use chrono::{DateTime, Utc};
+#[cfg(all(not(test), feature = "amqp"))]
+use dove::container::*;
+#[cfg(all(not(test), feature = "amqp"))]
+use dove::message::MessageBody;
 use hyper::{Body, Client, Method, Request, StatusCode};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "mysql")]
 use sqlx::MySqlPool;
-#[cfg(feature = "postgres")]
+#[cfg(all(not(test), feature = "postgres"))]
 use sqlx::PgPool;
+#[cfg(feature = "sqlite")]
+use sqlx::{sqlite::SqlitePoolOptions, Executor, SqlitePool};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+pub const MQ_MESSAGE_ID: &str = "message_id"; //unique for each sended mq message
+pub const MQ_REPLY_TO: &str = "reply_to"; //address for reply
+pub const MQ_IN_REPLY_TO: &str = "in_reply_to"; //message_id of request in reply
+pub const MQ_STATUS: &str = "status"; //http-like status code in reply
+pub const MQ_AUTHORIZATION: &str = "authorization"; //basic authorization token of request
+pub const MQ_PATH: &str = "path"; //service path of request, object type path if not set
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Copy, Clone, ToString, JsonSchema)]
 pub enum Proto {
@@ -15,11 +37,16 @@ pub enum Proto {
     Mq,
 }
 
-pub struct HttpProvider;
+#[derive(Clone)]
+pub struct HttpProvider {
+    reply_wait: Duration,
+}
 
 impl HttpProvider {
-    pub async fn new() -> connectors::Result<HttpProvider> {
-        Ok(HttpProvider {})
+    pub async fn new(reply_wait: Duration) -> connectors::Result<HttpProvider> {
+        Ok(HttpProvider {
+            reply_wait: reply_wait,
+        })
     }
 
     pub async fn execute(
@@ -44,65 +71,513 @@ impl HttpProvider {
             .body(body)
             .expect("request builder");
         let client = Client::new();
-        let resp = client.request(req).await?;
+        let resp = match timeout(self.reply_wait, client.request(req)).await {
+            Ok(r) => r?,
+            Err(_) => return Err(errors::ErrorKind::TimeoutExpired.into()),
+        };
         let (parts, body) = resp.into_parts();
         if parts.status == StatusCode::OK {
             Ok(body)
         } else {
-            Err(errors::ProtoProviderError.into())
+            Err(errors::ErrorKind::ProtoProvider.into())
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MqMessage {
+    pub properties: HashMap<String, String>, //amqp application properties
+    pub body: String,
+}
+
+#[cfg(all(not(test), feature = "amqp"))]
+pub struct MqSession {
+    session: Session,
+    senders: Mutex<HashMap<String, Sender>>,
+}
+
+#[cfg(all(not(test), feature = "amqp"))]
+impl MqSession {
+    pub fn new(session: Session) -> MqSession {
+        MqSession {
+            session: session,
+            senders: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn send(&self, to: &str, message: MqMessage) -> connectors::Result<()> {
+        let mut senders = self.senders.lock().await;
+        if !senders.contains_key(to) {
+            let sender = self.session.new_sender(to).await?;
+            senders.insert(to.to_string(), sender);
         }
+        let mut m = Message::amqp_value(Value::String(message.body));
+        let mut ap = Vec::<(Value, Value)>::new();
+        for item in message.properties {
+            ap.push((Value::String(item.0), Value::String(item.1)));
+        }
+        m.application_properties = Some(ap);
+        senders.get(to).unwrap().send(m).await?;
+        Ok({})
+    }
+
+    pub async fn receiver(&self, address: &str) -> connectors::Result<MqReceiver> {
+        let receiver = self.session.new_receiver(address).await?;
+        receiver.flow(MqReceiver::CREDIT)?;
+        Ok(MqReceiver { receiver: receiver })
+    }
+}
+
+#[cfg(all(not(test), feature = "amqp"))]
+pub struct MqReceiver {
+    receiver: Receiver,
+}
+
+#[cfg(all(not(test), feature = "amqp"))]
+impl MqReceiver {
+    const CREDIT: u32 = 10;
+
+    pub async fn receive(&self) -> connectors::Result<MqMessage> {
+        let mut delivery = self.receiver.receive().await?;
+        let message = delivery.message();
+        let body = match &message.body {
+            MessageBody::AmqpValue(Value::String(s)) => Some(s.clone()),
+            MessageBody::Data(d) => String::from_utf8(d.clone()).ok(),
+            _ => None,
+        };
+        let mut properties = HashMap::<String, String>::new();
+        if let Some(ap) = &message.application_properties {
+            for item in ap {
+                if let (Value::String(k), Value::String(v)) = item {
+                    properties.insert(k.clone(), v.clone());
+                }
+            }
+        }
+        delivery.disposition(true, DeliveryState::Accepted).await?;
+        self.receiver.flow(1)?;
+        match body {
+            Some(b) => Ok(MqMessage {
+                properties: properties,
+                body: b,
+            }),
+            None => Err(errors::ErrorKind::ProtoProvider.into()),
+        }
+    }
+}
+
+#[cfg(all(not(test), not(feature = "amqp")))]
+pub struct MqSession;
+
+#[cfg(all(not(test), not(feature = "amqp")))]
+impl MqSession {
+    pub async fn send(&self, _to: &str, _message: MqMessage) -> connectors::Result<()> {
+        Err(errors::ErrorKind::UnsupportedProto.into())
+    }
+
+    pub async fn receiver(&self, _address: &str) -> connectors::Result<MqReceiver> {
+        Err(errors::ErrorKind::UnsupportedProto.into())
+    }
+}
+
+#[cfg(all(not(test), not(feature = "amqp")))]
+pub struct MqReceiver;
+
+#[cfg(all(not(test), not(feature = "amqp")))]
+impl MqReceiver {
+    pub async fn receive(&self) -> connectors::Result<MqMessage> {
+        Err(errors::ErrorKind::UnsupportedProto.into())
     }
 }
 
-pub struct MqProvider;
+#[derive(Clone)]
+pub struct MqProvider {
+    session: Option<Arc<MqSession>>,
+    reply_to: Option<String>,
+    reply_wait: Duration,
+    waiters: Arc<std::sync::Mutex<HashMap<String, oneshot::Sender<MqMessage>>>>,
+}
 
 impl MqProvider {
-    pub async fn new() -> connectors::Result<MqProvider> {
-        Ok(MqProvider {})
+    pub async fn new(
+        session: Option<Arc<MqSession>>,
+        reply_to: Option<String>,
+        reply_wait: Duration,
+    ) -> connectors::Result<MqProvider> {
+        Ok(MqProvider {
+            session: session,
+            reply_to: reply_to,
+            reply_wait: reply_wait,
+            waiters: Arc::new(std::sync::Mutex::new(HashMap::<
+                String,
+                oneshot::Sender<MqMessage>,
+            >::new())),
+        })
+    }
+
+    pub fn get_session(&self) -> Option<Arc<MqSession>> {
+        self.session.clone()
+    }
+
+    pub fn get_reply_to(&self) -> Option<String> {
+        self.reply_to.clone()
+    }
+
+    //pass reply to waiting execute, return false if message is not reply
+    pub async fn dispatch_reply(&self, message: MqMessage) -> bool {
+        match message.properties.get(MQ_IN_REPLY_TO) {
+            Some(id) => {
+                match self.waiters.lock().unwrap().remove(id) {
+                    Some(w) => {
+                        let _ = w.send(message);
+                    }
+                    None => warn!("mq provider reply {} without waiter", id),
+                };
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn reply(
+        &self,
+        request: &MqMessage,
+        status: StatusCode,
+        body: Body,
+    ) -> connectors::Result<()> {
+        if self.session.is_none() {
+            return Err(errors::ErrorKind::UnsupportedProto.into());
+        }
+        let to = match request.properties.get(MQ_REPLY_TO) {
+            Some(r) => r,
+            None => return Err(errors::ErrorKind::UnsetRequiredValue.into()),
+        };
+        let mut properties = HashMap::<String, String>::new();
+        properties.insert(
+            MQ_MESSAGE_ID.to_string(),
+            Uuid::new_v4().to_hyphenated().to_string(),
+        );
+        if let Some(id) = request.properties.get(MQ_MESSAGE_ID) {
+            properties.insert(MQ_IN_REPLY_TO.to_string(), id.to_string());
+        }
+        if let Some(cid) = request.properties.get("correlation_id") {
+            properties.insert("correlation_id".to_string(), cid.to_string());
+        }
+        properties.insert(MQ_STATUS.to_string(), status.as_u16().to_string());
+        let bytes = hyper::body::to_bytes(body).await?;
+        let body = String::from_utf8(bytes.to_vec())?;
+        self.session
+            .as_ref()
+            .unwrap()
+            .send(
+                to,
+                MqMessage {
+                    properties: properties,
+                    body: body,
+                },
+            )
+            .await
     }
 
     pub async fn execute(
         &self,
-        _to: &str,
-        _prop: HashMap<&str, &str>,
-        _body: Body,
+        to: &str,
+        prop: HashMap<&str, &str>,
+        bat: String,
+        body: Body,
     ) -> connectors::Result<Body> {
-        //todo:
-        Ok(Body::empty())
+        if self.session.is_none() || self.reply_to.is_none() {
+            return Err(errors::ErrorKind::UnsupportedProto.into());
+        }
+        let message_id = Uuid::new_v4().to_hyphenated().to_string();
+        let mut properties = HashMap::<String, String>::new();
+        for item in prop.iter() {
+            properties.insert(item.0.to_string(), item.1.to_string());
+        }
+        properties.insert(MQ_AUTHORIZATION.to_string(), bat);
+        properties.insert(MQ_MESSAGE_ID.to_string(), message_id.clone());
+        properties.insert(
+            MQ_REPLY_TO.to_string(),
+            self.reply_to.as_ref().unwrap().to_string(),
+        );
+        let bytes = hyper::body::to_bytes(body).await?;
+        let body = String::from_utf8(bytes.to_vec())?;
+        let (sender, receiver) = oneshot::channel::<MqMessage>();
+        self.waiters
+            .lock()
+            .unwrap()
+            .insert(message_id.clone(), sender);
+        if let Err(e) = self
+            .session
+            .as_ref()
+            .unwrap()
+            .send(
+                to,
+                MqMessage {
+                    properties: properties,
+                    body: body,
+                },
+            )
+            .await
+        {
+            self.waiters.lock().unwrap().remove(&message_id);
+            return Err(e);
+        }
+        let reply = match timeout(self.reply_wait, receiver).await {
+            Ok(Ok(r)) => r,
+            Ok(Err(_)) => return Err(errors::ErrorKind::Channel.into()),
+            Err(_) => {
+                self.waiters.lock().unwrap().remove(&message_id);
+                return Err(errors::ErrorKind::TimeoutExpired.into());
+            }
+        };
+        if reply.properties.get(MQ_STATUS) == Some(&StatusCode::OK.as_u16().to_string()) {
+            Ok(Body::from(reply.body))
+        } else {
+            Err(errors::ErrorKind::ProtoProvider.into())
+        }
     }
 }
 
-#[cfg(not(test))]
+#[cfg(any(not(test), not(feature = "postgres")))]
 pub struct SqlDbProvider {
     #[cfg(feature = "postgres")]
     pub pool: Arc<PgPool>,
     #[cfg(feature = "mysql")]
     pub pool: Arc<MySqlPool>,
+    #[cfg(feature = "sqlite")]
+    pub pool: Arc<SqlitePool>,
 }
 
-#[cfg(not(test))]
+#[cfg(any(not(test), not(feature = "postgres")))]
 impl SqlDbProvider {
-    pub async fn new(connection_string: &str) -> connectors::Result<SqlDbProvider> {
+    pub async fn new(connection_string: &str, dev_seed: bool) -> connectors::Result<SqlDbProvider> {
         debug!("connection string {}", connection_string);
         #[cfg(feature = "postgres")]
         let pool = PgPool::connect(&connection_string).await?;
         #[cfg(feature = "mysql")]
         let pool = MySqlPool::connect(&connection_string).await?;
+        #[cfg(feature = "sqlite")]
+        let pool = SqlDbProvider::connect_sqlite(&connection_string).await?;
+        let count = migrations::apply(&pool).await?;
+        if count > 0 {
+            info!("{} migrations applied", count);
+        }
+        migrations::seed_errors(&pool).await?;
+        if dev_seed {
+            migrations::seed_dev(&pool).await?;
+        }
         Ok(SqlDbProvider {
             pool: Arc::new(pool),
         })
     }
 
+    // sqlite has no schemas, so webapi database is attached to each connection,
+    // in-memory database lives while single pool connection is open
+    #[cfg(feature = "sqlite")]
+    async fn connect_sqlite(connection_string: &str) -> connectors::Result<SqlitePool> {
+        let path = connection_string
+            .trim_start_matches("sqlite:")
+            .trim_start_matches("//")
+            .split('?')
+            .next()
+            .unwrap_or_default();
+        let is_memory = path.len() == 0 || path == ":memory:";
+        let attach = format!(
+            "ATTACH DATABASE '{}' AS webapi",
+            if is_memory { ":memory:" } else { path }.replace('\'', "''")
+        );
+        let mut options = SqlitePoolOptions::new().after_connect(move |conn, _meta| {
+            let attach = attach.clone();
+            Box::pin(async move {
+                conn.execute(attach.as_str()).await?;
+                Ok(())
+            })
+        });
+        if is_memory {
+            options = options
+                .max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+        }
+        Ok(options.connect("sqlite::memory:").await?)
+    }
+
     pub async fn get_errors(&self) -> connectors::Result<Vec<entities::error::Error>> {
-        Ok(vec![
-            entities::error::Error {
-                error_code: errors::ErrorCode::DatabaseError.to_string(),
-                error_name: "Database error".to_string(),
-            },
-            entities::error::Error {
-                error_code: errors::ErrorCode::NotFoundError.to_string(),
-                error_name: "Some items with specified id is not found".to_string(),
-            },
-        ])
+        let items: Vec<entities::error::Error> =
+            sqlx::query_as(r#"SELECT error_code,locale,error_name FROM webapi.error"#)
+                .fetch_all(&*self.pool)
+                .await?;
+        Ok(items)
+    }
+}
+
+// all tables of webapi schema in memory, single lock makes each change atomic like sql transaction
+pub struct MemoryDbProvider {
+    pub db: Mutex<MemoryDb>,
+}
+
+#[derive(Default)]
+pub struct MemoryDb {
+    pub usr: Vec<entities::usr::Usr>,
+    pub car: Vec<entities::car::Car>,
+    pub service: Vec<(entities::route::Route, DateTime<Utc>)>, //route with service added_at
+    pub sended_async_command: Vec<entities::executor::SendedAsyncCommand>, //with history
+    pub received_async_command: Vec<entities::executor::ReceivedAsyncCommand>, //with history
+    pub event_outbox: Vec<entities::publisher::OutboxEvent>,
+    pub undelivered_event: Vec<entities::publisher::UndeliveredEvent>,
+    pub dead_letter_event: Vec<entities::publisher::UndeliveredEvent>,
+    seq: HashMap<&'static str, i32>, //identity by table
+}
+
+impl MemoryDb {
+    // next identity of table, as generated by sql database
+    pub fn next_id(&mut self, table: &'static str) -> i32 {
+        let id = self.seq.entry(table).or_insert(0);
+        *id += 1;
+        *id
+    }
+}
+
+impl MemoryDbProvider {
+    // same seed data as first migration, passwords are hashed as after first start
+    pub fn new() -> MemoryDbProvider {
+        let mut db = MemoryDb::default();
+        for (name, password, is_admin) in &[
+            ("user1", "pass1", false),
+            ("test", "1234567890", true),
+        ] {
+            let id = db.next_id("usr");
+            db.usr.push(entities::usr::Usr {
+                id: id,
+                usr_name: name.to_string(),
+                usr_password: access::hash_password(password).unwrap(),
+                is_disabled: false,
+                is_admin: *is_admin,
+            });
+        }
+        MemoryDbProvider { db: Mutex::new(db) }
+    }
+
+    pub async fn get_errors(&self) -> connectors::Result<Vec<entities::error::Error>> {
+        Ok(errors::get_error_names())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get_mq_provider(reply_wait: Duration) -> MqProvider {
+        MqProvider::new(
+            Some(Arc::new(MqSession::new())),
+            Some("client".to_string()),
+            reply_wait,
+        )
+        .await
+        .unwrap()
+    }
+
+    // answer one request of "service" address with status, pass reply to waiting execute
+    async fn serve_one(mp: &MqProvider, status: StatusCode) {
+        let session = mp.get_session().unwrap();
+        let service = session.receiver("service").await.unwrap();
+        let client = session.receiver("client").await.unwrap();
+        let mp = mp.clone();
+        tokio::spawn(async move {
+            if let Some(request) = service.receive().await.ok() {
+                let body = Body::from(format!("echo {}", request.body));
+                let _ = mp.reply(&request, status, body).await.is_ok();
+            }
+            if let Some(reply) = client.receive().await.ok() {
+                assert!(mp.dispatch_reply(reply).await);
+            }
+        });
+    }
+
+    async fn execute(mp: &MqProvider) -> Option<String> {
+        let mut prop = HashMap::<&str, &str>::new();
+        prop.insert("correlation_id", "1");
+        match mp
+            .execute("service", prop, "Basic 1".to_string(), Body::from("ping"))
+            .await
+        {
+            Ok(b) => {
+                Some(String::from_utf8(hyper::body::to_bytes(b).await.unwrap().to_vec()).unwrap())
+            }
+            Err(_) => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn mq_execute_returns_reply_body() {
+        let mp = get_mq_provider(Duration::from_secs(1)).await;
+        serve_one(&mp, StatusCode::OK).await;
+        assert_eq!(execute(&mp).await, Some("echo ping".to_string()));
+        assert!(mp.waiters.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn mq_execute_fails_on_error_status() {
+        let mp = get_mq_provider(Duration::from_secs(1)).await;
+        serve_one(&mp, StatusCode::BAD_REQUEST).await;
+        assert_eq!(execute(&mp).await, None);
+    }
+
+    #[tokio::test]
+    async fn mq_execute_times_out_without_reply() {
+        let mp = get_mq_provider(Duration::from_millis(50)).await;
+        assert_eq!(execute(&mp).await, None);
+        assert!(mp.waiters.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn mq_execute_sends_authorization_and_reply_to() {
+        let mp = get_mq_provider(Duration::from_millis(50)).await;
+        let service = mp.get_session().unwrap().receiver("service").await.unwrap();
+        execute(&mp).await;
+        let request = service.receive().await.unwrap();
+        assert_eq!(request.properties.get(MQ_AUTHORIZATION).unwrap(), "Basic 1");
+        assert_eq!(request.properties.get(MQ_REPLY_TO).unwrap(), "client");
+        assert_eq!(request.properties.get("correlation_id").unwrap(), "1");
+        assert!(request.properties.contains_key(MQ_MESSAGE_ID));
+        assert_eq!(request.body, "ping");
+    }
+
+    #[tokio::test]
+    async fn mq_reply_without_waiter_is_dropped() {
+        let mp = get_mq_provider(Duration::from_millis(50)).await;
+        let mut properties = HashMap::<String, String>::new();
+        properties.insert(MQ_IN_REPLY_TO.to_string(), "unknown".to_string());
+        assert!(
+            mp.dispatch_reply(MqMessage {
+                properties: properties,
+                body: String::new(),
+            })
+            .await
+        );
+        assert!(
+            !mp.dispatch_reply(MqMessage {
+                properties: HashMap::new(),
+                body: String::new(),
+            })
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn memory_errors_from_error_names() {
+        let items = MemoryDbProvider::new().get_errors().await.unwrap();
+        assert_eq!(items.len(), errors::ERROR_NAMES.len());
+        let mut catalogue = errors::ErrorCatalogue::new(errors::DEFAULT_LOCALE);
+        catalogue.extend_missing(items);
+        assert_eq!(
+            catalogue.get_name(&errors::ErrorCode::TooManyRequestsError, Some("en")),
+            "Too many requests, async command queue is full"
+        );
+        assert_eq!(
+            catalogue.get_name(&errors::ErrorCode::InternalError, Some("ru")),
+            "Внутренняя ошибка"
+        );
     }
 }
@@ -1,30 +1,79 @@
-use super::{access, connectors, errors, providers, router, traits, workers};
+use super::{access, connectors, entities, errors, providers, replies, router, traits, workers};
+use bytes::Buf;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
 use hyper::Body;
 use serde::ser;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+
+const MAX_DELIVERY_ATTEMPTS: i32 = 5; //then event goes to dead letter
+const RETRY_BASE_DELAY_IN_SECONDS: i64 = 10; //doubled on each attempt
+const RETRY_MAX_DELAY_IN_SECONDS: i64 = 3600;
+const RETRY_BATCH_SIZE: i64 = 100;
+const DEFAULT_DELIVERY_CONCURRENCY: u16 = 10;
+const DEFAULT_DELIVERY_WAIT_IN_SECONDS: u16 = 30;
+
+#[derive(Debug, Clone)]
+pub struct DeliveryReport {
+    pub service_name: String,
+    pub proto: Option<providers::Proto>, //none if subscriber has no supported proto
+    pub error: Option<String>,           //none if delivered
+    pub stored: bool,                    //failed delivery is stored for retry
+}
+
+impl DeliveryReport {
+    pub fn is_delivered(&self) -> bool {
+        self.error.is_none()
+    }
+
+    // delivered or left to redelivery, nothing to retry without supported proto
+    pub fn is_settled(&self) -> bool {
+        self.is_delivered() || self.stored || self.proto.is_none()
+    }
+}
 
 pub struct EventPublisher {
+    dc: Arc<connectors::DataConnector>,
     ac: Arc<access::AccessChecker>,
     rt: Arc<router::Router>,
     hp: providers::HttpProvider,
     mp: providers::MqProvider,
     _cs: mpsc::Sender<workers::SignalCode>,
+    max_concurrency: usize,
+    delivery_wait: Duration,
+    proto_preference: Vec<providers::Proto>,
 }
 
 impl EventPublisher {
     pub async fn new(
+        dc: Arc<connectors::DataConnector>,
         ac: Arc<access::AccessChecker>,
         rt: Arc<router::Router>,
+        hp: providers::HttpProvider,
+        mp: providers::MqProvider,
         cs: mpsc::Sender<workers::SignalCode>,
+        max_concurrency: Option<u16>,
+        max_wait_in_seconds: Option<u16>,
+        proto_preference: Option<Vec<providers::Proto>>,
     ) -> connectors::Result<EventPublisher> {
         Ok(EventPublisher {
+            dc: dc,
             ac: ac,
             rt: rt,
-            hp: providers::HttpProvider::new().await?,
-            mp: providers::MqProvider::new().await?,
+            hp: hp,
+            mp: mp,
             _cs: cs,
+            max_concurrency: max_concurrency
+                .unwrap_or(DEFAULT_DELIVERY_CONCURRENCY)
+                .max(1) as usize,
+            delivery_wait: Duration::from_secs(
+                max_wait_in_seconds.unwrap_or(DEFAULT_DELIVERY_WAIT_IN_SECONDS) as u64,
+            ),
+            proto_preference: proto_preference
+                .unwrap_or(vec![providers::Proto::Http, providers::Proto::Mq]),
         })
     }
 
@@ -34,12 +83,16 @@ impl EventPublisher {
             Ok(_) => Ok({}),
             Err(e) => {
                 error!("event publisher: {}", e);
-                return Err(errors::SignalSendError.into());
+                return Err(errors::ErrorKind::SignalSend.into());
             }
         }
     }
 
-    pub async fn send<T>(&self, correlation_id: &str, items: Vec<T>) -> connectors::Result<()>
+    pub async fn send<T>(
+        &self,
+        correlation_id: &str,
+        items: Vec<T>,
+    ) -> connectors::Result<Vec<DeliveryReport>>
     where
         T: ser::Serialize,
         T: traits::ObjectType,
@@ -50,64 +103,606 @@ impl EventPublisher {
             items.len(),
             T::get_type_name()
         );
-        if let Some(s) = self.rt.get_subscriptions(T::get_type_name()) {
+        self.send_body(
+            correlation_id,
+            T::get_type_name(),
+            serde_json::to_string(&items).unwrap(),
+        )
+        .await
+    }
+
+    // deliver to all subscribers concurrently, failed deliveries go to undelivered for retry
+    async fn send_body(
+        &self,
+        correlation_id: &str,
+        object_type: &str,
+        body: String,
+    ) -> connectors::Result<Vec<DeliveryReport>> {
+        let mut targets = Vec::<(String, Vec<(providers::Proto, String)>)>::new();
+        if let Some(s) = self.rt.get_subscriptions(object_type) {
             for item in s {
-                let mut prop = HashMap::<&str, &str>::new();
-                prop.insert("correlation_id", correlation_id);
-                prop.insert("object_type", T::get_type_name());
-                if item.path.contains_key(&providers::Proto::Http.to_string()) {
-                    let token = self.ac.get_client_basic_authorization_token(
-                        item.service_name.as_ref().unwrap(),
-                    )?;
-                    match self
-                        .hp
-                        .execute(
-                            item.path.get(&providers::Proto::Http.to_string()).unwrap(),
-                            prop,
-                            token,
-                            Body::from(serde_json::to_string(&items).unwrap()),
-                        )
-                        .await
-                    {
-                        Ok(_) => {}
-                        Err(e) => {
-                            warn!(
-                                "correlation id {} object type {} send error {}",
-                                correlation_id,
-                                T::get_type_name(),
-                                e
-                            );
-                        }
+                let mut paths = Vec::<(providers::Proto, String)>::new();
+                for proto in &self.proto_preference {
+                    if let Some(path) = item.path.get(&proto.to_string()) {
+                        paths.push((*proto, path.to_string()));
                     }
-                } else if item.path.contains_key(&providers::Proto::Mq.to_string()) {
-                    match self
-                        .mp
-                        .execute(
-                            item.path.get(&providers::Proto::Http.to_string()).unwrap(),
-                            prop,
-                            Body::from(serde_json::to_string(&items).unwrap()),
-                        )
-                        .await
+                }
+                targets.push((item.service_name.clone().unwrap_or_default(), paths));
+            }
+        }
+        let body = &body;
+        Ok(stream::iter(targets)
+            .map(|(service_name, paths)| async move {
+                if paths.is_empty() {
+                    warn!(
+                        "correlation id {} object type {} supported proto not found error",
+                        correlation_id, object_type
+                    );
+                    return DeliveryReport {
+                        service_name: service_name,
+                        proto: None,
+                        error: Some(errors::ErrorKind::UnsupportedProto.to_string()),
+                        stored: false,
+                    };
+                }
+                //try protos by preference, fall back to next one on failure
+                let mut last_error = String::new();
+                for (proto, path) in &paths {
+                    let result = match timeout(
+                        self.delivery_wait,
+                        self.deliver_body(
+                            correlation_id,
+                            object_type,
+                            &service_name,
+                            *proto,
+                            path,
+                            body,
+                        ),
+                    )
+                    .await
                     {
-                        Ok(_) => {}
+                        Ok(r) => r,
+                        Err(_) => Err(errors::ErrorKind::TimeoutExpired.into()),
+                    };
+                    match result {
+                        Ok(_) => {
+                            return DeliveryReport {
+                                service_name: service_name,
+                                proto: Some(*proto),
+                                error: None,
+                                stored: false,
+                            }
+                        }
                         Err(e) => {
                             warn!(
-                                "correlation id {} object type {} send error {}",
+                                "correlation id {} object type {} proto {} send error {}",
                                 correlation_id,
-                                T::get_type_name(),
+                                object_type,
+                                proto.to_string(),
                                 e
                             );
+                            last_error = e.to_string();
                         }
+                    };
+                }
+                //retry later by most preferred proto
+                let (proto, path) = &paths[0];
+                let stored = match self
+                    .add_undelivered(
+                        correlation_id,
+                        object_type,
+                        &service_name,
+                        *proto,
+                        path,
+                        body,
+                        last_error.clone(),
+                    )
+                    .await
+                {
+                    Ok(_) => true,
+                    Err(e) => {
+                        error!(
+                            "correlation id {} store undelivered error {}",
+                            correlation_id, e
+                        );
+                        false
                     }
-                } else {
+                };
+                DeliveryReport {
+                    service_name: service_name,
+                    proto: Some(*proto),
+                    error: Some(last_error),
+                    stored: stored,
+                }
+            })
+            .buffer_unordered(self.max_concurrency)
+            .collect()
+            .await)
+    }
+
+    pub fn get_outbox_event<T>(
+        correlation_id: &str,
+        items: &Vec<T>,
+    ) -> entities::publisher::OutboxEvent
+    where
+        T: ser::Serialize,
+        T: traits::ObjectType,
+    {
+        entities::publisher::OutboxEvent {
+            id: None,
+            correlation_id: correlation_id.to_string(),
+            object_type: T::get_type_name().to_string(),
+            body: serde_json::to_string(items).unwrap(),
+            added_at: chrono::offset::Utc::now(),
+        }
+    }
+
+    // publish events committed to outbox, removed only if every subscriber got it or
+    // failed delivery is stored for retry, otherwise kept for next drain
+    pub async fn drain_outbox(&self) -> connectors::Result<()> {
+        let items = self.dc.event_outbox.get(RETRY_BATCH_SIZE).await?;
+        for item in items {
+            debug!(
+                "correlation id {} publish outbox event {}",
+                item.correlation_id, item.object_type
+            );
+            let report = self
+                .send_body(&item.correlation_id, &item.object_type, item.body)
+                .await?;
+            debug!(
+                "correlation id {} outbox event {} delivered {} of {}",
+                item.correlation_id,
+                item.object_type,
+                report.iter().filter(|r| r.is_delivered()).count(),
+                report.len()
+            );
+            if !report.iter().all(|r| r.is_settled()) {
+                warn!(
+                    "correlation id {} outbox event {} kept, undelivered is not stored",
+                    item.correlation_id, item.object_type
+                );
+                continue;
+            }
+            let result = self.dc.event_outbox.remove(vec![item.id.unwrap()]).await?;
+            if result != errors::ErrorCode::ReplyOk {
+                error!("drain outbox event {}: {:?}", item.id.unwrap(), result);
+            }
+        }
+        Ok({})
+    }
+
+    fn get_next_attempt_at(attempt: i32) -> DateTime<Utc> {
+        // shift overflows silently, so multiply by checked power
+        let delay = 2i64
+            .checked_pow(attempt as u32)
+            .and_then(|m| RETRY_BASE_DELAY_IN_SECONDS.checked_mul(m))
+            .unwrap_or(RETRY_MAX_DELAY_IN_SECONDS)
+            .min(RETRY_MAX_DELAY_IN_SECONDS);
+        chrono::offset::Utc::now() + chrono::Duration::seconds(delay)
+    }
+
+    async fn add_undelivered(
+        &self,
+        correlation_id: &str,
+        object_type: &str,
+        service_name: &str,
+        proto: providers::Proto,
+        path: &str,
+        body: &str,
+        last_error: String,
+    ) -> connectors::Result<()> {
+        let (result, _) = self
+            .dc
+            .undelivered_event
+            .add(vec![entities::publisher::UndeliveredEvent {
+                id: None,
+                correlation_id: correlation_id.to_string(),
+                object_type: object_type.to_string(),
+                service_name: service_name.to_string(),
+                proto: proto.to_string(),
+                path: path.to_string(),
+                body: body.to_string(),
+                attempt: 1,
+                last_error: last_error,
+                next_attempt_at: EventPublisher::get_next_attempt_at(1),
+                added_at: chrono::offset::Utc::now(),
+            }])
+            .await?;
+        if result == errors::ErrorCode::ReplyOk {
+            Ok({})
+        } else {
+            Err(errors::ErrorKind::Database.into())
+        }
+    }
+
+    async fn deliver_body(
+        &self,
+        correlation_id: &str,
+        object_type: &str,
+        service_name: &str,
+        proto: providers::Proto,
+        path: &str,
+        body: &str,
+    ) -> connectors::Result<()> {
+        let mut prop = HashMap::<&str, &str>::new();
+        prop.insert("correlation_id", correlation_id);
+        prop.insert("object_type", object_type);
+        let token = self
+            .ac
+            .get_client_basic_authorization_token(&service_name.to_string())?;
+        let reply = match proto {
+            providers::Proto::Http => {
+                self.hp
+                    .execute(path, prop, token, Body::from(body.to_string()))
+                    .await?
+            }
+            providers::Proto::Mq => {
+                self.mp
+                    .execute(path, prop, token, Body::from(body.to_string()))
+                    .await?
+            }
+        };
+        // subscriber accepts event by ok reply only, any other reply is retried
+        let reader = hyper::body::aggregate(reply).await?.reader();
+        let reply: replies::common::StandardReply = match serde_json::from_reader(reader) {
+            Ok(r) => r,
+            Err(e) => {
+                return Err(errors::Error::new(errors::ErrorKind::BadReplyEvent)
+                    .with_source(e)
+                    .into())
+            }
+        };
+        if reply.error_code == errors::ErrorCode::ReplyOk {
+            Ok({})
+        } else {
+            Err(errors::Error::new(errors::ErrorKind::BadReplyEvent)
+                .with_source(format!("reply error code {:?}", reply.error_code))
+                .into())
+        }
+    }
+
+    async fn deliver(
+        &self,
+        event: &entities::publisher::UndeliveredEvent,
+    ) -> connectors::Result<()> {
+        let proto = if event.proto == providers::Proto::Http.to_string() {
+            providers::Proto::Http
+        } else if event.proto == providers::Proto::Mq.to_string() {
+            providers::Proto::Mq
+        } else {
+            return Err(errors::ErrorKind::UnsupportedProto.into());
+        };
+        self.deliver_body(
+            &event.correlation_id,
+            &event.object_type,
+            &event.service_name,
+            proto,
+            &event.path,
+            &event.body,
+        )
+        .await
+    }
+
+    // retry due undelivered events, move to dead letter after max attempts
+    pub async fn redeliver(&self) -> connectors::Result<()> {
+        let items = self.dc.undelivered_event.get_due(RETRY_BATCH_SIZE).await?;
+        for item in items {
+            let id = item.id.unwrap();
+            // error is kept as text, boxed error is not Send across awaits below
+            let result = match self.deliver(&item).await.map_err(|e| e.to_string()) {
+                Ok(_) => self.dc.undelivered_event.remove(vec![id]).await?,
+                Err(e) => {
                     warn!(
-                        "correlation id {} object type {} supported proto not found error",
-                        correlation_id,
-                        T::get_type_name()
+                        "correlation id {} object type {} attempt {} send error {}",
+                        item.correlation_id,
+                        item.object_type,
+                        item.attempt + 1,
+                        e
                     );
+                    if item.attempt + 1 >= MAX_DELIVERY_ATTEMPTS {
+                        error!(
+                            "correlation id {} object type {} moved to dead letter",
+                            item.correlation_id, item.object_type
+                        );
+                        self.dc.undelivered_event.move_to_dead_letter(id, e).await?
+                    } else {
+                        self.dc
+                            .undelivered_event
+                            .postpone(id, e, EventPublisher::get_next_attempt_at(item.attempt + 1))
+                            .await?
+                    }
                 }
+            };
+            if result != errors::ErrorCode::ReplyOk {
+                error!("redeliver event {}: {:?}", id, result);
             }
         }
         Ok({})
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{entities::route, events, memory, settings, tests};
+    use super::*;
+
+    // memory storages, undelivered events are not stored if failing
+    fn get_data_connector(
+        fail_undelivered: bool,
+    ) -> (
+        Arc<providers::MemoryDbProvider>,
+        Arc<connectors::DataConnector>,
+    ) {
+        let dp = Arc::new(providers::MemoryDbProvider::new());
+        let undelivered_event: Box<dyn traits::UndeliveredEventStorage> = if fail_undelivered {
+            Box::new(tests::fakes::publisher::UndeliveredEventCollection::failing())
+        } else {
+            Box::new(memory::publisher::UndeliveredEventCollection::new(
+                dp.clone(),
+            ))
+        };
+        let dc = connectors::DataConnector::from_storages(
+            errors::ErrorCatalogue::new(errors::DEFAULT_LOCALE),
+            Box::new(memory::usr::UsrCollection::new(dp.clone())),
+            Box::new(memory::car::CarCollection::new(dp.clone())),
+            Box::new(memory::route::RouteCollection::new(dp.clone())),
+            Box::new(memory::executor::SendedAsyncCommandCollection::new(
+                dp.clone(),
+            )),
+            Box::new(memory::executor::ReceivedAsyncCommandCollection::new(
+                dp.clone(),
+            )),
+            Box::new(memory::publisher::EventOutboxCollection::new(dp.clone())),
+            undelivered_event,
+            Box::new(memory::publisher::DeadLetterEventCollection::new(
+                dp.clone(),
+            )),
+        );
+        (dp, Arc::new(dc))
+    }
+
+    // "test" service subscribes to OnRouteUpdate by mq "service" address
+    async fn get_publisher_for(dc: Arc<connectors::DataConnector>) -> EventPublisher {
+        let (sender, _) = mpsc::channel::<workers::SignalCode>(5);
+        let ac = Arc::new(
+            access::AccessChecker::from_data_connector(
+                &dc,
+                &settings::Authentication {
+                    server: HashMap::new(),
+                    client: vec![route::ClientAccess {
+                        service_name: "test".to_string(),
+                        usr_name: "user1".to_string(),
+                        usr_password: "pass1".to_string(),
+                    }],
+                },
+                sender.clone(),
+            )
+            .await
+            .unwrap(),
+        );
+        let hp = providers::HttpProvider::new(Duration::from_millis(50))
+            .await
+            .unwrap();
+        let mp = providers::MqProvider::new(
+            Some(Arc::new(providers::MqSession::new())),
+            Some("client".to_string()),
+            Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+        let mut service = HashMap::<String, route::Route>::new();
+        service.insert(
+            "test".to_string(),
+            route::Route {
+                service_name: None,
+                description: "test".to_string(),
+                priority: 0,
+                command: vec![],
+                event: vec![],
+                subscription: vec![route::ServiceSubscription {
+                    service_name: None,
+                    object_type: "OnRouteUpdate".to_string(),
+                    path: Some(
+                        [(providers::Proto::Mq.to_string(), "service".to_string())]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                    ),
+                }],
+                path: None,
+            },
+        );
+        let rt = Arc::new(
+            router::Router::new(
+                dc.clone(),
+                ac.clone(),
+                hp.clone(),
+                mp.clone(),
+                None,
+                HashMap::new(),
+                service,
+                "localhost",
+                "localhost",
+            )
+            .await
+            .unwrap(),
+        );
+        EventPublisher::new(
+            dc,
+            ac,
+            rt,
+            hp,
+            mp,
+            sender,
+            None,
+            Some(1),
+            Some(vec![providers::Proto::Mq]),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn get_publisher() -> (Arc<connectors::DataConnector>, EventPublisher) {
+        let (_, dc) = get_data_connector(false);
+        let ep = get_publisher_for(dc.clone()).await;
+        (dc, ep)
+    }
+
+    async fn add_outbox_event(dc: &connectors::DataConnector) {
+        let events = vec![events::route::OnRouteUpdate {
+            services: vec!["test".to_string()],
+        }];
+        let result = dc
+            .event_outbox
+            .add(vec![EventPublisher::get_outbox_event("1", &events)])
+            .await
+            .unwrap();
+        assert_eq!(result, errors::ErrorCode::ReplyOk);
+    }
+
+    const REPLY_OK: &str = r#"{"error_code":"ReplyOk"}"#;
+
+    // reply to one event sent to "service" address
+    async fn serve_one(ep: &EventPublisher, reply: &'static str) {
+        let session = ep.mp.get_session().unwrap();
+        let service = session.receiver("service").await.unwrap();
+        let client = session.receiver("client").await.unwrap();
+        let mp = ep.mp.clone();
+        tokio::spawn(async move {
+            if let Some(request) = service.receive().await.ok() {
+                let _ = mp
+                    .reply(&request, hyper::StatusCode::OK, Body::from(reply))
+                    .await
+                    .is_ok();
+            }
+            if let Some(reply) = client.receive().await.ok() {
+                mp.dispatch_reply(reply).await;
+            }
+        });
+    }
+
+    async fn add_undelivered(dc: &connectors::DataConnector, attempt: i32) {
+        dc.undelivered_event
+            .add(vec![entities::publisher::UndeliveredEvent {
+                id: None,
+                correlation_id: "1".to_string(),
+                object_type: "OnRouteUpdate".to_string(),
+                service_name: "test".to_string(),
+                proto: providers::Proto::Mq.to_string(),
+                path: "service".to_string(),
+                body: "[]".to_string(),
+                attempt: attempt,
+                last_error: String::new(),
+                next_attempt_at: Utc::now() - chrono::Duration::seconds(1),
+                added_at: Utc::now(),
+            }])
+            .await
+            .unwrap();
+    }
+
+    async fn get_dead_letter_count(dc: &connectors::DataConnector) -> usize {
+        let (items, _) = dc
+            .dead_letter_event
+            .get(None, None, &Default::default())
+            .await
+            .unwrap();
+        items.len()
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let delay =
+            |attempt| (EventPublisher::get_next_attempt_at(attempt) - Utc::now()).num_seconds();
+        assert!((19..=20).contains(&delay(1)));
+        assert!((39..=40).contains(&delay(2)));
+        assert!((159..=160).contains(&delay(4)));
+        assert!((3599..=3600).contains(&delay(9)));
+        assert!((3599..=3600).contains(&delay(60)));
+        assert!((3599..=3600).contains(&delay(100)));
+    }
+
+    #[tokio::test]
+    async fn drain_outbox_removes_delivered_event() {
+        let (dp, dc) = get_data_connector(false);
+        let ep = get_publisher_for(dc.clone()).await;
+        add_outbox_event(&dc).await;
+        serve_one(&ep, REPLY_OK).await;
+        assert!(ep.drain_outbox().await.is_ok());
+        let db = dp.db.lock().await;
+        assert!(db.event_outbox.is_empty());
+        assert!(db.undelivered_event.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drain_outbox_removes_event_stored_for_retry() {
+        let (dp, dc) = get_data_connector(false);
+        let ep = get_publisher_for(dc.clone()).await;
+        add_outbox_event(&dc).await;
+        assert!(ep.drain_outbox().await.is_ok());
+        let db = dp.db.lock().await;
+        assert!(db.event_outbox.is_empty());
+        assert_eq!(db.undelivered_event.len(), 1);
+        assert_eq!(db.undelivered_event[0].attempt, 1);
+    }
+
+    #[tokio::test]
+    async fn drain_outbox_keeps_event_not_stored_for_retry() {
+        let (dp, dc) = get_data_connector(true);
+        let ep = get_publisher_for(dc.clone()).await;
+        add_outbox_event(&dc).await;
+        assert!(ep.drain_outbox().await.is_ok());
+        assert_eq!(dp.db.lock().await.event_outbox.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn redeliver_removes_delivered_event() {
+        let (dc, ep) = get_publisher().await;
+        add_undelivered(&dc, 1).await;
+        serve_one(&ep, REPLY_OK).await;
+        assert!(ep.redeliver().await.is_ok());
+        assert!(dc.undelivered_event.get_due(10).await.unwrap().is_empty());
+        assert_eq!(get_dead_letter_count(&dc).await, 0);
+    }
+
+    #[tokio::test]
+    async fn redeliver_postpones_failed_event() {
+        let (dc, ep) = get_publisher().await;
+        add_undelivered(&dc, 1).await;
+        assert!(ep.redeliver().await.is_ok());
+        // not due until backoff delay is over
+        assert!(dc.undelivered_event.get_due(10).await.unwrap().is_empty());
+        assert_eq!(get_dead_letter_count(&dc).await, 0);
+    }
+
+    #[tokio::test]
+    async fn redeliver_postpones_event_with_error_reply() {
+        let (dp, dc) = get_data_connector(false);
+        let ep = get_publisher_for(dc.clone()).await;
+        add_undelivered(&dc, 1).await;
+        serve_one(&ep, r#"{"error_code":"DatabaseError"}"#).await;
+        assert!(ep.redeliver().await.is_ok());
+        let db = dp.db.lock().await;
+        assert_eq!(db.undelivered_event.len(), 1);
+        assert_eq!(db.undelivered_event[0].attempt, 2);
+        assert_eq!(
+            db.undelivered_event[0].last_error,
+            errors::ErrorKind::BadReplyEvent.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn redeliver_moves_last_attempt_to_dead_letter() {
+        let (dc, ep) = get_publisher().await;
+        add_undelivered(&dc, MAX_DELIVERY_ATTEMPTS - 1).await;
+        assert!(ep.redeliver().await.is_ok());
+        assert!(dc.undelivered_event.get_due(10).await.unwrap().is_empty());
+        let (items, _) = dc
+            .dead_letter_event
+            .get(None, None, &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].attempt, MAX_DELIVERY_ATTEMPTS);
+        assert!(!items[0].last_error.is_empty());
+    }
+}
@@ -0,0 +1,419 @@
+use super::entities::route;
+use super::{connectors, executors, replies, traits::ObjectType};
+use futures::future::BoxFuture;
+use schemars::{schema_for, JsonSchema};
+use serde::{de, ser};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+pub const COMMAND_PATH_PREFIX: &str = "/command/"; //path of registered command is prefix and object type
+pub const EVENT_PATH_PREFIX: &str = "/event/"; //path of registered event is prefix and object type
+
+// request params are correlation_id, object_type and service_name of caller
+pub type Params = HashMap<String, String>;
+
+// handler gets raw body, replies with serialized reply or None for bad body
+type BoxedHandler = Box<
+    dyn Fn(String, Params) -> BoxFuture<'static, connectors::Result<Option<String>>> + Send + Sync,
+>;
+
+pub trait CommandHandler<C, R>: Send + Sync + 'static {
+    fn call(&self, command: C, params: Params) -> BoxFuture<'static, connectors::Result<R>>;
+}
+
+impl<C, R, F, Fut> CommandHandler<C, R> for F
+where
+    F: Fn(C, Params) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = connectors::Result<R>> + Send + 'static,
+{
+    fn call(&self, command: C, params: Params) -> BoxFuture<'static, connectors::Result<R>> {
+        Box::pin(self(command, params))
+    }
+}
+
+pub trait EventHandler<E>: Send + Sync + 'static {
+    fn call(
+        &self,
+        events: Vec<E>,
+        params: Params,
+    ) -> BoxFuture<'static, connectors::Result<replies::common::StandardReply>>;
+}
+
+impl<E, F, Fut> EventHandler<E> for F
+where
+    F: Fn(Vec<E>, Params) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = connectors::Result<replies::common::StandardReply>> + Send + 'static,
+{
+    fn call(
+        &self,
+        events: Vec<E>,
+        params: Params,
+    ) -> BoxFuture<'static, connectors::Result<replies::common::StandardReply>> {
+        Box::pin(self(events, params))
+    }
+}
+
+// Commands and events of downstream services, registered handler replaces built-in one
+// of same object type. Path, schema and route entry are derived from object type.
+pub struct HandlerRegistry {
+    path: HashMap<&'static str, String>,    // object type/path
+    handler: HashMap<String, BoxedHandler>, // path/handler
+    command: Vec<route::ServiceCommand>,
+    subscription: Vec<route::ServiceSubscription>,
+    schema: HashMap<&'static str, schemars::schema::RootSchema>,
+}
+
+impl HandlerRegistry {
+    pub fn new() -> HandlerRegistry {
+        HandlerRegistry {
+            path: HashMap::new(),
+            handler: HashMap::new(),
+            command: Vec::new(),
+            subscription: Vec::new(),
+            schema: HashMap::new(),
+        }
+    }
+
+    pub fn register_command<C, R>(&mut self, handler: impl CommandHandler<C, R>) -> &mut Self
+    where
+        C: de::DeserializeOwned + ObjectType + JsonSchema + Send + 'static,
+        R: ser::Serialize + ObjectType + JsonSchema + 'static,
+    {
+        let path = format!("{}{}", COMMAND_PATH_PREFIX, C::get_type_name());
+        self.register_command_at(&path, handler)
+    }
+
+    // command with own path, built-in commands keep paths of routes::path
+    pub fn register_command_at<C, R>(
+        &mut self,
+        path: &str,
+        handler: impl CommandHandler<C, R>,
+    ) -> &mut Self
+    where
+        C: de::DeserializeOwned + ObjectType + JsonSchema + Send + 'static,
+        R: ser::Serialize + ObjectType + JsonSchema + 'static,
+    {
+        let handler = Arc::new(handler);
+        let path = path.to_string();
+        self.insert(
+            C::get_type_name(),
+            path,
+            Box::new(move |body, params| {
+                let handler = handler.clone();
+                Box::pin(async move {
+                    let command: Option<C> = serde_json::from_str(&body).unwrap_or(None);
+                    match command {
+                        Some(c) => Ok(Some(serde_json::to_string(
+                            &handler.call(c, params).await?,
+                        )?)),
+                        None => Ok(None),
+                    }
+                })
+            }),
+        );
+        self.schema.insert(C::get_type_name(), schema_for!(C));
+        self.schema.insert(R::get_type_name(), schema_for!(R));
+        self.command.retain(|c| c.object_type != C::get_type_name());
+        self.command.push(route::ServiceCommand {
+            service_name: None,
+            priority: None,
+            object_type: C::get_type_name().to_string(),
+            description: String::new(),
+            reply_type: R::get_type_name().to_string(),
+            exec_mode: executors::ExecMode::Any.to_string(),
+            state: None,
+            path: None,
+        });
+        self
+    }
+
+    pub fn register_event<E>(&mut self, handler: impl EventHandler<E>) -> &mut Self
+    where
+        E: de::DeserializeOwned + ObjectType + JsonSchema + Send + 'static,
+    {
+        let path = format!("{}{}", EVENT_PATH_PREFIX, E::get_type_name());
+        self.register_event_at(&path, handler)
+    }
+
+    // event with own path, built-in events keep paths of routes::path
+    pub fn register_event_at<E>(&mut self, path: &str, handler: impl EventHandler<E>) -> &mut Self
+    where
+        E: de::DeserializeOwned + ObjectType + JsonSchema + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let path = path.to_string();
+        self.insert(
+            E::get_type_name(),
+            path,
+            Box::new(move |body, params| {
+                let handler = handler.clone();
+                Box::pin(async move {
+                    let events: Option<Vec<E>> = serde_json::from_str(&body).unwrap_or(None);
+                    match events {
+                        Some(e) => Ok(Some(serde_json::to_string(
+                            &handler.call(e, params).await?,
+                        )?)),
+                        None => Ok(None),
+                    }
+                })
+            }),
+        );
+        self.schema.insert(E::get_type_name(), schema_for!(E));
+        self.subscription
+            .retain(|s| s.object_type != E::get_type_name());
+        self.subscription.push(route::ServiceSubscription {
+            service_name: None,
+            object_type: E::get_type_name().to_string(),
+            path: None,
+        });
+        self
+    }
+
+    fn insert(&mut self, object_type: &'static str, path: String, handler: BoxedHandler) {
+        self.handler.insert(path.clone(), handler);
+        self.path.insert(object_type, path);
+    }
+
+    pub fn get_path(&self, object_type: &str) -> Option<&str> {
+        self.path.get(object_type).map(|p| p.as_str())
+    }
+
+    pub fn get_object_type(&self, path: &str) -> Option<&'static str> {
+        self.path.iter().find(|p| p.1 == path).map(|p| *p.0)
+    }
+
+    // event paths take list of events as body
+    pub fn is_event_path(&self, path: &str) -> bool {
+        self.subscription
+            .iter()
+            .any(|s| self.get_path(&s.object_type) == Some(path))
+    }
+
+    pub fn contains_path(&self, path: &str) -> bool {
+        self.handler.contains_key(path)
+    }
+
+    // None if path is not registered, Ok(None) if body is not valid for object type
+    pub async fn handle(
+        &self,
+        path: &str,
+        body: String,
+        params: Params,
+    ) -> Option<connectors::Result<Option<String>>> {
+        match self.handler.get(path) {
+            Some(h) => Some(h(body, params).await),
+            None => None,
+        }
+    }
+
+    // handlers of other registry replace ones of same object type,
+    // so commands of downstream service replace built-in ones
+    pub fn merge(&mut self, other: HandlerRegistry) {
+        self.handler.extend(other.handler);
+        self.path.extend(other.path);
+        self.schema.extend(other.schema);
+        for c in other.command {
+            self.command.retain(|i| i.object_type != c.object_type);
+            self.command.push(c);
+        }
+        for s in other.subscription {
+            self.subscription.retain(|i| i.object_type != s.object_type);
+            self.subscription.push(s);
+        }
+    }
+
+    // add registered commands and subscriptions to service route from settings,
+    // path is left empty so router takes default service path
+    pub fn update_route(&self, item: &mut route::Route) {
+        for c in &self.command {
+            if !item.command.iter().any(|i| i.object_type == c.object_type) {
+                item.command.push(c.clone());
+            }
+        }
+        for s in &self.subscription {
+            if !item
+                .subscription
+                .iter()
+                .any(|i| i.object_type == s.object_type)
+            {
+                item.subscription.push(s.clone());
+            }
+        }
+    }
+
+    pub fn update_schema(&self, schema: &mut HashMap<&'static str, schemars::schema::RootSchema>) {
+        for (k, v) in &self.schema {
+            schema.insert(k, v.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webapi::errors;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, JsonSchema, ObjectType)]
+    struct PingCommand {
+        text: String,
+    }
+
+    #[derive(Serialize, JsonSchema, ObjectType)]
+    struct PingReply {
+        text: String,
+        service_name: Option<String>,
+    }
+
+    #[derive(Deserialize, JsonSchema, ObjectType)]
+    struct OnPing {
+        text: String,
+    }
+
+    fn get_registry() -> HandlerRegistry {
+        let mut hr = HandlerRegistry::new();
+        hr.register_command(|cmd: PingCommand, params: Params| async move {
+            Ok(PingReply {
+                text: cmd.text,
+                service_name: params.get("service_name").cloned(),
+            })
+        })
+        .register_event(|events: Vec<OnPing>, _| async move {
+            Ok(replies::common::StandardReply {
+                error_code: if events.iter().all(|e| !e.text.is_empty()) {
+                    errors::ErrorCode::ReplyOk
+                } else {
+                    errors::ErrorCode::BadRequestError
+                },
+                error_name: None,
+            })
+        });
+        hr
+    }
+
+    #[tokio::test]
+    async fn dispatch_registered_command() {
+        let hr = get_registry();
+        let path = hr.get_path("PingCommand").unwrap().to_string();
+        assert_eq!(path, "/command/PingCommand");
+        assert_eq!(hr.get_object_type(&path), Some("PingCommand"));
+        assert!(!hr.is_event_path(&path));
+        let mut params = Params::new();
+        params.insert("service_name".to_string(), "caller".to_string());
+        let reply = hr
+            .handle(&path, r#"{"text":"ping"}"#.to_string(), params)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply, r#"{"text":"ping","service_name":"caller"}"#);
+    }
+
+    #[tokio::test]
+    async fn dispatch_registered_event() {
+        let hr = get_registry();
+        let path = hr.get_path("OnPing").unwrap().to_string();
+        assert!(hr.is_event_path(&path));
+        let reply = hr
+            .handle(&path, r#"[{"text":"ping"}]"#.to_string(), Params::new())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(reply.contains("\"error_code\":\"ReplyOk\""));
+    }
+
+    #[tokio::test]
+    async fn reject_bad_body_and_unknown_path() {
+        let hr = get_registry();
+        let reply = hr
+            .handle("/command/PingCommand", "{}".to_string(), Params::new())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(reply.is_none());
+        assert!(hr
+            .handle("/command/Unknown", "{}".to_string(), Params::new())
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn command_at_own_path() {
+        let mut hr = HandlerRegistry::new();
+        hr.register_command_at("/ping", |cmd: PingCommand, _| async move {
+            Ok(PingReply {
+                text: cmd.text,
+                service_name: None,
+            })
+        });
+        assert_eq!(hr.get_path("PingCommand"), Some("/ping"));
+        assert!(hr.contains_path("/ping"));
+    }
+
+    #[test]
+    fn event_at_own_path() {
+        let mut hr = HandlerRegistry::new();
+        hr.register_event_at("/on_ping", |_: Vec<OnPing>, _| async move {
+            Ok(replies::common::StandardReply {
+                error_code: errors::ErrorCode::ReplyOk,
+                error_name: None,
+            })
+        });
+        assert_eq!(hr.get_path("OnPing"), Some("/on_ping"));
+        assert!(hr.is_event_path("/on_ping"));
+    }
+
+    #[tokio::test]
+    async fn merged_handler_replaces_built_in_one() {
+        let mut hr = HandlerRegistry::new();
+        hr.register_command_at("/ping", |_: PingCommand, _| async move {
+            Ok(PingReply {
+                text: "built-in".to_string(),
+                service_name: None,
+            })
+        });
+        hr.merge(get_registry());
+        assert_eq!(hr.get_path("PingCommand"), Some("/command/PingCommand"));
+        assert!(hr.is_event_path("/event/OnPing"));
+        let reply = hr
+            .handle(
+                hr.get_path("PingCommand").unwrap(),
+                r#"{"text":"ping"}"#.to_string(),
+                Params::new(),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(reply.contains("\"text\":\"ping\""));
+        // built-in path is still served
+        assert!(hr.contains_path("/ping"));
+    }
+
+    #[test]
+    fn update_route_and_schema() {
+        let hr = get_registry();
+        let mut item = route::Route {
+            service_name: Some("webapi".to_string()),
+            description: String::new(),
+            priority: 0,
+            command: vec![],
+            event: vec![],
+            subscription: vec![],
+            path: None,
+        };
+        hr.update_route(&mut item);
+        hr.update_route(&mut item);
+        assert_eq!(item.command.len(), 1);
+        assert_eq!(item.command[0].reply_type, "PingReply");
+        assert_eq!(item.subscription.len(), 1);
+        let mut schema = HashMap::new();
+        hr.update_schema(&mut schema);
+        assert!(schema.contains_key("PingCommand"));
+        assert!(schema.contains_key("PingReply"));
+        assert!(schema.contains_key("OnPing"));
+    }
+}
@@ -1,8 +1,8 @@
-use super::super::{entities::car, errors, traits};
+use super::super::{entities::car, errors};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct GetCarReply {
     pub error_code: errors::ErrorCode,
 
@@ -13,11 +13,11 @@ pub struct GetCarReply {
     pub url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Vec<car::Car>>,
-}
+    pub total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
 
-impl traits::ObjectType for GetCarReply {
-    fn get_type_name() -> &'static str {
-        "GetCarReply"
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<car::Car>>,
 }
@@ -1,8 +1,8 @@
-use super::super::{errors, traits};
+use super::super::errors;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct StandardReply {
     pub error_code: errors::ErrorCode,
 
@@ -16,13 +16,7 @@ impl StandardReply {
     }
 }
 
-impl traits::ObjectType for StandardReply {
-    fn get_type_name() -> &'static str {
-        "StandardReply"
-    }
-}
-
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct AddIntIdsReply {
     pub error_code: errors::ErrorCode,
 
@@ -33,13 +27,7 @@ pub struct AddIntIdsReply {
     pub ids: Option<Vec<i32>>,
 }
 
-impl traits::ObjectType for AddIntIdsReply {
-    fn get_type_name() -> &'static str {
-        "AddIntIdsReply"
-    }
-}
-
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct AddStrIdsReply {
     pub error_code: errors::ErrorCode,
 
@@ -56,8 +44,31 @@ impl AddStrIdsReply {
     }
 }
 
-impl traits::ObjectType for AddStrIdsReply {
-    fn get_type_name() -> &'static str {
-        "AddStrIdsReply"
-    }
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
+pub struct ErrorReply {
+    pub error_code: errors::ErrorCode,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>, //offending field of body or param
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub cause: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub errors: Vec<ValidationError>, //body values not matching schema
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct ValidationError {
+    pub pointer: String,    //json pointer of failing value, empty for whole body
+    pub constraint: String, //schema keyword and expectation
 }
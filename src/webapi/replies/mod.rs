@@ -1,3 +1,4 @@
 pub mod car;
 pub mod common;
+pub mod publisher;
 pub mod route;
@@ -0,0 +1,23 @@
+use super::super::{entities::publisher, errors};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
+pub struct GetDeadLetterEventReply {
+    pub error_code: errors::ErrorCode,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<publisher::UndeliveredEvent>>,
+}
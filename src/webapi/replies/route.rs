@@ -1,8 +1,8 @@
-use super::super::{entities::route, errors, traits};
+use super::super::{entities::route, errors};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct GetRouteReply {
     pub error_code: errors::ErrorCode,
 
@@ -13,16 +13,16 @@ pub struct GetRouteReply {
     pub url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Vec<route::Route>>,
-}
+    pub total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
 
-impl traits::ObjectType for GetRouteReply {
-    fn get_type_name() -> &'static str {
-        "GetRouteReply"
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<route::Route>>,
 }
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct GetServiceCommandReply {
     pub error_code: errors::ErrorCode,
 
@@ -33,16 +33,16 @@ pub struct GetServiceCommandReply {
     pub url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Vec<route::ServiceCommand>>,
-}
+    pub total: Option<i64>,
 
-impl traits::ObjectType for GetServiceCommandReply {
-    fn get_type_name() -> &'static str {
-        "GetServiceCommandReply"
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<route::ServiceCommand>>,
 }
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct GetServiceEventReply {
     pub error_code: errors::ErrorCode,
 
@@ -53,16 +53,16 @@ pub struct GetServiceEventReply {
     pub url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Vec<route::ServiceEvent>>,
-}
+    pub total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
 
-impl traits::ObjectType for GetServiceEventReply {
-    fn get_type_name() -> &'static str {
-        "GetServiceEventReply"
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<route::ServiceEvent>>,
 }
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct GetServiceSubscriptionReply {
     pub error_code: errors::ErrorCode,
 
@@ -73,16 +73,16 @@ pub struct GetServiceSubscriptionReply {
     pub url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Vec<route::ServiceSubscription>>,
-}
+    pub total: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
 
-impl traits::ObjectType for GetServiceSubscriptionReply {
-    fn get_type_name() -> &'static str {
-        "GetServiceSubscriptionReply"
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<route::ServiceSubscription>>,
 }
 
-#[derive(Deserialize, Serialize, JsonSchema)]
+#[derive(Deserialize, Serialize, JsonSchema, ObjectType)]
 pub struct GetServiceReply {
     pub error_code: errors::ErrorCode,
 
@@ -93,11 +93,11 @@ pub struct GetServiceReply {
     pub url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Vec<route::Service>>,
-}
+    pub total: Option<i64>,
 
-impl traits::ObjectType for GetServiceReply {
-    fn get_type_name() -> &'static str {
-        "GetServiceReply"
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<i64>, //offset of next page, none on last page
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<route::Service>>,
 }
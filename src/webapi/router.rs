@@ -19,6 +19,7 @@ pub struct Router {
     http_provider: providers::HttpProvider,
     mq_provider: providers::MqProvider,
     remote_router: Option<HashMap<String, String>>,
+    local_path: HashMap<String, route::ServicePath>,
     service_path: RwLock<HashMap<String, HashMap<String, route::ServicePath>>>,
     command: RwLock<HashMap<String, entities::route::CommandRoute>>,
     subscription: RwLock<HashMap<String, Vec<entities::route::SubscriptionRoute>>>,
@@ -169,6 +170,8 @@ impl Router {
     pub async fn new(
         dc: Arc<connectors::DataConnector>,
         ac: Arc<access::AccessChecker>,
+        hp: providers::HttpProvider,
+        mp: providers::MqProvider,
         remote_router: Option<HashMap<String, String>>,
         mut path: HashMap<String, route::ServicePath>,
         mut service: HashMap<String, route::Route>,
@@ -246,16 +249,22 @@ impl Router {
             item.subscription = ns;
         }
         let is_local = remote_router.is_none();
-        let hp = providers::HttpProvider::new().await?;
-        let mp = providers::MqProvider::new().await?;
         let mut _service_paths = Vec::<route::ServicePath>::new();
         let mut _commands = Vec::<route::ServiceCommand>::new();
         let mut _subscriptions = Vec::<route::ServiceSubscription>::new();
         if is_local {
-            &dc.route.add(service.values().cloned().collect()).await?;
+            dc.route
+                .add(service.values().cloned().collect(), vec![])
+                .await?;
             let p = &dc.route.get_service_path(None).await?;
-            let c = &dc.route.get_command(None).await?;
-            let s = &dc.route.get_subscription(None).await?;
+            let (c, _) = &dc
+                .route
+                .get_command(None, None, &commands::common::Page::default())
+                .await?;
+            let (s, _) = &dc
+                .route
+                .get_subscription(None, None, &commands::common::Page::default())
+                .await?;
             _service_paths = p.to_vec();
             _commands = c.to_vec();
             _subscriptions = s.to_vec();
@@ -266,7 +275,7 @@ impl Router {
                 .contains_key(&providers::Proto::Http.to_string())
             {
                 //todo: support mq
-                return Err(errors::UnsupportedProtoError.into());
+                return Err(errors::ErrorKind::UnsupportedProto.into());
             }
             let r = remote_router
                 .as_ref()
@@ -294,6 +303,7 @@ impl Router {
                         serde_json::to_string(&commands::route::GetServiceCommand {
                             filter: None,
                             services: None,
+                            page: commands::common::Page::default(),
                         })
                         .unwrap(),
                     ),
@@ -319,6 +329,7 @@ impl Router {
                         serde_json::to_string(&commands::route::GetServiceSubscription {
                             filter: None,
                             services: None,
+                            page: commands::common::Page::default(),
                         })
                         .unwrap(),
                     ),
@@ -347,7 +358,7 @@ impl Router {
                     .unwrap()
                     .to_vec();
             } else {
-                return Err(errors::GeRemoteRouterError.into());
+                return Err(errors::ErrorKind::GetRemoteRouter.into());
             }
         }
         Ok(Router {
@@ -357,6 +368,7 @@ impl Router {
             mq_provider: mp,
             schema: schema::make_schema(),
             remote_router: remote_router,
+            local_path: path,
             service_path: RwLock::new(Router::make_service_path_hash_map(_service_paths)),
             command: RwLock::new(Router::make_command_hash_map(_commands)),
             subscription: RwLock::new(Router::make_subscription_hash_map(_subscriptions)),
@@ -388,7 +400,7 @@ impl Router {
             *ss = Router::make_subscription_hash_map(subscriptions);
             Ok(true)
         } else {
-            Err(errors::DataIntegrityError.into())
+            Err(errors::ErrorKind::DataIntegrity.into())
         }
     }
 
@@ -403,10 +415,21 @@ impl Router {
             if sn.contains_key(&proto.to_string()) {
                 Ok(sn.get(&proto.to_string()).unwrap().clone())
             } else {
-                Err(errors::UnsupportedProtoError.into())
+                Err(errors::ErrorKind::UnsupportedProto.into())
             }
         } else {
-            Err(errors::UnknownServiceNameError.into())
+            Err(errors::ErrorKind::UnknownServiceName.into())
+        }
+    }
+
+    pub fn get_local_service_path(
+        &self,
+        proto: providers::Proto,
+    ) -> connectors::Result<entities::route::ServicePath> {
+        if self.local_path.contains_key(&proto.to_string()) {
+            Ok(self.local_path.get(&proto.to_string()).unwrap().clone())
+        } else {
+            Err(errors::ErrorKind::UnsupportedProto.into())
         }
     }
 
@@ -423,7 +446,7 @@ impl Router {
                 .unwrap()
                 .clone())
         } else {
-            Err(errors::UnknownCommandError.into())
+            Err(errors::ErrorKind::UnknownCommand.into())
         }
     }
 
@@ -455,7 +478,7 @@ impl Router {
                 .as_ref()
                 .unwrap()
                 .route
-                .remove(s)
+                .remove(s, vec![])
                 .await?;
             debug!("remove service route");
         } else {
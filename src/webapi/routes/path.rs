@@ -2,10 +2,17 @@ pub const HELTH: &str = "/helth"; //return uptime is body if alive
 pub const SCHEMA: &str = "/schema"; //require object_type
 pub const ERROR: &str = "/error"; //require error_code
 pub const STATE: &str = "/state"; //require async_command_id
+pub const REQUEST: &str = "/request"; //require object_type, command path by object type
+pub const EVENT: &str = "/event"; //require object_type, event path by object type
+pub const REPLY: &str = "/reply"; //require async_command_id, reply for sended async command
 
 pub const USR_ITEMS: &str = "/usrs";
 pub const USR_SIGHN_IN: &str = "/usr/signin";
 pub const USR_SIGHN_UP: &str = "/usr/signup";
+pub const USR_CHANGE_PASSWORD: &str = "/usr/change_password";
+pub const USR_DISABLE: &str = "/usr/disable";
+
+pub const ACCESS_RELOAD: &str = "/access/reload";
 
 pub const ROUTER_ROUTE_GET: &str = "/router/route/get";
 pub const ROUTER_ROUTE_ADD: &str = "/router/route/add";
@@ -14,6 +21,8 @@ pub const ROUTER_COMMAND_GET: &str = "/router/command/get";
 pub const ROUTER_EVENT_GET: &str = "/router/event/get";
 pub const ROUTER_SUBSCIBTION_GET: &str = "/router/subscription/get";
 pub const ROUTER_SERVICE_GET: &str = "/router/service/get";
+pub const ROUTER_DEAD_LETTER_GET: &str = "/router/dead_letter/get";
+pub const ROUTER_DEAD_LETTER_REPLAY: &str = "/router/dead_letter/replay";
 pub const ROUTER_EVENT_ON_SERVICE_UNAVAILABLE: &str = "/router/event/on_service_unavailable";
 
 pub const EVENT_ON_ROUTE_UPDATE: &str = "/event/on_route_update";
@@ -26,4 +35,67 @@ pub const CAR_REMOVE: &str = "/car/remove";
 pub const CAR_RESERVE: &str = "/car/reserve";
 
 #[cfg(test)]
-pub const ROUTE_WITH_EMPTY_BODY: [&str; 4] = [HELTH, USR_SIGHN_IN, USR_SIGHN_UP, USR_ITEMS];
+pub const ROUTE_WITH_EMPTY_BODY: [&str; 2] = [HELTH, USR_ITEMS];
+
+// submitted by derive of ObjectType for type with #[object_type(path = ...)],
+// paths of registry handlers are kept by registry
+pub struct ObjectPath {
+    pub type_name: &'static str,
+    pub path: &'static str,
+}
+
+inventory::collect!(ObjectPath);
+
+pub fn get_object_type_path(object_type: &str) -> Option<&'static str> {
+    inventory::iter::<ObjectPath>
+        .into_iter()
+        .find(|i| i.type_name == object_type)
+        .map(|i| i.path)
+}
+
+pub fn get_path_object_type(path: &str) -> Option<&'static str> {
+    inventory::iter::<ObjectPath>
+        .into_iter()
+        .find(|i| i.path == path)
+        .map(|i| i.type_name)
+}
+
+// admin paths are allowed to admin usr only
+pub fn is_admin_path(path: &str) -> bool {
+    [USR_SIGHN_UP, ACCESS_RELOAD, ROUTER_DEAD_LETTER_REPLAY].contains(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::traits::ObjectType;
+    use super::*;
+    use schemars::JsonSchema;
+
+    // webapi path is set as for service linking library
+    #[derive(JsonSchema, ObjectType)]
+    #[object_type(crate = "super::super::super", path = "/test/object_path")]
+    #[allow(dead_code)]
+    struct TestObjectPath {
+        id: i32,
+    }
+
+    #[test]
+    fn paths_of_derived_types() {
+        assert_eq!(get_object_type_path("SignIn"), Some(USR_SIGHN_IN));
+        assert_eq!(
+            get_object_type_path("OnRouteUpdate"),
+            Some(EVENT_ON_ROUTE_UPDATE)
+        );
+        assert_eq!(
+            get_path_object_type(ROUTER_DEAD_LETTER_GET),
+            Some("GetDeadLetterEvent")
+        );
+        assert_eq!(
+            get_object_type_path(TestObjectPath::get_type_name()),
+            Some("/test/object_path")
+        );
+        // registry keeps paths of car handlers
+        assert_eq!(get_object_type_path("GetCar"), None);
+        assert_eq!(get_path_object_type("/unknown"), None);
+    }
+}
@@ -1,5 +1,6 @@
 use super::super::{
-    access, commands, connectors, errors, events, executors, handlers, publishers, router,
+    access, connectors, entities, errors, executors, handlers, providers, registry, replies,
+    router, schema,
 };
 use super::{index, path};
 use bytes::Buf;
@@ -7,66 +8,47 @@ use hyper::{header, Body, Method, Request, Response, StatusCode};
 use serde::ser;
 use std::collections::HashMap;
 use std::convert::From;
+use std::io::Read;
 use std::sync::Arc;
 
+const RETRY_AFTER_IN_SECONDS: u64 = 60; //suggested delay when async command queue is full
+pub const LOCALE_PARAM: &str = "locale"; //locale of error names, Accept-Language header for http
+pub const AUTHORIZED_USR_PARAM: &str = "authorized_usr_name"; //set from authorization only
+
 type GenericError = Box<dyn std::error::Error + Send + Sync>;
 type Result<T> = std::result::Result<T, GenericError>;
 
-// pub type Handler = dyn FnOnce(
-//     Body,
-//     HashMap<String, String>,
-// ) -> Box<dyn Future<Output = (Body, HashMap<String, String>)>>;
-
-pub type Handler = fn(Body, HashMap<String, String>) -> (Body, HashMap<String, String>);
-
-// pub async fn async_command_handler(
-//     body: Body,
-//     param: HashMap<String, String>,
-// ) -> (Body, HashMap<String, String>) {
-//     (Body::empty(), HashMap::<String, String>::new())
-// }
-
-// pub async fn event_handler(
-//     body: Body,
-//     param: HashMap<String, String>,
-// ) -> (Body, HashMap<String, String>) {
-//     (Body::empty(), HashMap::<String, String>::new())
-// }
-
 pub async fn service_route(
     req: Request<Body>,
     dc: Arc<connectors::DataConnector>,
     ac: Arc<access::AccessChecker>,
     ce: Arc<executors::CommandExecutor>,
-    ep: Arc<publishers::EventPublisher>,
     rt: Arc<router::Router>,
-    hr: Arc<HashMap<&str, Handler>>,
+    hr: Arc<registry::HandlerRegistry>,
 ) -> Result<Response<Body>> {
-    if hr.contains_key("s1") {
-        let f = hr.get("s1").unwrap();
-        let _ = f(Body::empty(), HashMap::<String, String>::new());
-    }
     let (parts, body) = req.into_parts();
+    let accept_language = parts
+        .headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok());
     if parts.method == Method::POST {
-        let mut is_authorized = false;
-        if parts.headers.get("Authorization").is_some() {
-            is_authorized = ac.is_authorized_by_header(
-                parts
-                    .headers
-                    .get("Authorization")
-                    .unwrap()
-                    .to_str()
-                    .unwrap(),
+        let authorized_usr_name = match parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(h) => ac.get_authorized_usr(h).await,
+            None => None,
+        };
+        if authorized_usr_name.is_none() {
+            let mut r = resp_error(&dc, accept_language, errors::ErrorKind::Unauthorized.into());
+            r.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                header::HeaderValue::from_static("Basic realm=\"Access to microservice\""),
             );
+            return Ok(r);
         }
-        if !is_authorized {
-            return Ok(Response::builder()
-                .status(StatusCode::UNAUTHORIZED)
-                .header("WWW-Authenticate", "Basic realm=\"Access to microservice\"")
-                .body(Body::empty())
-                .unwrap());
-        }
-        let params: HashMap<String, String> = parts
+        let mut params: HashMap<String, String> = parts
             .uri
             .query()
             .map(|v| {
@@ -75,308 +57,336 @@ pub async fn service_route(
                     .collect()
             })
             .unwrap_or_else(HashMap::new);
-        if !params.contains_key("correlation_id") {
-            return Ok(resp_with_code(StatusCode::BAD_REQUEST));
+        if let Some(l) = accept_language {
+            params
+                .entry(LOCALE_PARAM.to_string())
+                .or_insert_with(|| l.to_string());
         }
-        let correlation_id = params.get("correlation_id").unwrap();
-        let _service_name = if params.contains_key("service_name") {
-            Some(params.get("service_name").unwrap())
-        } else {
-            None
-        };
-        let reader = hyper::body::aggregate(body).await?.reader();
+        params.insert(
+            AUTHORIZED_USR_PARAM.to_string(),
+            authorized_usr_name.unwrap(),
+        );
+        request_route(
+            parts.uri.path(),
+            params,
+            body,
+            providers::Proto::Http,
+            &dc,
+            &ac,
+            &ce,
+            &rt,
+            &hr,
+        )
+        .await
+    } else if parts.method == Method::GET {
         Ok(match parts.uri.path() {
-            path::USR_SIGHN_IN => resp(handlers::usr::signin(&dc).await),
-            path::USR_SIGHN_UP => resp(handlers::usr::signup(&dc).await),
-            path::ROUTER_ROUTE_GET => {
-                let cmd: Option<commands::route::GetRoute> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::route::get(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("get_route handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::ROUTER_COMMAND_GET => {
-                let cmd: Option<commands::route::GetServiceCommand> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::route::get_command(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("get_route_command handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::ROUTER_EVENT_GET => {
-                let cmd: Option<commands::route::GetServiceEvent> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::route::get_event(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("get_route_event handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+            "/" => index::handler().await,
+            "/openapi.json" => index::spec_json().await,
+            "/openapi.yaml" => index::spec_yaml().await,
+            _ => resp_error(&dc, accept_language, errors::ErrorKind::NotFound.into()),
+        })
+    } else {
+        Ok(resp_error(
+            &dc,
+            accept_language,
+            errors::ErrorKind::NotFound.into(),
+        ))
+    }
+}
+
+// shared by http service route and mq receiver, caller is already authorized,
+// every error is replied with context of request
+pub async fn request_route(
+    uri_path: &str,
+    params: HashMap<String, String>,
+    body: Body,
+    proto: providers::Proto,
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    ce: &executors::CommandExecutor,
+    rt: &router::Router,
+    hr: &registry::HandlerRegistry,
+) -> Result<Response<Body>> {
+    let correlation_id = params.get("correlation_id").cloned();
+    let object_type = params.get("object_type").cloned();
+    let locale = params.get(LOCALE_PARAM).cloned();
+    match route_request(uri_path, params, body, proto, dc, ac, ce, rt, hr).await {
+        Ok(r) => Ok(r),
+        Err(e) => {
+            let mut e = errors::Error::from_boxed(e);
+            if e.correlation_id.is_none() {
+                e.correlation_id = correlation_id;
             }
-            path::ROUTER_SUBSCIBTION_GET => {
-                let cmd: Option<commands::route::GetServiceSubscription> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::route::get_subscription(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("get_route_subscription handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+            if e.object_type.is_none() {
+                e.object_type = object_type;
             }
-            path::ROUTER_SERVICE_GET => {
-                let cmd: Option<commands::route::GetService> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::route::get_service(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("get_service handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
+            Ok(resp_error(dc, locale.as_deref(), e))
+        }
+    }
+}
+
+async fn route_request(
+    uri_path: &str,
+    params: HashMap<String, String>,
+    body: Body,
+    proto: providers::Proto,
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    ce: &executors::CommandExecutor,
+    rt: &router::Router,
+    hr: &registry::HandlerRegistry,
+) -> connectors::Result<Response<Body>> {
+    if !params.contains_key("correlation_id") {
+        return Err(errors::Error::new(errors::ErrorKind::BadRequest)
+            .with_field("correlation_id")
+            .into());
+    }
+    let correlation_id = params.get("correlation_id").unwrap();
+    let locale = dc
+        .error
+        .negotiate(params.get(LOCALE_PARAM).map(|l| l.as_str()));
+    let uri_path = match uri_path {
+        path::REQUEST | path::EVENT => {
+            match params
+                .get("object_type")
+                .and_then(|ot| hr.get_path(ot).or_else(|| path::get_object_type_path(ot)))
+            {
+                Some(p) => p,
+                None => {
+                    error!("request handler: unknown object type");
+                    return Err(errors::Error::new(errors::ErrorKind::NotFound)
+                        .with_field("object_type")
+                        .into());
                 }
             }
-            path::ROUTER_ROUTE_ADD => {
-                let cmd: Option<commands::route::AddRoute> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    let res = match handlers::route::add(&dc, cmd.unwrap()).await {
-                        Ok(r) => r,
-                        Err(e) => {
-                            error!("handler: {}", e);
-                            return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                        }
-                    };
-                    if res.0.is_ok() {
-                        match ep.send(correlation_id, res.1.unwrap()).await {
-                            Ok(_) => {}
-                            Err(e) => {
-                                error!("event publisher: {}", e);
-                                return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                            }
-                        }
-                    }
-                    resp(Ok(res.0))
-                } else {
-                    error!("add_routes handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+        }
+        _ => uri_path,
+    };
+    if path::is_admin_path(uri_path)
+        && !params
+            .get(AUTHORIZED_USR_PARAM)
+            .map_or(false, |u| ac.is_admin(u))
+    {
+        error!("request handler: {} is allowed to admin only", uri_path);
+        return Err(errors::Error::new(errors::ErrorKind::Forbidden).into());
+    }
+    let exec_mode = match params.get("exec_mode") {
+        Some(m) => match m.parse::<executors::ExecMode>() {
+            Ok(m) => m,
+            Err(_) => {
+                error!("request handler: unsupported exec mode {}", m);
+                return Err(errors::Error::new(errors::ErrorKind::UnsupportedExecMode)
+                    .with_field("exec_mode")
+                    .into());
             }
-            path::ROUTER_ROUTE_REMOVE => {
-                let cmd: Option<commands::route::RemoveRoute> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    let res = match handlers::route::remove(&dc, cmd.unwrap()).await {
-                        Ok(r) => r,
-                        Err(e) => {
-                            error!("handler: {}", e);
-                            return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                        }
-                    };
-                    if res.0.is_ok() {
-                        match ep.send(correlation_id, res.1.unwrap()).await {
-                            Ok(_) => {}
-                            Err(e) => {
-                                error!("event publisher: {}", e);
-                                return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                            }
-                        }
-                    }
-                    resp(Ok(res.0))
-                } else {
-                    error!("remove_routes handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+        },
+        None => executors::ExecMode::Any,
+    };
+    let body = hyper::body::to_bytes(body).await?;
+    let validation_errors = validate_body(uri_path, &body, rt, hr);
+    if !validation_errors.is_empty() {
+        error!(
+            "request handler: body of {} does not match schema",
+            uri_path
+        );
+        return Err(errors::Error::new(errors::ErrorKind::BadRequest)
+            .with_errors(validation_errors)
+            .into());
+    }
+    let mut reader = body.reader();
+    if exec_mode == executors::ExecMode::Async {
+        // reply is sent with client access of caller service
+        for p in &["object_type", "reply_to", "service_name"] {
+            if !params.contains_key(*p) {
+                error!("async command handler: bad request");
+                return Err(errors::Error::new(errors::ErrorKind::BadRequest)
+                    .with_field(p)
+                    .into());
             }
-            path::ROUTER_EVENT_ON_SERVICE_UNAVAILABLE => {
-                let events: Option<Vec<events::route::OnServiceUnavailable>> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if events.is_some() {
-                    let res =
-                        match handlers::route::on_service_unavailable(&dc, &rt, events.unwrap())
-                            .await
-                        {
-                            Ok(r) => r,
-                            Err(e) => {
-                                error!("handler: {}", e);
-                                return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                            }
-                        };
-                    if res.0.is_ok() {
-                        match ep.send(correlation_id, res.1.unwrap()).await {
-                            Ok(_) => {}
-                            Err(e) => {
-                                error!("event publisher: {}", e);
-                                return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                            }
-                        }
-                    }
-                    resp(Ok(res.0))
-                } else {
-                    error!("on_service_unavailable handler: bad body");
-                    resp_with_code(StatusCode::BAD_REQUEST)
-                }
+        }
+        let mut request_body = String::new();
+        reader.read_to_string(&mut request_body)?;
+        let now = chrono::offset::Utc::now();
+        let reply = handlers::executor::add_received_async_command(
+            &dc,
+            &ce,
+            vec![entities::executor::ReceivedAsyncCommand {
+                id: correlation_id.to_string(),
+                object_type: params.get("object_type").unwrap().to_string(),
+                service_name: params.get("service_name").unwrap().to_string(),
+                request_body: request_body,
+                state: entities::executor::CommandSystemState::Initial.to_string(),
+                change_state_event: 0,
+                reply_body: String::new(),
+                proto: proto.to_string(),
+                reply_to: params.get("reply_to").unwrap().to_string(),
+                added_at: now,
+                state_changed_at: now,
+                history: None,
+                authorized_usr_name: params.get(AUTHORIZED_USR_PARAM).cloned(),
+                locale: params.get(LOCALE_PARAM).cloned(),
+            }],
+        )
+        .await;
+        if let Ok(r) = &reply {
+            if r.error_code == errors::ErrorCode::TooManyRequestsError {
+                return Ok(resp_too_many_requests(r));
             }
-            path::EVENT_ON_ROUTE_UPDATE => {
-                let events: Option<Vec<events::route::OnRouteUpdate>> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if events.is_some() {
-                    resp(handlers::route::on_route_update(&dc, &rt, events.unwrap()).await)
-                } else {
-                    error!("on_route_update handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+        }
+        return resp(dc, locale, reply);
+    }
+    if hr.contains_path(uri_path) {
+        let mut request_body = String::new();
+        reader.read_to_string(&mut request_body)?;
+        return match hr.handle(uri_path, request_body, params).await {
+            Some(Ok(Some(r))) => resp(
+                dc,
+                locale,
+                Ok(serde_json::from_str::<serde_json::Value>(&r)?),
+            ),
+            Some(Ok(None)) => {
+                error!("{} handler: bad body", uri_path);
+                Err(errors::ErrorKind::BadRequest.into())
             }
-            path::EVENT_ON_ASYNC_COMMAND_STATE_CHANGE => {
-                let events: Option<Vec<events::executor::OnAsyncCommandStateChange>> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if events.is_some() {
-                    resp(
-                        handlers::executor::on_async_command_state_change(
-                            &dc,
-                            &rt,
-                            events.unwrap(),
-                        )
-                        .await,
+            Some(Err(e)) => Err(e),
+            None => Err(errors::ErrorKind::NotFound.into()),
+        };
+    }
+    // built-in commands and events are handled by registry, transport paths are left
+    match uri_path {
+        // usr list has no command type, it is requested with empty body
+        path::USR_ITEMS => resp(dc, locale, handlers::usr::get(&dc, None).await),
+        path::REPLY => {
+            if params.contains_key("async_command_id") {
+                let mut reply_body = String::new();
+                reader.read_to_string(&mut reply_body)?;
+                resp(
+                    dc,
+                    locale,
+                    handlers::executor::complete_sended_async_command(
+                        &dc,
+                        params.get("async_command_id").unwrap().as_str(),
+                        reply_body,
                     )
-                } else {
-                    error!("on_async_command_state_change handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::USR_ITEMS => resp(handlers::usr::get(&dc, None).await),
-            path::CAR_GET => {
-                let cmd: Option<commands::car::GetCar> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::car::get(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("get_car handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::CAR_ADD => {
-                let cmd: Option<commands::car::AddCar> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::car::add(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("add_cars handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+                    .await,
+                )
+            } else {
+                error!("reply handler: bad request");
+                return Err(errors::Error::new(errors::ErrorKind::BadRequest)
+                    .with_field("async_command_id")
+                    .into());
             }
-            path::CAR_CHANGE => {
-                let cmd: Option<commands::car::ChangeCar> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::car::change(&dc, &ce, cmd.unwrap()).await)
-                } else {
-                    error!("change_cars handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::CAR_REMOVE => {
-                let cmd: Option<commands::car::RemoveCar> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::car::remove(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("remove_cars handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::CAR_RESERVE => {
-                let cmd: Option<commands::car::ReserveCar> =
-                    serde_json::from_reader(reader).unwrap_or(None);
-                if cmd.is_some() {
-                    resp(handlers::car::reserve(&dc, cmd.unwrap()).await)
-                } else {
-                    error!("reserve_cars handler: bad body");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
-            }
-            path::STATE => {
-                if params.contains_key("async_command_id") {
-                    match ce
-                        .get_received_async_command_state(
-                            params.get("async_command_id").unwrap().as_str(),
-                        )
-                        .await
-                    {
-                        Ok(r) => resp(Ok(r)),
-                        Err(e) => {
-                            error!("state handler: {}", e);
-                            if let Some(_) = e.downcast_ref::<errors::AsyncCommandNotFoundError>() {
-                                return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                            } else {
-                                return Ok(resp_with_code(StatusCode::INTERNAL_SERVER_ERROR));
-                            }
-                        }
-                    }
-                } else {
-                    error!("state handler: bad request");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+        }
+        path::STATE => {
+            if params.contains_key("async_command_id") {
+                resp(
+                    dc,
+                    locale,
+                    ce.get_received_async_command_state(
+                        params.get("async_command_id").unwrap().as_str(),
+                    )
+                    .await,
+                )
+            } else {
+                error!("state handler: bad request");
+                return Err(errors::Error::new(errors::ErrorKind::BadRequest)
+                    .with_field("async_command_id")
+                    .into());
             }
-            path::SCHEMA => {
-                if params.contains_key("object_type") {
-                    let ot = params.get("object_type").unwrap().as_str();
-                    if rt.schema.contains_key(ot) {
-                        resp_schema(&rt.schema.get(ot))
-                    } else {
-                        error!("schema handler: bad request");
-                        return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                    }
+        }
+        path::SCHEMA => {
+            if params.contains_key("object_type") {
+                let ot = params.get("object_type").unwrap().as_str();
+                if rt.schema.contains_key(ot) {
+                    Ok(resp_schema(&rt.schema.get(ot)))
                 } else {
                     error!("schema handler: bad request");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
+                    return Err(errors::Error::new(errors::ErrorKind::NotFound)
+                        .with_field("object_type")
+                        .into());
                 }
+            } else {
+                error!("schema handler: bad request");
+                return Err(errors::Error::new(errors::ErrorKind::BadRequest)
+                    .with_field("object_type")
+                    .into());
             }
-            path::ERROR => {
-                if params.contains_key("error_code") {
-                    let ec = params.get("error_code").unwrap().as_str();
-                    match handlers::route::get_error(&dc, ec) {
-                        Ok(r) => resp(Ok(r)),
-                        Err(e) => {
-                            error!("error handler: {}", e);
-                            return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                        }
-                    }
-                } else {
-                    error!("error handler: bad request");
-                    return Ok(resp_with_code(StatusCode::BAD_REQUEST));
-                }
+        }
+        path::ERROR => match params.get("error_code") {
+            Some(ec) => resp(dc, locale, handlers::route::get_error(&dc, ec, locale)),
+            None => resp(dc, locale, handlers::route::get_errors(&dc, locale)),
+        },
+        path::HELTH => resp(dc, locale, handlers::route::get_helth()),
+        _ => Err(errors::ErrorKind::NotFound.into()),
+    }
+}
+
+// error name of reply is replaced by name in requested locale
+fn resp<T>(
+    dc: &connectors::DataConnector,
+    locale: &str,
+    res: connectors::Result<T>,
+) -> connectors::Result<Response<Body>>
+where
+    T: ser::Serialize,
+{
+    let mut reply = serde_json::to_value(&res?)?;
+    if let Some(r) = reply.as_object_mut() {
+        let ec = r
+            .get("error_code")
+            .and_then(|ec| serde_json::from_value::<errors::ErrorCode>(ec.clone()).ok());
+        if let (Some(ec), Some(en)) = (ec, r.get_mut("error_name")) {
+            if !en.is_null() {
+                *en = serde_json::Value::String(dc.error.get_name(&ec, Some(locale)));
             }
-            path::HELTH => resp(handlers::route::get_helth()),
-            _ => resp_with_code(StatusCode::NOT_FOUND),
-        })
-    } else if parts.method == Method::GET {
-        Ok(match parts.uri.path() {
-            "/" => index::handler().await,
-            "/openapi.json" => index::spec_json().await,
-            "/openapi.yaml" => index::spec_yaml().await,
-            _ => resp_with_code(StatusCode::NOT_FOUND),
-        })
-    } else {
-        Ok(resp_with_code(StatusCode::NOT_FOUND))
+        }
     }
+    Ok(Response::builder()
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CONTENT_LANGUAGE, locale)
+        .body(Body::from(serde_json::to_string(&reply)?))
+        .unwrap())
 }
 
-fn resp<T>(res: connectors::Result<T>) -> Response<Body>
+fn resp_too_many_requests<T>(res: T) -> Response<Body>
 where
     T: ser::Serialize,
 {
-    match res {
-        Ok(r) => Response::builder()
-            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
-            .body(Body::from(serde_json::to_string(&r).unwrap()))
-            .unwrap(),
-        Err(e) => {
-            error!("handler: {}", e);
-            resp_with_code(StatusCode::INTERNAL_SERVER_ERROR)
+    Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::RETRY_AFTER, RETRY_AFTER_IN_SECONDS.to_string())
+        .body(Body::from(serde_json::to_string(&res).unwrap()))
+        .unwrap()
+}
+
+// validate by schema of object type on path, path without schema has no body
+fn validate_body(
+    uri_path: &str,
+    body: &[u8],
+    rt: &router::Router,
+    hr: &registry::HandlerRegistry,
+) -> Vec<replies::common::ValidationError> {
+    let root = match hr
+        .get_object_type(uri_path)
+        .or_else(|| path::get_path_object_type(uri_path))
+        .and_then(|ot| rt.schema.get(ot))
+    {
+        Some(r) => r,
+        None => return vec![],
+    };
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(v) => {
+            if hr.is_event_path(uri_path) {
+                schema::validate_items(root, &v)
+            } else {
+                schema::validate(root, &v)
+            }
         }
+        Err(e) => vec![replies::common::ValidationError {
+            pointer: String::new(),
+            constraint: format!("json: {}", e),
+        }],
     }
 }
 
@@ -390,9 +400,37 @@ where
         .unwrap()
 }
 
-pub fn resp_with_code(status: StatusCode) -> Response<Body> {
+// status and error code are taken from kind, causes are replied for client errors only,
+// internal ones are in log
+pub fn resp_error(
+    dc: &connectors::DataConnector,
+    locale: Option<&str>,
+    e: errors::Error,
+) -> Response<Body> {
+    let locale = dc.error.negotiate(locale);
+    let status = e.kind.get_status_code();
+    let ec = e.kind.get_error_code();
+    let causes = e.get_causes();
+    error!("handler: {} {:?}", e, causes);
     Response::builder()
         .status(status)
-        .body(Body::empty())
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CONTENT_LANGUAGE, locale)
+        .body(Body::from(
+            serde_json::to_string(&replies::common::ErrorReply {
+                error_code: ec,
+                error_name: Some(dc.error.get_name(&ec, Some(locale))),
+                object_type: e.object_type,
+                correlation_id: e.correlation_id,
+                field: e.field,
+                cause: if status.is_client_error() {
+                    causes
+                } else {
+                    vec![]
+                },
+                errors: e.errors,
+            })
+            .unwrap(),
+        ))
         .unwrap()
 }
@@ -1,84 +1,514 @@
-use super::{commands, events, replies, traits::ObjectType};
-use schemars::schema_for;
+use super::replies;
+use regex::Regex;
+use schemars::schema::{
+    ArrayValidation, InstanceType, NumberValidation, ObjectValidation, Schema, SchemaObject,
+    SingleOrVec, StringValidation, SubschemaValidation,
+};
+use serde_json::Value;
 use std::collections::HashMap;
 
+// submitted by derive of ObjectType for every command, reply and event
+pub struct ObjectSchema {
+    pub type_name: &'static str,
+    pub schema: fn() -> schemars::schema::RootSchema,
+}
+
+inventory::collect!(ObjectSchema);
+
 pub fn make_schema() -> HashMap<&'static str, schemars::schema::RootSchema> {
     let mut hm = HashMap::<&str, schemars::schema::RootSchema>::new();
-    //commands
-    hm.insert(
-        commands::route::GetRoute::get_type_name(),
-        schema_for!(commands::route::GetRoute),
-    );
-    hm.insert(
-        commands::route::AddRoute::get_type_name(),
-        schema_for!(commands::route::AddRoute),
-    );
-    hm.insert(
-        commands::route::RemoveRoute::get_type_name(),
-        schema_for!(commands::route::RemoveRoute),
-    );
-    hm.insert(
-        commands::route::GetServiceCommand::get_type_name(),
-        schema_for!(commands::route::GetServiceCommand),
-    );
-    hm.insert(
-        commands::route::GetServiceEvent::get_type_name(),
-        schema_for!(commands::route::GetServiceEvent),
-    );
-    hm.insert(
-        commands::route::GetServiceSubscription::get_type_name(),
-        schema_for!(commands::route::GetServiceSubscription),
-    );
-    hm.insert(
-        commands::route::GetService::get_type_name(),
-        schema_for!(commands::route::GetService),
-    );
-    //replies
-    hm.insert(
-        replies::common::StandardReply::get_type_name(),
-        schema_for!(replies::common::StandardReply),
-    );
-    hm.insert(
-        replies::common::AddIntIdsReply::get_type_name(),
-        schema_for!(replies::common::AddIntIdsReply),
-    );
-    hm.insert(
-        replies::common::AddStrIdsReply::get_type_name(),
-        schema_for!(replies::common::AddStrIdsReply),
-    );
-    hm.insert(
-        replies::route::GetRouteReply::get_type_name(),
-        schema_for!(replies::route::GetRouteReply),
-    );
-    hm.insert(
-        replies::route::GetServiceCommandReply::get_type_name(),
-        schema_for!(replies::route::GetServiceCommandReply),
-    );
-    hm.insert(
-        replies::route::GetServiceEventReply::get_type_name(),
-        schema_for!(replies::route::GetServiceEventReply),
-    );
-    hm.insert(
-        replies::route::GetServiceSubscriptionReply::get_type_name(),
-        schema_for!(replies::route::GetServiceSubscriptionReply),
-    );
-    hm.insert(
-        replies::route::GetServiceReply::get_type_name(),
-        schema_for!(replies::route::GetServiceReply),
-    );
-    //events
-    hm.insert(
-        events::route::OnServiceUnavailable::get_type_name(),
-        schema_for!(events::route::OnServiceUnavailable),
-    );
-    hm.insert(
-        events::route::OnRouteUpdate::get_type_name(),
-        schema_for!(events::route::OnRouteUpdate),
-    );
-    hm.insert(
-        events::executor::OnAsyncCommandStateChange::get_type_name(),
-        schema_for!(events::executor::OnAsyncCommandStateChange),
-    );
-
+    for item in inventory::iter::<ObjectSchema> {
+        hm.insert(item.type_name, (item.schema)());
+    }
     hm
 }
+
+// Check request body against schema of object type, returns all failing values
+// as json pointers with keyword of broken constraint.
+pub fn validate(
+    root: &schemars::schema::RootSchema,
+    value: &Value,
+) -> Vec<replies::common::ValidationError> {
+    let mut errors = Vec::<replies::common::ValidationError>::new();
+    validate_object(root, &root.schema, value, "", &mut errors);
+    errors
+}
+
+// events are sent as list, every item is checked against schema of event
+pub fn validate_items(
+    root: &schemars::schema::RootSchema,
+    value: &Value,
+) -> Vec<replies::common::ValidationError> {
+    let mut errors = Vec::<replies::common::ValidationError>::new();
+    match value {
+        Value::Array(a) => {
+            for (i, item) in a.iter().enumerate() {
+                validate_object(root, &root.schema, item, &format!("/{}", i), &mut errors);
+            }
+        }
+        _ => add_error(&mut errors, "", "type: expected array".to_string()),
+    }
+    errors
+}
+
+fn validate_schema(
+    root: &schemars::schema::RootSchema,
+    schema: &Schema,
+    value: &Value,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    match schema {
+        Schema::Bool(true) => {}
+        Schema::Bool(false) => {
+            add_error(errors, pointer, "false: value is not allowed".to_string())
+        }
+        Schema::Object(o) => validate_object(root, o, value, pointer, errors),
+    }
+}
+
+fn validate_object(
+    root: &schemars::schema::RootSchema,
+    schema: &SchemaObject,
+    value: &Value,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    if let Some(r) = &schema.reference {
+        match r.rsplit('/').next().and_then(|n| root.definitions.get(n)) {
+            Some(s) => validate_schema(root, s, value, pointer, errors),
+            None => add_error(errors, pointer, format!("$ref: unknown {}", r)),
+        }
+        return;
+    }
+    if let Some(t) = &schema.instance_type {
+        let types = match t {
+            SingleOrVec::Single(t) => vec![**t],
+            SingleOrVec::Vec(v) => v.clone(),
+        };
+        if !types.iter().any(|t| is_instance_type(t, value)) {
+            let names: Vec<String> = types.iter().map(|t| get_type_name(t)).collect();
+            add_error(
+                errors,
+                pointer,
+                format!("type: expected {}", names.join(" or ")),
+            );
+            return;
+        }
+    }
+    if let Some(e) = &schema.enum_values {
+        if !e.contains(value) {
+            let names: Vec<String> = e.iter().map(|v| v.to_string()).collect();
+            add_error(
+                errors,
+                pointer,
+                format!("enum: expected one of {}", names.join(", ")),
+            );
+        }
+    }
+    if let Some(c) = &schema.const_value {
+        if c != value {
+            add_error(errors, pointer, format!("const: expected {}", c));
+        }
+    }
+    if let Some(s) = &schema.subschemas {
+        validate_subschemas(root, s, value, pointer, errors);
+    }
+    match value {
+        Value::Number(n) => {
+            if let Some(v) = &schema.number {
+                validate_number(v, n.as_f64().unwrap_or_default(), pointer, errors);
+            }
+            if let Some(f) = &schema.format {
+                validate_integer_format(f, n, pointer, errors);
+            }
+        }
+        Value::String(s) => {
+            if let Some(v) = &schema.string {
+                validate_string(v, s, pointer, errors);
+            }
+            if let Some(f) = &schema.format {
+                validate_string_format(f, s, pointer, errors);
+            }
+        }
+        Value::Array(a) => {
+            if let Some(v) = &schema.array {
+                validate_array(root, v, a, pointer, errors);
+            }
+        }
+        Value::Object(o) => {
+            if let Some(v) = &schema.object {
+                validate_properties(root, v, o, pointer, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_subschemas(
+    root: &schemars::schema::RootSchema,
+    schema: &SubschemaValidation,
+    value: &Value,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    if let Some(all_of) = &schema.all_of {
+        for s in all_of {
+            validate_schema(root, s, value, pointer, errors);
+        }
+    }
+    if let Some(any_of) = &schema.any_of {
+        if !any_of.iter().any(|s| is_valid(root, s, value)) {
+            add_error(
+                errors,
+                pointer,
+                "anyOf: value matches none of schemas".to_string(),
+            );
+        }
+    }
+    if let Some(one_of) = &schema.one_of {
+        let count = one_of.iter().filter(|s| is_valid(root, s, value)).count();
+        if count != 1 {
+            add_error(
+                errors,
+                pointer,
+                format!("oneOf: value matches {} schemas, expected 1", count),
+            );
+        }
+    }
+    if let Some(not) = &schema.not {
+        if is_valid(root, not, value) {
+            add_error(errors, pointer, "not: value matches schema".to_string());
+        }
+    }
+}
+
+fn validate_number(
+    schema: &NumberValidation,
+    n: f64,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    if let Some(m) = schema.minimum {
+        if n < m {
+            add_error(errors, pointer, format!("minimum: {}", m));
+        }
+    }
+    if let Some(m) = schema.maximum {
+        if n > m {
+            add_error(errors, pointer, format!("maximum: {}", m));
+        }
+    }
+    if let Some(m) = schema.exclusive_minimum {
+        if n <= m {
+            add_error(errors, pointer, format!("exclusiveMinimum: {}", m));
+        }
+    }
+    if let Some(m) = schema.exclusive_maximum {
+        if n >= m {
+            add_error(errors, pointer, format!("exclusiveMaximum: {}", m));
+        }
+    }
+    if let Some(m) = schema.multiple_of {
+        // quotient of floats is compared with tolerance, 0.3 is multiple of 0.1
+        let q = n / m;
+        if m <= 0.0 || (q - q.round()).abs() > 1e-9 {
+            add_error(errors, pointer, format!("multipleOf: {}", m));
+        }
+    }
+}
+
+fn validate_string(
+    schema: &StringValidation,
+    s: &str,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    let len = s.chars().count() as u32;
+    if schema.min_length.map_or(false, |m| len < m) {
+        add_error(
+            errors,
+            pointer,
+            format!("minLength: {}", schema.min_length.unwrap()),
+        );
+    }
+    if schema.max_length.map_or(false, |m| len > m) {
+        add_error(
+            errors,
+            pointer,
+            format!("maxLength: {}", schema.max_length.unwrap()),
+        );
+    }
+    if let Some(p) = &schema.pattern {
+        match Regex::new(p) {
+            Ok(r) => {
+                if !r.is_match(s) {
+                    add_error(errors, pointer, format!("pattern: {}", p));
+                }
+            }
+            Err(_) => add_error(errors, pointer, format!("pattern: invalid {}", p)),
+        }
+    }
+}
+
+// formats of string values with chrono types, unknown formats are not checked
+fn validate_string_format(
+    format: &str,
+    s: &str,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    let is_valid = match format {
+        "date-time" => chrono::DateTime::parse_from_rfc3339(s).is_ok(),
+        "date" => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+        _ => return,
+    };
+    if !is_valid {
+        add_error(errors, pointer, format!("format: expected {}", format));
+    }
+}
+
+// schemars marks rust integer types by format, value must fit into type
+fn validate_integer_format(
+    format: &str,
+    n: &serde_json::Number,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    let (min, max): (i128, i128) = match format {
+        "int8" => (i8::MIN.into(), i8::MAX.into()),
+        "int16" => (i16::MIN.into(), i16::MAX.into()),
+        "int32" => (i32::MIN.into(), i32::MAX.into()),
+        "int64" => (i64::MIN.into(), i64::MAX.into()),
+        "uint8" => (0, u8::MAX.into()),
+        "uint16" => (0, u16::MAX.into()),
+        "uint32" => (0, u32::MAX.into()),
+        "uint64" => (0, u64::MAX.into()),
+        _ => return,
+    };
+    let v = match (n.as_i64(), n.as_u64()) {
+        (Some(v), _) => i128::from(v),
+        (None, Some(v)) => i128::from(v),
+        _ => return,
+    };
+    if v < min || v > max {
+        add_error(
+            errors,
+            pointer,
+            format!("format: value is out of {} range", format),
+        );
+    }
+}
+
+fn validate_array(
+    root: &schemars::schema::RootSchema,
+    schema: &ArrayValidation,
+    items: &Vec<Value>,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    let len = items.len() as u32;
+    if schema.min_items.map_or(false, |m| len < m) {
+        add_error(
+            errors,
+            pointer,
+            format!("minItems: {}", schema.min_items.unwrap()),
+        );
+    }
+    if schema.max_items.map_or(false, |m| len > m) {
+        add_error(
+            errors,
+            pointer,
+            format!("maxItems: {}", schema.max_items.unwrap()),
+        );
+    }
+    if schema.unique_items == Some(true) {
+        for (i, item) in items.iter().enumerate() {
+            if let Some(j) = items[..i].iter().position(|v| v == item) {
+                add_error(errors, pointer, format!("uniqueItems: {} equals {}", i, j));
+            }
+        }
+    }
+    match &schema.items {
+        Some(SingleOrVec::Single(s)) => {
+            for (i, item) in items.iter().enumerate() {
+                validate_schema(root, s, item, &format!("{}/{}", pointer, i), errors);
+            }
+        }
+        Some(SingleOrVec::Vec(v)) => {
+            for (i, item) in items.iter().enumerate() {
+                let p = format!("{}/{}", pointer, i);
+                match v.get(i) {
+                    Some(s) => validate_schema(root, s, item, &p, errors),
+                    None => {
+                        if let Some(s) = &schema.additional_items {
+                            validate_schema(root, s, item, &p, errors);
+                        }
+                    }
+                }
+            }
+        }
+        None => {}
+    }
+}
+
+fn validate_properties(
+    root: &schemars::schema::RootSchema,
+    schema: &ObjectValidation,
+    items: &serde_json::Map<String, Value>,
+    pointer: &str,
+    errors: &mut Vec<replies::common::ValidationError>,
+) {
+    for name in &schema.required {
+        if !items.contains_key(name) {
+            add_error(errors, pointer, format!("required: {}", name));
+        }
+    }
+    let len = items.len() as u32;
+    if schema.min_properties.map_or(false, |m| len < m) {
+        add_error(
+            errors,
+            pointer,
+            format!("minProperties: {}", schema.min_properties.unwrap()),
+        );
+    }
+    if schema.max_properties.map_or(false, |m| len > m) {
+        add_error(
+            errors,
+            pointer,
+            format!("maxProperties: {}", schema.max_properties.unwrap()),
+        );
+    }
+    for (name, item) in items {
+        let p = format!("{}/{}", pointer, name.replace('~', "~0").replace('/', "~1"));
+        match schema.properties.get(name) {
+            Some(s) => validate_schema(root, s, item, &p, errors),
+            None => {
+                if let Some(s) = &schema.additional_properties {
+                    validate_schema(root, s, item, &p, errors);
+                }
+            }
+        }
+    }
+}
+
+fn is_valid(root: &schemars::schema::RootSchema, schema: &Schema, value: &Value) -> bool {
+    let mut errors = Vec::<replies::common::ValidationError>::new();
+    validate_schema(root, schema, value, "", &mut errors);
+    errors.is_empty()
+}
+
+fn is_instance_type(t: &InstanceType, value: &Value) -> bool {
+    match t {
+        InstanceType::Null => value.is_null(),
+        InstanceType::Boolean => value.is_boolean(),
+        InstanceType::Object => value.is_object(),
+        InstanceType::Array => value.is_array(),
+        InstanceType::Number => value.is_number(),
+        InstanceType::String => value.is_string(),
+        InstanceType::Integer => value.is_i64() || value.is_u64(),
+    }
+}
+
+fn get_type_name(t: &InstanceType) -> String {
+    serde_json::to_value(t)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
+
+fn add_error(
+    errors: &mut Vec<replies::common::ValidationError>,
+    pointer: &str,
+    constraint: String,
+) {
+    errors.push(replies::common::ValidationError {
+        pointer: pointer.to_string(),
+        constraint: constraint,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use schemars::JsonSchema;
+    use serde_json::json;
+
+    fn get_schema(value: Value) -> schemars::schema::RootSchema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn get_constraints(schema: Value, value: Value) -> Vec<String> {
+        validate(&get_schema(schema), &value)
+            .into_iter()
+            .map(|e| format!("{} {}", e.pointer, e.constraint))
+            .collect()
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Reserve {
+        at: DateTime<Utc>,
+    }
+
+    #[test]
+    fn format_date_time_of_chrono_field() {
+        let root = schemars::schema_for!(Reserve);
+        assert!(validate(&root, &json!({"at": "2021-03-01T10:00:00Z"})).is_empty());
+        let errors = validate(&root, &json!({"at": "2021-03-01 10:00"}));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pointer, "/at");
+        assert_eq!(errors[0].constraint, "format: expected date-time");
+    }
+
+    #[test]
+    fn format_date() {
+        let schema = json!({"type": "string", "format": "date"});
+        assert!(get_constraints(schema.clone(), json!("2021-02-28")).is_empty());
+        assert_eq!(
+            get_constraints(schema, json!("2021-02-30")),
+            vec![" format: expected date"]
+        );
+    }
+
+    #[test]
+    fn pattern() {
+        let schema = json!({"type": "string", "pattern": "^[a-z]+$"});
+        assert!(get_constraints(schema.clone(), json!("audi")).is_empty());
+        assert_eq!(
+            get_constraints(schema, json!("Audi")),
+            vec![" pattern: ^[a-z]+$"]
+        );
+        assert_eq!(
+            get_constraints(json!({"type": "string", "pattern": "("}), json!("a")),
+            vec![" pattern: invalid ("]
+        );
+    }
+
+    #[test]
+    fn unique_items() {
+        let schema = json!({"type": "array", "uniqueItems": true});
+        assert!(get_constraints(schema.clone(), json!([1, 2, {"a": 1}, {"a": 2}])).is_empty());
+        assert_eq!(
+            get_constraints(schema, json!([1, {"a": 1}, 1, {"a": 1}])),
+            vec![" uniqueItems: 2 equals 0", " uniqueItems: 3 equals 1"]
+        );
+        assert!(get_constraints(json!({"type": "array"}), json!([1, 1])).is_empty());
+    }
+
+    #[test]
+    fn multiple_of() {
+        let schema = json!({"type": "number", "multipleOf": 0.1});
+        assert!(get_constraints(schema.clone(), json!(0.3)).is_empty());
+        assert!(get_constraints(schema.clone(), json!(-2)).is_empty());
+        assert_eq!(
+            get_constraints(schema, json!(0.35)),
+            vec![" multipleOf: 0.1"]
+        );
+        assert_eq!(
+            get_constraints(json!({"type": "integer", "multipleOf": 5}), json!(12)),
+            vec![" multipleOf: 5"]
+        );
+    }
+}
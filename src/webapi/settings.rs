@@ -1,4 +1,5 @@
 use super::entities::route;
+use super::{errors, providers};
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -7,8 +8,14 @@ pub struct AppSettings {
     pub max_async_command_queue_length: u16,
     pub max_async_command_reply_wait_in_hours: u16,
     pub max_sync_command_reply_wait_in_seconds: u16,
-    pub error: Option<HashMap<String, String>>,
+    pub max_event_delivery_concurrency: Option<u16>,
+    pub max_event_delivery_wait_in_seconds: Option<u16>,
+    pub event_proto_preference: Option<Vec<providers::Proto>>, //fall back to next on failure
+    pub default_locale: Option<String>,
+    pub error: Option<HashMap<String, String>>, //names in default locale
+    pub error_locale: Option<HashMap<String, HashMap<String, String>>>, //locale/error code/name
     pub access: Access,
+    pub storage: Option<Storage>, //default is sql database of enabled feature
     pub database: HashMap<String, String>,
     pub mq_broker: HashMap<String, String>,
     pub router: Option<HashMap<String, String>>,
@@ -16,9 +23,40 @@ pub struct AppSettings {
     pub service: HashMap<String, route::Route>,
 }
 
+impl AppSettings {
+    // error names of settings, database names are added by data connector
+    pub fn get_error_catalogue(&self) -> errors::ErrorCatalogue {
+        let default_locale = self
+            .default_locale
+            .as_deref()
+            .unwrap_or(errors::DEFAULT_LOCALE);
+        let mut catalogue = errors::ErrorCatalogue::new(default_locale);
+        if let Some(error) = &self.error {
+            for (error_code, error_name) in error {
+                catalogue.insert(default_locale, error_code, error_name);
+            }
+        }
+        if let Some(error_locale) = &self.error_locale {
+            for (locale, error) in error_locale {
+                for (error_code, error_name) in error {
+                    catalogue.insert(locale, error_code, error_name);
+                }
+            }
+        }
+        catalogue
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Copy, Clone)]
+pub enum Storage {
+    Sql,
+    Memory, //no database, data is lost on exit
+}
+
 #[derive(Deserialize)]
 pub struct Access {
     pub authentication: Authentication,
+    pub reload_interval_in_seconds: Option<u16>, //server users are reloaded on change only if not set or 0
 }
 
 #[derive(Deserialize)]
@@ -1,4 +1,5 @@
-use super::super::super::{connectors, entities::car, errors};
+use super::super::super::{commands, connectors, entities::car, errors, filters, traits};
+use async_trait::async_trait;
 
 pub struct CarCollection {
     items: Vec<car::Car>,
@@ -12,19 +13,36 @@ impl CarCollection {
         }];
         CarCollection { items: items }
     }
-    pub async fn get(&self, _ids: Option<Vec<i32>>) -> connectors::Result<Vec<car::Car>> {
-        Ok(self.items.clone())
+}
+
+#[async_trait]
+impl traits::CarStorage for CarCollection {
+    async fn get(
+        &self,
+        _ids: Option<Vec<i32>>,
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<car::Car>, filters::PageInfo)> {
+        let items: Vec<car::Car> = self.items.clone();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
     }
-    pub async fn add(
+    async fn add(
         &self,
         _items: Vec<car::Car>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
         Ok((errors::ErrorCode::ReplyOk, None))
     }
-    pub async fn change(&self, _items: Vec<car::Car>) -> connectors::Result<errors::ErrorCode> {
+    async fn change(&self, _items: Vec<car::Car>) -> connectors::Result<errors::ErrorCode> {
         Ok(errors::ErrorCode::ReplyOk)
     }
-    pub async fn remove(&self, _ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+    async fn remove(&self, _ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
         Ok(errors::ErrorCode::ReplyOk)
     }
 }
@@ -1,4 +1,5 @@
-use super::super::super::{connectors, entities::executor, errors};
+use super::super::super::{commands, connectors, entities::executor, errors, filters, traits};
+use async_trait::async_trait;
 
 pub struct SendedAsyncCommandCollection {
     _items: Vec<executor::SendedAsyncCommand>,
@@ -9,12 +10,47 @@ impl SendedAsyncCommandCollection {
         let items = vec![];
         SendedAsyncCommandCollection { _items: items }
     }
+}
 
-    pub async fn get(
+#[async_trait]
+impl traits::SendedAsyncCommandStorage for SendedAsyncCommandCollection {
+    async fn get(
         &self,
         _ids: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<executor::SendedAsyncCommand>> {
-        Ok(self._items.clone())
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::SendedAsyncCommand>, filters::PageInfo)> {
+        let items: Vec<executor::SendedAsyncCommand> = self._items.clone();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
+    }
+
+    async fn add(
+        &self,
+        _items: Vec<executor::SendedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        Ok((errors::ErrorCode::ReplyOk, None))
+    }
+
+    async fn complete(
+        &self,
+        _id: String,
+        _reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn expire(&self, _wait_in_hours: u16) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, _ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
     }
 }
 
@@ -27,31 +63,75 @@ impl ReceivedAsyncCommandCollection {
         let items = vec![];
         ReceivedAsyncCommandCollection { items: items }
     }
+}
 
-    pub async fn get(
+#[async_trait]
+impl traits::ReceivedAsyncCommandStorage for ReceivedAsyncCommandCollection {
+    async fn get(
         &self,
         _ids: Option<Vec<String>>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::ReceivedAsyncCommand>, filters::PageInfo)> {
+        let items: Vec<executor::ReceivedAsyncCommand> = self.items.clone();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
+    }
+
+    async fn get_by_state(
+        &self,
+        _state: String,
     ) -> connectors::Result<Vec<executor::ReceivedAsyncCommand>> {
         Ok(self.items.clone())
     }
 
-    pub async fn change_state(
+    async fn add(
+        &self,
+        _items: Vec<executor::ReceivedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        Ok((errors::ErrorCode::ReplyOk, None))
+    }
+
+    async fn add_within_limit(
+        &self,
+        _items: Vec<executor::ReceivedAsyncCommand>,
+        _max_pending: i64,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
+        Ok((errors::ErrorCode::ReplyOk, None))
+    }
+
+    async fn change_state(
         &self,
         state: String,
         ids: Vec<String>,
-    ) -> connectors::Result<(
-        errors::ErrorCode,
-        Option<Vec<(String, executor::AsyncCommandState)>>,
-    )> {
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<executor::AsyncCommandState>>)> {
         if state == executor::CommandSystemState::Initial.to_string()
             || state == executor::CommandSystemState::Completed.to_string()
+            || state == executor::CommandSystemState::Expired.to_string()
         {
-            return Err(errors::UnknownAsyncCommandStateError.into());
+            return Err(errors::ErrorKind::UnknownAsyncCommandState.into());
         }
         Ok((errors::ErrorCode::ReplyOk, None))
     }
 
-    pub async fn complete(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+    async fn complete(
+        &self,
+        _id: String,
+        _reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn expire(&self, _wait_in_hours: u16) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, _ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
         Ok(errors::ErrorCode::ReplyOk)
     }
 }
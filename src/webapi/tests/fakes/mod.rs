@@ -1,4 +1,6 @@
 pub mod car;
 pub mod executor;
+pub mod mq;
+pub mod publisher;
 pub mod route;
 pub mod usr;
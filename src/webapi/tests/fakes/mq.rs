@@ -0,0 +1,70 @@
+use super::super::super::{connectors, errors, providers::MqMessage};
+use std::collections::HashMap;
+use tokio::sync::{mpsc, Mutex};
+
+type Queue = (
+    mpsc::UnboundedSender<MqMessage>,
+    Option<mpsc::UnboundedReceiver<MqMessage>>,
+);
+
+// in-process stand-in for amqp broker session, one queue per address
+pub struct MqSession {
+    queues: Mutex<HashMap<String, Queue>>,
+}
+
+impl MqSession {
+    pub fn new() -> MqSession {
+        MqSession {
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn send(&self, to: &str, message: MqMessage) -> connectors::Result<()> {
+        let mut queues = self.queues.lock().await;
+        if !queues.contains_key(to) {
+            let (sender, receiver) = mpsc::unbounded_channel::<MqMessage>();
+            queues.insert(to.to_string(), (sender, Some(receiver)));
+        }
+        match queues.get(to).unwrap().0.send(message) {
+            Ok(_) => Ok({}),
+            Err(_) => Err(errors::ErrorKind::Channel.into()),
+        }
+    }
+
+    pub async fn receiver(&self, address: &str) -> connectors::Result<MqReceiver> {
+        let mut queues = self.queues.lock().await;
+        if !queues.contains_key(address) {
+            let (sender, receiver) = mpsc::unbounded_channel::<MqMessage>();
+            queues.insert(address.to_string(), (sender, Some(receiver)));
+        }
+        match queues.get_mut(address).unwrap().1.take() {
+            Some(r) => Ok(MqReceiver {
+                receiver: Mutex::new(r),
+            }),
+            None => Err(errors::ErrorKind::Channel.into()),
+        }
+    }
+
+    // listener of address is started
+    pub async fn is_taken(&self, address: &str) -> bool {
+        matches!(self.queues.lock().await.get(address), Some((_, None)))
+    }
+
+    // broker drops queue of address, its receiver gets error and new receiver gets new queue
+    pub async fn close(&self, address: &str) {
+        self.queues.lock().await.remove(address);
+    }
+}
+
+pub struct MqReceiver {
+    receiver: Mutex<mpsc::UnboundedReceiver<MqMessage>>,
+}
+
+impl MqReceiver {
+    pub async fn receive(&self) -> connectors::Result<MqMessage> {
+        match self.receiver.lock().await.recv().await {
+            Some(m) => Ok(m),
+            None => Err(errors::ErrorKind::Channel.into()),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::super::super::{commands, connectors, entities::publisher, errors, filters, traits};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+pub struct EventOutboxCollection {
+    items: Vec<publisher::OutboxEvent>,
+}
+
+impl EventOutboxCollection {
+    pub fn new() -> EventOutboxCollection {
+        let items = vec![];
+        EventOutboxCollection { items: items }
+    }
+}
+
+#[async_trait]
+impl traits::EventOutboxStorage for EventOutboxCollection {
+    async fn get(&self, _limit: i64) -> connectors::Result<Vec<publisher::OutboxEvent>> {
+        Ok(self.items.clone())
+    }
+
+    async fn add(
+        &self,
+        _items: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, _ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
+
+pub struct UndeliveredEventCollection {
+    items: Vec<publisher::UndeliveredEvent>,
+    fail: bool,
+}
+
+impl UndeliveredEventCollection {
+    pub fn new() -> UndeliveredEventCollection {
+        let items = vec![];
+        UndeliveredEventCollection {
+            items: items,
+            fail: false,
+        }
+    }
+
+    // add fails as on database error
+    pub fn failing() -> UndeliveredEventCollection {
+        UndeliveredEventCollection {
+            items: vec![],
+            fail: true,
+        }
+    }
+}
+
+#[async_trait]
+impl traits::UndeliveredEventStorage for UndeliveredEventCollection {
+    async fn get_due(&self, _limit: i64) -> connectors::Result<Vec<publisher::UndeliveredEvent>> {
+        Ok(self.items.clone())
+    }
+
+    async fn add(
+        &self,
+        _items: Vec<publisher::UndeliveredEvent>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        if self.fail {
+            return Ok((errors::ErrorCode::DatabaseError, None));
+        }
+        Ok((errors::ErrorCode::ReplyOk, None))
+    }
+
+    async fn postpone(
+        &self,
+        _id: i32,
+        _last_error: String,
+        _next_attempt_at: DateTime<Utc>,
+    ) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn move_to_dead_letter(
+        &self,
+        _id: i32,
+        _last_error: String,
+    ) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn remove(&self, _ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
+
+pub struct DeadLetterEventCollection {
+    items: Vec<publisher::UndeliveredEvent>,
+}
+
+impl DeadLetterEventCollection {
+    pub fn new() -> DeadLetterEventCollection {
+        let items = vec![];
+        DeadLetterEventCollection { items: items }
+    }
+}
+
+#[async_trait]
+impl traits::DeadLetterEventStorage for DeadLetterEventCollection {
+    async fn get(
+        &self,
+        _ids: Option<Vec<i32>>,
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<publisher::UndeliveredEvent>, filters::PageInfo)> {
+        let items: Vec<publisher::UndeliveredEvent> = self.items.clone();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
+    }
+
+    async fn replay(&self, _ids: Vec<i32>) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+}
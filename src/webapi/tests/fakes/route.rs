@@ -1,4 +1,9 @@
-use super::super::super::{connectors, entities::route, errors};
+use super::super::super::{
+    commands, connectors,
+    entities::{publisher, route},
+    errors, filters, traits,
+};
+use async_trait::async_trait;
 
 pub struct RouteCollection {
     items: Vec<route::Route>,
@@ -9,57 +14,157 @@ impl RouteCollection {
         let items = vec![];
         RouteCollection { items: items }
     }
+}
 
-    pub async fn get(
+#[async_trait]
+impl traits::RouteStorage for RouteCollection {
+    async fn get(
         &self,
         _services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::Route>> {
-        Ok(self.items.clone())
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Route>, filters::PageInfo)> {
+        let items: Vec<route::Route> = self.items.clone();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
     }
 
-    pub async fn get_command(
+    async fn get_command(
         &self,
         _services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::ServiceCommand>> {
-        Ok(vec![])
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceCommand>, filters::PageInfo)> {
+        let items: Vec<route::ServiceCommand> = self
+            .items
+            .iter()
+            .flat_map(|r| {
+                r.command.iter().map(move |c| route::ServiceCommand {
+                    service_name: r.service_name.clone(),
+                    priority: Some(r.priority),
+                    ..c.clone()
+                })
+            })
+            .collect();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
     }
 
-    pub async fn get_event(
+    async fn get_event(
         &self,
         _services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::ServiceEvent>> {
-        Ok(vec![])
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceEvent>, filters::PageInfo)> {
+        let items: Vec<route::ServiceEvent> = self
+            .items
+            .iter()
+            .flat_map(|r| {
+                r.event.iter().map(move |e| route::ServiceEvent {
+                    service_name: r.service_name.clone(),
+                    ..e.clone()
+                })
+            })
+            .collect();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
     }
 
-    pub async fn get_subscription(
+    async fn get_subscription(
         &self,
         _services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::ServiceSubscription>> {
-        Ok(vec![])
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceSubscription>, filters::PageInfo)> {
+        let items: Vec<route::ServiceSubscription> = self
+            .items
+            .iter()
+            .flat_map(|r| {
+                r.subscription
+                    .iter()
+                    .map(move |s| route::ServiceSubscription {
+                        service_name: r.service_name.clone(),
+                        ..s.clone()
+                    })
+            })
+            .collect();
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
     }
 
-    pub async fn get_service(
+    async fn get_service(
         &self,
         _services: Option<Vec<String>>,
-    ) -> connectors::Result<Vec<route::Service>> {
-        Ok(vec![])
+        _filter: Option<String>,
+        _page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Service>, filters::PageInfo)> {
+        let items: Vec<route::Service> = vec![];
+        let total = items.len() as i64;
+        Ok((
+            items,
+            filters::PageInfo {
+                total: total,
+                next_offset: None,
+            },
+        ))
     }
 
-    pub async fn get_service_path(
+    async fn get_service_path(
         &self,
         _services: Option<Vec<String>>,
     ) -> connectors::Result<Vec<route::ServicePath>> {
-        Ok(vec![])
+        Ok(self
+            .items
+            .iter()
+            .flat_map(|r| {
+                r.path.iter().flat_map(move |p| {
+                    p.iter().map(move |(proto, path)| route::ServicePath {
+                        service_name: r.service_name.clone(),
+                        proto: Some(proto.clone()),
+                        ..path.clone()
+                    })
+                })
+            })
+            .collect())
     }
 
-    pub async fn add(
+    async fn add(
         &self,
         _items: Vec<route::Route>,
+        _events: Vec<publisher::OutboxEvent>,
     ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)> {
         Ok((errors::ErrorCode::ReplyOk, None))
     }
 
-    pub async fn remove(&self, _ids: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+    async fn remove(
+        &self,
+        _ids: Vec<String>,
+        _events: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode> {
         Ok(errors::ErrorCode::ReplyOk)
     }
 }
@@ -1,16 +1,58 @@
-use super::super::super::{connectors, entities::usr};
+use super::super::super::{connectors, entities::usr, errors, traits};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 pub struct UsrCollection {
     items: Vec<usr::Usr>,
+    gets: Arc<AtomicUsize>, // count of get calls, access checker loads users by get
 }
 
 impl UsrCollection {
     pub fn new() -> UsrCollection {
+        UsrCollection::with_counter(Arc::new(AtomicUsize::new(0)))
+    }
+
+    pub fn with_counter(gets: Arc<AtomicUsize>) -> UsrCollection {
         let items = vec![];
-        UsrCollection { items: items }
+        UsrCollection {
+            items: items,
+            gets: gets,
+        }
     }
+}
 
-    pub async fn get(&self, _ids: Option<Vec<i32>>) -> connectors::Result<Vec<usr::Usr>> {
+#[async_trait]
+impl traits::UsrStorage for UsrCollection {
+    async fn get(&self, _ids: Option<Vec<i32>>) -> connectors::Result<Vec<usr::Usr>> {
+        self.gets.fetch_add(1, Ordering::SeqCst);
         Ok(self.items.clone())
     }
+
+    async fn get_by_name(&self, usr_name: &str) -> connectors::Result<Option<usr::Usr>> {
+        Ok(self
+            .items
+            .iter()
+            .find(|item| item.usr_name == usr_name)
+            .cloned())
+    }
+
+    async fn add(
+        &self,
+        _items: Vec<usr::Usr>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)> {
+        Ok((errors::ErrorCode::ReplyOk, Some(vec![])))
+    }
+
+    async fn change_password(
+        &self,
+        _usr_name: &str,
+        _usr_password: &str,
+    ) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
+
+    async fn disable(&self, _usr_names: Vec<String>) -> connectors::Result<errors::ErrorCode> {
+        Ok(errors::ErrorCode::ReplyOk)
+    }
 }
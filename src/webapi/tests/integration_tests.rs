@@ -1,103 +1,104 @@
-/*
 use super::super::{
-    access, connectors, executors, publishers, router, routes::*, settings, workers,
+    access, errors, executors, handlers, providers, publishers, registry, replies, router,
+    routes::*, settings, workers,
 };
+use super::get_sql_data_connector;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Client, Error, Method, Request, Response, Server, StatusCode};
-use rand::prelude::Rng;
-use std::fs;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
-fn get_basic_authorization_token(user: &String, password: &String) -> String {
-    format!(
-        "Basic {}",
-        base64::encode(&format!("{}:{}", user, password))
-    )
+fn get_basic_authorization_token(user: &str, password: &str) -> String {
+    access::AccessChecker::get_basic_authorization_token(&user.to_string(), &password.to_string())
 }
 
-async fn get_settings() -> (
-    SocketAddr,
-    Arc<connectors::DataConnector>,
-    Arc<access::AccessChecker>,
-    Arc<executors::CommandExecutor>,
-    Arc<publishers::EventPublisher>,
-    Arc<router::Router>,
-) {
-    let mut rng = rand::thread_rng();
-    let app_settings: settings::AppSettings =
-        serde_json::from_str(&fs::read_to_string("appsettings.test.json").unwrap()).unwrap();
-    let data_connector = connectors::DataConnector::new(
-        app_settings.error,
-        Some(app_settings.pg_db),
-        Some(app_settings.my_sql_db),
-    )
-    .await
-    .expect("error while initialize data connector");
-    let access_checker = access::AccessChecker::_from_app_settings(&app_settings.access)
+// service on sql storages is started on free port, None if database of test is not set
+async fn start_service() -> Option<u16> {
+    let dc = Arc::new(get_sql_data_connector().await?);
+    let (sender, mut receiver) = mpsc::channel::<workers::SignalCode>(10);
+    // signals of workers are accepted, workers are not started
+    tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+    let ac = Arc::new(
+        access::AccessChecker::from_data_connector(
+            &dc,
+            &settings::Authentication {
+                server: HashMap::new(),
+                client: vec![],
+            },
+            sender.clone(),
+        )
         .await
-        .expect("error while initialize access checker");
-    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), rng.gen_range(15000, 25000));
-    let host = format!("{}:{}", &addr.ip(), &addr.port());
-    let router = router::Router::new_local(
-        &data_connector,
-        app_settings.router.local.unwrap(),
-        app_settings.service,
-        &host,
-    )
-    .await
-    .expect("error while local router initialize");
-    let access_checker_arc = Arc::new(access_checker);
-    let router_arc = Arc::new(router);
-    let (command_sender, _command_receiver) = mpsc::channel::<workers::SignalCode>(10);
-    let command_executor = executors::CommandExecutor::new(access_checker_arc.clone(), router_arc.clone(), command_sender)
+        .expect("error while initialize access checker"),
+    );
+    let hp = providers::HttpProvider::new(Duration::from_secs(1))
         .await
-        .expect("error while initialize command executor");
-    let (event_sender, _event_receiver) = mpsc::channel::<workers::SignalCode>(10);
-    let event_publisher = publishers::EventPublisher::new(access_checker_arc.clone(), router_arc.clone(), event_sender)
+        .unwrap();
+    let mp = providers::MqProvider::new(None, None, Duration::from_secs(1))
         .await
-        .expect("error while event publisher initialize");
-    (
-        addr,
-        Arc::new(data_connector),
-        access_checker_arc,
-        Arc::new(command_executor),
-        Arc::new(event_publisher),
-        router_arc,
-    )
-}
-
-async fn call_service(method: hyper::Method, port: u16, path: &str, body: Body) -> Response<Body> {
-    let req = Request::builder()
-        .method(method)
-        .uri(format!("http://{}:{}{}", Ipv4Addr::LOCALHOST, port, path))
-        .header(
-            "Authorization",
-            get_basic_authorization_token(&"test".to_string(), &"1234567890".to_string()),
+        .unwrap();
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+    let builder = Server::bind(&addr);
+    let port = builder.local_addr().port();
+    let host = format!("{}:{}", Ipv4Addr::LOCALHOST, port);
+    let rt = Arc::new(
+        router::Router::new(
+            dc.clone(),
+            ac.clone(),
+            hp.clone(),
+            mp.clone(),
+            None,
+            HashMap::new(),
+            HashMap::new(),
+            &host,
+            "",
         )
-        .body(body)
-        .expect("request builder");
-    let client = Client::new();
-    client.request(req).await.unwrap()
-}
-
-#[tokio::test(threaded_scheduler)]
-async fn test_index_ok() {
-    let (
-        addr,
-        data_connector_arc,
-        access_checker_arc,
-        command_executor_arc,
-        event_publisher_arc,
-        router_arc,
-    ) = get_settings().await;
+        .await
+        .expect("error while local router initialize"),
+    );
+    let ce = Arc::new(
+        executors::CommandExecutor::new(
+            dc.clone(),
+            ac.clone(),
+            rt.clone(),
+            hp.clone(),
+            mp.clone(),
+            sender.clone(),
+            10,
+            "webapi",
+        )
+        .await
+        .expect("error while initialize command executor"),
+    );
+    let ep = Arc::new(
+        publishers::EventPublisher::new(
+            dc.clone(),
+            ac.clone(),
+            rt.clone(),
+            hp,
+            mp,
+            sender,
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("error while event publisher initialize"),
+    );
+    let mut hr = registry::HandlerRegistry::new();
+    handlers::register_service(&mut hr, dc.clone(), ac.clone(), rt.clone(), ep);
+    let mut service_hr = registry::HandlerRegistry::new();
+    handlers::register(&mut service_hr, dc.clone());
+    hr.merge(service_hr);
+    let hr = Arc::new(hr);
     let make_svc = make_service_fn(move |_| {
-        let dc = data_connector_arc.clone();
-        let ac = access_checker_arc.clone();
-        let ce = command_executor_arc.clone();
-        let ep = event_publisher_arc.clone();
-        let rt = router_arc.clone();
+        let dc = dc.clone();
+        let ac = ac.clone();
+        let ce = ce.clone();
+        let rt = rt.clone();
+        let hr = hr.clone();
         async move {
             Ok::<_, Error>(service_fn(move |req| {
                 service::service_route(
@@ -105,188 +106,224 @@ async fn test_index_ok() {
                     dc.clone(),
                     ac.clone(),
                     ce.clone(),
-                    ep.clone(),
                     rt.clone(),
+                    hr.clone(),
                 )
             }))
         }
     });
-    let app = Server::bind(&addr).serve(make_svc);
-
+    let app = builder.serve(make_svc);
     tokio::spawn(async move {
         if let Err(err) = app.await {
             error!("server: {}", err);
         }
     });
-
-    let resp = call_service(Method::GET, addr.port(), "/", Body::empty()).await;
-    assert_eq!(resp.status(), StatusCode::OK);
+    Some(port)
 }
 
-#[tokio::test(threaded_scheduler)]
-async fn test_spec_json_ok() {
-    let (
-        addr,
-        data_connector_arc,
-        access_checker_arc,
-        command_executor_arc,
-        event_publisher_arc,
-        router_arc,
-    ) = get_settings().await;
-    let make_svc = make_service_fn(move |_| {
-        let dc = data_connector_arc.clone();
-        let ac = access_checker_arc.clone();
-        let ce = command_executor_arc.clone();
-        let ep = event_publisher_arc.clone();
-        let rt = router_arc.clone();
-        async move {
-            Ok::<_, Error>(service_fn(move |req| {
-                service::service_route(
-                    req,
-                    dc.clone(),
-                    ac.clone(),
-                    ce.clone(),
-                    ep.clone(),
-                    rt.clone(),
-                )
-            }))
-        }
-    });
-    let app = Server::bind(&addr).serve(make_svc);
-
-    tokio::spawn(async move {
-        if let Err(err) = app.await {
-            error!("server: {}", err);
-        }
-    });
-
-    let resp = call_service(Method::GET, addr.port(), "/openapi.json", Body::empty()).await;
-    assert_eq!(resp.status(), StatusCode::OK);
+async fn call_service(
+    method: Method,
+    port: u16,
+    path: &str,
+    authorization: Option<String>,
+    body: Body,
+) -> Response<Body> {
+    let mut builder = Request::builder().method(method).uri(format!(
+        "http://{}:{}{}",
+        Ipv4Addr::LOCALHOST,
+        port,
+        path
+    ));
+    if let Some(a) = authorization {
+        builder = builder.header("Authorization", a);
+    }
+    let req = builder.body(body).expect("request builder");
+    let client = Client::new();
+    client.request(req).await.unwrap()
 }
 
-#[tokio::test(threaded_scheduler)]
-async fn test_spec_yaml_ok() {
-    let (
-        addr,
-        data_connector_arc,
-        access_checker_arc,
-        command_executor_arc,
-        event_publisher_arc,
-        router_arc,
-    ) = get_settings().await;
-    let make_svc = make_service_fn(move |_| {
-        let dc = data_connector_arc.clone();
-        let ac = access_checker_arc.clone();
-        let ce = command_executor_arc.clone();
-        let ep = event_publisher_arc.clone();
-        let rt = router_arc.clone();
-        async move {
-            Ok::<_, Error>(service_fn(move |req| {
-                service::service_route(
-                    req,
-                    dc.clone(),
-                    ac.clone(),
-                    ce.clone(),
-                    ep.clone(),
-                    rt.clone(),
-                )
-            }))
-        }
-    });
-    let app = Server::bind(&addr).serve(make_svc);
+// request of dev user, every request carries correlation id
+async fn call_test(port: u16, path: &str, body: &str) -> Response<Body> {
+    call_service(
+        Method::POST,
+        port,
+        &format!(
+            "{}{}correlation_id=1",
+            path,
+            if path.contains('?') { '&' } else { '?' }
+        ),
+        Some(get_basic_authorization_token("test", "1234567890")),
+        Body::from(body.to_string()),
+    )
+    .await
+}
 
-    tokio::spawn(async move {
-        if let Err(err) = app.await {
-            error!("server: {}", err);
-        }
-    });
+async fn get_reply(resp: Response<Body>) -> replies::common::StandardReply {
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
 
-    let resp = call_service(Method::GET, addr.port(), "/openapi.yaml", Body::empty()).await;
-    assert_eq!(resp.status(), StatusCode::OK);
+#[tokio::test]
+async fn test_index_ok() {
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    for path in &["/", "/openapi.json", "/openapi.yaml"] {
+        let resp = call_service(Method::GET, port, path, None, Body::empty()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
 
-#[tokio::test(threaded_scheduler)]
+#[tokio::test]
 async fn test_route_ok() {
-    let (
-        addr,
-        data_connector_arc,
-        access_checker_arc,
-        command_executor_arc,
-        event_publisher_arc,
-        router_arc,
-    ) = get_settings().await;
-    let make_svc = make_service_fn(move |_| {
-        let dc = data_connector_arc.clone();
-        let ac = access_checker_arc.clone();
-        let ce = command_executor_arc.clone();
-        let ep = event_publisher_arc.clone();
-        let rt = router_arc.clone();
-        async move {
-            Ok::<_, Error>(service_fn(move |req| {
-                service::service_route(
-                    req,
-                    dc.clone(),
-                    ac.clone(),
-                    ce.clone(),
-                    ep.clone(),
-                    rt.clone(),
-                )
-            }))
-        }
-    });
-    let app = Server::bind(&addr).serve(make_svc);
-
-    tokio::spawn(async move {
-        if let Err(err) = app.await {
-            error!("server: {}", err);
-        }
-    });
-
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
     for route in path::ROUTE_WITH_EMPTY_BODY.iter() {
-        let resp = call_service(Method::POST, addr.port(), route, Body::empty()).await;
+        let resp = call_test(port, route, "").await;
         assert_eq!(resp.status(), StatusCode::OK);
     }
 }
 
-#[tokio::test(threaded_scheduler)]
+#[tokio::test]
 async fn test_route_err() {
-    let (
-        addr,
-        data_connector_arc,
-        access_checker_arc,
-        command_executor_arc,
-        event_publisher_arc,
-        router_arc,
-    ) = get_settings().await;
-    let make_svc = make_service_fn(move |_| {
-        let dc = data_connector_arc.clone();
-        let ac = access_checker_arc.clone();
-        let ce = command_executor_arc.clone();
-        let ep = event_publisher_arc.clone();
-        let rt = router_arc.clone();
-        async move {
-            Ok::<_, Error>(service_fn(move |req| {
-                service::service_route(
-                    req,
-                    dc.clone(),
-                    ac.clone(),
-                    ce.clone(),
-                    ep.clone(),
-                    rt.clone(),
-                )
-            }))
-        }
-    });
-    let app = Server::bind(&addr).serve(make_svc);
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    let resp = call_test(port, "/fake", "{}").await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = call_service(
+        Method::POST,
+        port,
+        path::USR_ITEMS,
+        Some(get_basic_authorization_token("test", "wrong")),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = call_service(Method::POST, port, path::USR_ITEMS, None, Body::empty()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
 
-    tokio::spawn(async move {
-        if let Err(err) = app.await {
-            error!("server: {}", err);
-        }
-    });
+#[tokio::test]
+async fn test_admin_route_err() {
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    // ordinary usr may not sign up users, reload access or replay dead letters
+    for route in &[
+        path::USR_SIGHN_UP,
+        path::ACCESS_RELOAD,
+        path::ROUTER_DEAD_LETTER_REPLAY,
+    ] {
+        let resp = call_service(
+            Method::POST,
+            port,
+            &format!("{}?correlation_id=1", route),
+            Some(get_basic_authorization_token("user1", "pass1")),
+            Body::from("{}"),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            get_reply(resp).await.error_code,
+            errors::ErrorCode::ForbiddenError
+        );
+    }
+    let resp = call_test(port, path::ACCESS_RELOAD, "{}").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
 
-    let resp = call_service(Method::POST, addr.port(), "/fake", Body::from("{}")).await;
-    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+#[tokio::test]
+async fn test_signin_ok() {
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    let resp = call_test(
+        port,
+        path::USR_SIGHN_IN,
+        r#"{"usr_name":"user1","usr_password":"pass1"}"#,
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(get_reply(resp).await.error_code, errors::ErrorCode::ReplyOk);
+    let resp = call_test(
+        port,
+        path::USR_SIGHN_IN,
+        r#"{"usr_name":"user1","usr_password":"wrong"}"#,
+    )
+    .await;
+    assert_eq!(
+        get_reply(resp).await.error_code,
+        errors::ErrorCode::UnauthorizedError
+    );
+}
+
+#[tokio::test]
+async fn test_car_ok() {
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    let resp = call_test(
+        port,
+        path::CAR_ADD,
+        r#"{"items":[{"car_name":"integration"}]}"#,
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    let reply: replies::common::AddIntIdsReply = serde_json::from_slice(&body).unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    let id = reply.ids.unwrap()[0];
+    let resp = call_test(port, path::CAR_GET, &format!(r#"{{"ids":[{}]}}"#, id)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    let reply: replies::car::GetCarReply = serde_json::from_slice(&body).unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    assert_eq!(reply.items.unwrap()[0].car_name, "integration");
+}
+
+#[tokio::test]
+async fn test_event_ok() {
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    // built-in event is handled by registry on own path
+    let events = r#"[{"services":[]}]"#;
+    let resp = call_test(port, path::EVENT_ON_ROUTE_UPDATE, events).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(get_reply(resp).await.error_code, errors::ErrorCode::ReplyOk);
+    let resp = call_test(
+        port,
+        &format!("{}?object_type=OnRouteUpdate", path::EVENT),
+        events,
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = call_test(port, path::EVENT_ON_ROUTE_UPDATE, r#"{"services":[]}"#).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 }
 
-*/
+#[tokio::test]
+async fn test_async_request_requires_service_name() {
+    let port = match start_service().await {
+        Some(port) => port,
+        None => return,
+    };
+    let request = format!(
+        "{}?object_type=GetCar&exec_mode=Async&reply_to=http://localhost/reply",
+        path::REQUEST
+    );
+    let resp = call_test(port, &request, "{}").await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = call_test(port, &format!("{}&service_name=client", request), "{}").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(get_reply(resp).await.error_code, errors::ErrorCode::AsyncOk);
+}
@@ -1,8 +1,62 @@
 #[cfg(test)]
 pub mod fakes;
 
-#[cfg(test)]
+// suites on sql storages, postgres build has no sql storages in tests
+#[cfg(all(test, not(feature = "postgres")))]
 mod unit_tests;
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "postgres")))]
 mod integration_tests;
+
+use super::{connectors, errors};
+
+// storages which keep nothing, for code under test which does not read data back
+pub fn get_fake_data_connector() -> connectors::DataConnector {
+    connectors::DataConnector::from_storages(
+        errors::ErrorCatalogue::new(errors::DEFAULT_LOCALE),
+        Box::new(fakes::usr::UsrCollection::new()),
+        Box::new(fakes::car::CarCollection::new()),
+        Box::new(fakes::route::RouteCollection::new()),
+        Box::new(fakes::executor::SendedAsyncCommandCollection::new()),
+        Box::new(fakes::executor::ReceivedAsyncCommandCollection::new()),
+        Box::new(fakes::publisher::EventOutboxCollection::new()),
+        Box::new(fakes::publisher::UndeliveredEventCollection::new()),
+        Box::new(fakes::publisher::DeadLetterEventCollection::new()),
+    )
+}
+
+// memory storages seeded like database after first migration
+pub async fn get_memory_data_connector() -> connectors::DataConnector {
+    connectors::DataConnector::new_memory(errors::ErrorCatalogue::new(errors::DEFAULT_LOCALE))
+        .await
+        .unwrap()
+}
+
+// mysql suites run only on database of this variable, e.g. in ci
+#[cfg(feature = "mysql")]
+const ENV_TEST_DATABASE_URL: &str = "MY_TEST_DATABASE_URL";
+
+// sql storages after migrations with development users, sqlite database lives
+// in memory of test, None if database of mysql test is not set
+#[cfg(not(feature = "postgres"))]
+pub async fn get_sql_data_connector() -> Option<connectors::DataConnector> {
+    #[cfg(feature = "sqlite")]
+    let connection_string = Some("sqlite::memory:".to_string());
+    #[cfg(feature = "mysql")]
+    let connection_string = std::env::var(ENV_TEST_DATABASE_URL).ok();
+    match connection_string {
+        Some(cs) => Some(
+            connectors::DataConnector::new(
+                errors::ErrorCatalogue::new(errors::DEFAULT_LOCALE),
+                &cs,
+                true,
+            )
+            .await
+            .unwrap(),
+        ),
+        None => {
+            eprintln!("database of test is not set, sql test is skipped");
+            None
+        }
+    }
+}
@@ -1,38 +1,300 @@
-/*
-use super::super::{handlers::*, errors, connectors};
+use super::super::{
+    access, commands, connectors, entities, errors, handlers::*, settings, workers,
+};
+use super::get_sql_data_connector;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
 
-#[tokio::test(threaded_scheduler)]
+async fn get_access_checker(
+    dc: &connectors::DataConnector,
+) -> (access::AccessChecker, mpsc::Receiver<workers::SignalCode>) {
+    let (sender, receiver) = mpsc::channel::<workers::SignalCode>(5);
+    let ac = access::AccessChecker::from_data_connector(
+        dc,
+        &settings::Authentication {
+            server: HashMap::new(),
+            client: vec![],
+        },
+        sender,
+    )
+    .await
+    .unwrap();
+    (ac, receiver)
+}
+
+async fn signin_code(
+    dc: &connectors::DataConnector,
+    usr_name: &str,
+    usr_password: &str,
+) -> errors::ErrorCode {
+    usr::signin(
+        dc,
+        commands::usr::SignIn {
+            usr_name: usr_name.to_string(),
+            usr_password: usr_password.to_string(),
+        },
+    )
+    .await
+    .unwrap()
+    .error_code
+}
+
+// names are unique per run, mysql database is shared by runs
+fn get_unique_name(prefix: &str) -> String {
+    format!("{}_{}", prefix, uuid::Uuid::new_v4().to_simple())
+}
+
+#[tokio::test]
 async fn test_signin_ok() {
-    let dc = connectors::DataConnector::new(None, None, None).await.unwrap();
-    let reply = usr::signin(&dc).await.unwrap();
-    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    assert_eq!(
+        signin_code(&dc, "user1", "pass1").await,
+        errors::ErrorCode::ReplyOk
+    );
+    assert_eq!(
+        signin_code(&dc, "test", "1234567890").await,
+        errors::ErrorCode::ReplyOk
+    );
 }
 
-#[tokio::test(threaded_scheduler)]
+#[tokio::test]
 async fn test_signin_err() {
-    let dc = connectors::DataConnector::new(None, None, None).await.unwrap();
-    let reply = usr::signin(&dc).await.unwrap();
-    assert_ne!(reply.error_code, errors::ErrorCode::DatabaseError);
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    assert_eq!(
+        signin_code(&dc, "user1", "wrong").await,
+        errors::ErrorCode::UnauthorizedError
+    );
+    assert_eq!(
+        signin_code(&dc, &get_unique_name("nobody"), "pass1").await,
+        errors::ErrorCode::UnauthorizedError
+    );
 }
 
-#[tokio::test(threaded_scheduler)]
+#[tokio::test]
 async fn test_signup_ok() {
-    let dc = connectors::DataConnector::new(None, None, None).await.unwrap();
-    let reply = usr::signin(&dc).await.unwrap();
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    let (ac, mut receiver) = get_access_checker(&dc).await;
+    let usr_name = get_unique_name("usr");
+    let reply = usr::signup(
+        &dc,
+        &ac,
+        commands::usr::SignUp {
+            usr_name: usr_name.clone(),
+            usr_password: "pass2".to_string(),
+        },
+    )
+    .await
+    .unwrap();
     assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    assert_eq!(reply.ids.unwrap().len(), 1);
+    assert!(matches!(
+        receiver.try_recv(),
+        Ok(workers::SignalCode::AccessReload)
+    ));
+    assert_eq!(
+        signin_code(&dc, &usr_name, "pass2").await,
+        errors::ErrorCode::ReplyOk
+    );
 }
 
-#[tokio::test(threaded_scheduler)]
+#[tokio::test]
 async fn test_signup_err() {
-    let dc = connectors::DataConnector::new(None, None, None).await.unwrap();
-    let reply = usr::signin(&dc).await.unwrap();
-    assert_ne!(reply.error_code, errors::ErrorCode::DatabaseError);
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    let (ac, _receiver) = get_access_checker(&dc).await;
+    // usr name is unique
+    let reply = usr::signup(
+        &dc,
+        &ac,
+        commands::usr::SignUp {
+            usr_name: "user1".to_string(),
+            usr_password: "pass2".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::DatabaseError);
+    assert_eq!(
+        signin_code(&dc, "user1", "pass1").await,
+        errors::ErrorCode::ReplyOk
+    );
+}
+
+#[tokio::test]
+async fn test_car_ok() {
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    let car_name = get_unique_name("car");
+    let reply = car::add(
+        &dc,
+        commands::car::AddCar {
+            items: vec![entities::car::Car {
+                id: None,
+                car_name: car_name.clone(),
+            }],
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    let id = reply.ids.unwrap()[0];
+    let get = |filter: &str| commands::car::GetCar {
+        filter: Some(filter.to_string()),
+        ids: None,
+        page: commands::common::Page::default(),
+    };
+    let reply = car::get(&dc, get(&format!("car_name = '{}'", car_name)))
+        .await
+        .unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    assert_eq!(reply.total, Some(1));
+    assert_eq!(reply.items.unwrap()[0].id, Some(id));
+    let reply = car::change(
+        &dc,
+        commands::car::ChangeCar {
+            items: vec![entities::car::Car {
+                id: Some(id),
+                car_name: format!("{}_changed", car_name),
+            }],
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    let reply = car::get(&dc, get(&format!("car_name = '{}_changed'", car_name)))
+        .await
+        .unwrap();
+    assert_eq!(reply.total, Some(1));
+    let reply = car::remove(&dc, commands::car::RemoveCar { ids: vec![id] })
+        .await
+        .unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::ReplyOk);
+    let reply = car::get(&dc, get(&format!("id = {}", id))).await.unwrap();
+    assert_eq!(reply.total, Some(0));
+}
+
+#[tokio::test]
+async fn test_car_get_err() {
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    let reply = car::get(
+        &dc,
+        commands::car::GetCar {
+            filter: Some("car_name = ".to_string()),
+            ids: None,
+            page: commands::common::Page::default(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply.error_code, errors::ErrorCode::BadFilterError);
+    assert!(reply.items.is_none());
+}
+
+#[tokio::test]
+async fn test_error_names_from_database() {
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    assert_ne!(
+        dc.error.get_name(&errors::ErrorCode::DatabaseError, None),
+        errors::ErrorCode::DatabaseError.to_string()
+    );
+    // error table is seeded from error names of service
+    for (error_code, locale, error_name) in errors::ERROR_NAMES {
+        assert_eq!(dc.error.get_name(error_code, Some(locale)), *error_name);
+    }
+}
+
+fn get_received_async_command(id: &str) -> entities::executor::ReceivedAsyncCommand {
+    let now = chrono::Utc::now();
+    entities::executor::ReceivedAsyncCommand {
+        id: id.to_string(),
+        object_type: "GetCar".to_string(),
+        service_name: "test".to_string(),
+        request_body: "{}".to_string(),
+        state: entities::executor::CommandSystemState::Initial.to_string(),
+        change_state_event: 0,
+        reply_body: String::new(),
+        proto: "http".to_string(),
+        reply_to: "http://localhost/reply".to_string(),
+        added_at: now,
+        state_changed_at: now,
+        history: None,
+        authorized_usr_name: None,
+        locale: None,
+    }
 }
 
-#[tokio::test(threaded_scheduler)]
-async fn test_car_get_ok() {
-    let dc = connectors::DataConnector::new(None, None, None).await.unwrap();
-    let reply = car::get(&dc, None).await.unwrap();
-    assert_eq!(reply.len(), 1);
+#[tokio::test]
+async fn test_received_async_command_queue_limit() {
+    let dc = match get_sql_data_connector().await {
+        Some(dc) => dc,
+        None => return,
+    };
+    // mysql database may keep pending commands of previous runs
+    let completed = entities::executor::CommandSystemState::Completed.to_string();
+    let expired = entities::executor::CommandSystemState::Expired.to_string();
+    let (items, _) = dc
+        .received_async_command
+        .get(None, &commands::common::Page::default())
+        .await
+        .unwrap();
+    let pending = items
+        .iter()
+        .filter(|c| c.state != completed && c.state != expired)
+        .count() as i64;
+    let first = get_unique_name("cmd");
+    let (result, _) = dc
+        .received_async_command
+        .add_within_limit(vec![get_received_async_command(&first)], pending + 2)
+        .await
+        .unwrap();
+    assert_eq!(result, errors::ErrorCode::ReplyOk);
+    // batch over limit adds nothing
+    let (second, third) = (get_unique_name("cmd"), get_unique_name("cmd"));
+    let (result, ids) = dc
+        .received_async_command
+        .add_within_limit(
+            vec![
+                get_received_async_command(&second),
+                get_received_async_command(&third),
+            ],
+            pending + 2,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, errors::ErrorCode::TooManyRequestsError);
+    assert!(ids.is_none());
+    let (items, _) = dc
+        .received_async_command
+        .get(
+            Some(vec![first.clone(), second.clone(), third]),
+            &commands::common::Page::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let (result, _) = dc
+        .received_async_command
+        .add_within_limit(vec![get_received_async_command(&second)], pending + 2)
+        .await
+        .unwrap();
+    assert_eq!(result, errors::ErrorCode::ReplyOk);
 }
-*/
@@ -1,4 +1,10 @@
-// use super::connectors;
+use super::{
+    commands, connectors,
+    entities::{car, executor, publisher, route, usr},
+    errors, filters,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 // use hyper::Body;
 // use std::collections::HashMap;
 
@@ -22,3 +28,184 @@ pub trait Provider {
     ) -> connectors::Result<Body>;
 }
 */
+
+// Storage traits are implemented by sql collections and test fakes,
+// data connector holds them as trait objects so implementation can be replaced at runtime.
+
+#[async_trait]
+pub trait UsrStorage: Send + Sync {
+    async fn get(&self, ids: Option<Vec<i32>>) -> connectors::Result<Vec<usr::Usr>>;
+    async fn get_by_name(&self, usr_name: &str) -> connectors::Result<Option<usr::Usr>>;
+    async fn add(
+        &self,
+        items: Vec<usr::Usr>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)>;
+    // password is hash already
+    async fn change_password(
+        &self,
+        usr_name: &str,
+        usr_password: &str,
+    ) -> connectors::Result<errors::ErrorCode>;
+    async fn disable(&self, usr_names: Vec<String>) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait CarStorage: Send + Sync {
+    async fn get(
+        &self,
+        ids: Option<Vec<i32>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<car::Car>, filters::PageInfo)>;
+    async fn add(
+        &self,
+        items: Vec<car::Car>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)>;
+    async fn change(&self, items: Vec<car::Car>) -> connectors::Result<errors::ErrorCode>;
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait RouteStorage: Send + Sync {
+    async fn get_command(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceCommand>, filters::PageInfo)>;
+    async fn get_event(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceEvent>, filters::PageInfo)>;
+    async fn get_subscription(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::ServiceSubscription>, filters::PageInfo)>;
+    async fn get_service(
+        &self,
+        names: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Service>, filters::PageInfo)>;
+    async fn get_service_path(
+        &self,
+        services: Option<Vec<String>>,
+    ) -> connectors::Result<Vec<route::ServicePath>>;
+    async fn get(
+        &self,
+        services: Option<Vec<String>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<route::Route>, filters::PageInfo)>;
+    async fn add(
+        &self,
+        items: Vec<route::Route>,
+        events: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)>;
+    async fn remove(
+        &self,
+        services: Vec<String>,
+        events: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait SendedAsyncCommandStorage: Send + Sync {
+    async fn get(
+        &self,
+        ids: Option<Vec<String>>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::SendedAsyncCommand>, filters::PageInfo)>;
+    async fn add(
+        &self,
+        items: Vec<executor::SendedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)>;
+    async fn complete(
+        &self,
+        id: String,
+        reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode>;
+    async fn expire(&self, wait_in_hours: u16) -> connectors::Result<errors::ErrorCode>;
+    async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait ReceivedAsyncCommandStorage: Send + Sync {
+    async fn get(
+        &self,
+        ids: Option<Vec<String>>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<executor::ReceivedAsyncCommand>, filters::PageInfo)>;
+    async fn add(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)>;
+    // TooManyRequestsError and nothing is added if pending commands would exceed max_pending
+    async fn add_within_limit(
+        &self,
+        items: Vec<executor::ReceivedAsyncCommand>,
+        max_pending: i64,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<String>>)>;
+    async fn change_state(
+        &self,
+        state: String,
+        ids: Vec<String>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<executor::AsyncCommandState>>)>;
+    async fn complete(
+        &self,
+        id: String,
+        reply_body: String,
+    ) -> connectors::Result<errors::ErrorCode>;
+    async fn get_by_state(
+        &self,
+        state: String,
+    ) -> connectors::Result<Vec<executor::ReceivedAsyncCommand>>;
+    async fn expire(&self, wait_in_hours: u16) -> connectors::Result<errors::ErrorCode>;
+    async fn remove(&self, ids: Vec<String>) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait EventOutboxStorage: Send + Sync {
+    async fn get(&self, limit: i64) -> connectors::Result<Vec<publisher::OutboxEvent>>;
+    async fn add(
+        &self,
+        items: Vec<publisher::OutboxEvent>,
+    ) -> connectors::Result<errors::ErrorCode>;
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait UndeliveredEventStorage: Send + Sync {
+    async fn get_due(&self, limit: i64) -> connectors::Result<Vec<publisher::UndeliveredEvent>>;
+    async fn add(
+        &self,
+        items: Vec<publisher::UndeliveredEvent>,
+    ) -> connectors::Result<(errors::ErrorCode, Option<Vec<i32>>)>;
+    async fn postpone(
+        &self,
+        id: i32,
+        last_error: String,
+        next_attempt_at: DateTime<Utc>,
+    ) -> connectors::Result<errors::ErrorCode>;
+    async fn move_to_dead_letter(
+        &self,
+        id: i32,
+        last_error: String,
+    ) -> connectors::Result<errors::ErrorCode>;
+    async fn remove(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode>;
+}
+
+#[async_trait]
+pub trait DeadLetterEventStorage: Send + Sync {
+    async fn get(
+        &self,
+        ids: Option<Vec<i32>>,
+        filter: Option<String>,
+        page: &commands::common::Page,
+    ) -> connectors::Result<(Vec<publisher::UndeliveredEvent>, filters::PageInfo)>;
+    async fn replay(&self, ids: Vec<i32>) -> connectors::Result<errors::ErrorCode>;
+}
@@ -1,12 +1,33 @@
-use super::{connectors, errors};
+use super::{
+    access, connectors, entities, errors, executors, providers, publishers, registry, router,
+    routes,
+};
+use bytes::Buf;
+use hyper::Body;
+use std::collections::HashMap;
+use std::io::Read;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio::time::{interval, interval_at, Duration, Instant};
+
+const EXPIRE_ASYNC_COMMAND_INTERVAL_IN_SECONDS: u64 = 60;
+const REDELIVER_EVENT_INTERVAL_IN_SECONDS: u64 = 10;
+// delay of mq receiver reconnect is doubled by attempt, worker stops after last attempt
+const MQ_RECONNECT_ATTEMPTS: u32 = 5;
+#[cfg(not(test))]
+const MQ_RECONNECT_DELAY_IN_MILLISECONDS: u64 = 1000;
+#[cfg(test)]
+const MQ_RECONNECT_DELAY_IN_MILLISECONDS: u64 = 10;
 
 #[derive(Debug)]
 pub enum SignalCode {
     Exit = 0,
     _JobNo1 = 1,
+    ReceivedAsyncCommand = 2,
+    UndeliveredEvent = 3,
+    EventOutbox = 4,
+    AccessReload = 5,
 }
 
 pub async fn mq_sender_worker(
@@ -31,7 +52,7 @@ pub async fn mq_sender_worker(
                     debug!("{} cancel flag", TASK);
                     return Ok({});
                 } else {
-                    return Err(errors::ChannelError.into());
+                    return Err(errors::ErrorKind::Channel.into());
                 }
             }
         };
@@ -41,46 +62,293 @@ pub async fn mq_sender_worker(
 pub async fn mq_receiver_worker(
     cancel_flag: Arc<AtomicBool>,
     mut receiver: mpsc::Receiver<SignalCode>,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+    ce: Arc<executors::CommandExecutor>,
+    rt: Arc<router::Router>,
+    hr: Arc<registry::HandlerRegistry>,
+    mp: providers::MqProvider,
+    addresses: Vec<String>,
 ) -> connectors::Result<()> {
     const TASK: &str = "mq receiver";
     debug!("start {}", TASK);
+    let mut listeners = Vec::<tokio::task::JoinHandle<()>>::new();
+    // listener reports address which is lost after all reconnect attempts
+    let (failure_sender, mut failures) = mpsc::channel::<String>(1);
+    if let Some(session) = mp.get_session() {
+        let mut addresses = addresses;
+        if let Some(reply_to) = mp.get_reply_to() {
+            addresses.push(reply_to);
+        }
+        for address in addresses {
+            let mq_receiver = session.receiver(&address).await?;
+            debug!("{} listen {}", TASK, address);
+            listeners.push(tokio::spawn(mq_listen(
+                address,
+                mq_receiver,
+                session.clone(),
+                failure_sender.clone(),
+                dc.clone(),
+                ac.clone(),
+                ce.clone(),
+                rt.clone(),
+                hr.clone(),
+                mp.clone(),
+            )));
+        }
+    }
     loop {
-        match receiver.recv().await {
+        let signal = tokio::select! {
+            s = receiver.recv() => s,
+            Some(address) = failures.recv() => {
+                for item in &listeners {
+                    item.abort();
+                }
+                return Err(errors::Error::new(errors::ErrorKind::ProtoProvider)
+                    .with_field(&address)
+                    .into());
+            }
+        };
+        match signal {
             Some(m) => {
                 debug!("{} signal {:?}", TASK, m);
                 match m {
                     SignalCode::Exit => {
+                        for item in &listeners {
+                            item.abort();
+                        }
                         return Ok({});
                     }
                     _ => {}
                 };
             }
             None => {
+                for item in &listeners {
+                    item.abort();
+                }
                 if cancel_flag.load(Ordering::SeqCst) {
                     debug!("{} cancel flag", TASK);
                     return Ok({});
                 } else {
-                    return Err(errors::ChannelError.into());
+                    return Err(errors::ErrorKind::Channel.into());
                 }
             }
         };
     }
 }
 
+async fn mq_listen(
+    address: String,
+    receiver: providers::MqReceiver,
+    session: Arc<providers::MqSession>,
+    failure_sender: mpsc::Sender<String>,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+    ce: Arc<executors::CommandExecutor>,
+    rt: Arc<router::Router>,
+    hr: Arc<registry::HandlerRegistry>,
+    mp: providers::MqProvider,
+) {
+    let mut receiver = receiver;
+    let mut attempt: u32 = 0;
+    loop {
+        let message = match receiver.receive().await {
+            Ok(m) => Some(m),
+            Err(e) => {
+                error!("mq receiver {}: {}", address, e);
+                None
+            }
+        };
+        let message = match message {
+            Some(m) => m,
+            None => match mq_reconnect(&address, &session, &mut attempt).await {
+                Some(r) => {
+                    receiver = r;
+                    continue;
+                }
+                None => {
+                    failure_sender.send(address).await.ok();
+                    return;
+                }
+            },
+        };
+        attempt = 0;
+        let dc = dc.clone();
+        let ac = ac.clone();
+        let ce = ce.clone();
+        let rt = rt.clone();
+        let hr = hr.clone();
+        let mp = mp.clone();
+        tokio::spawn(async move {
+            if message.properties.contains_key(providers::MQ_IN_REPLY_TO) {
+                mp.dispatch_reply(message).await;
+            } else {
+                mq_dispatch(message, dc, ac, ce, rt, hr, mp).await;
+            }
+        });
+    }
+}
+
+// new receiver of address after backoff delay, None if attempts are over
+async fn mq_reconnect(
+    address: &str,
+    session: &providers::MqSession,
+    attempt: &mut u32,
+) -> Option<providers::MqReceiver> {
+    while *attempt < MQ_RECONNECT_ATTEMPTS {
+        let delay = MQ_RECONNECT_DELAY_IN_MILLISECONDS * 2u64.pow(*attempt);
+        *attempt += 1;
+        warn!(
+            "mq receiver {}: reconnect attempt {} in {} ms",
+            address, attempt, delay
+        );
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        let error = match session.receiver(address).await {
+            Ok(r) => return Some(r),
+            Err(e) => e.to_string(),
+        };
+        error!("mq receiver {} reconnect: {}", address, error);
+    }
+    error!("mq receiver {}: reconnect attempts are over", address);
+    None
+}
+
+async fn mq_dispatch(
+    message: providers::MqMessage,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+    ce: Arc<executors::CommandExecutor>,
+    rt: Arc<router::Router>,
+    hr: Arc<registry::HandlerRegistry>,
+    mp: providers::MqProvider,
+) {
+    // token is checked as authorization header of http, then it is dropped from params
+    let mut properties = message.properties.clone();
+    let authorized_usr_name = match properties.remove(providers::MQ_AUTHORIZATION) {
+        Some(h) => ac.get_authorized_usr(&h).await,
+        None => None,
+    };
+    let authorized_usr_name = match authorized_usr_name {
+        Some(n) => n,
+        None => {
+            error!("mq receiver: unauthorized");
+            let response = routes::service::resp_error(
+                &dc,
+                properties
+                    .get(routes::service::LOCALE_PARAM)
+                    .map(|l| l.as_str()),
+                errors::ErrorKind::Unauthorized.into(),
+            );
+            mq_reply(&message, response, &mp).await;
+            return;
+        }
+    };
+    properties.insert(
+        routes::service::AUTHORIZED_USR_PARAM.to_string(),
+        authorized_usr_name,
+    );
+    // reply and state of async command are sent with explicit path, not by object type
+    let path = match properties.remove(providers::MQ_PATH) {
+        Some(p) => Some(p),
+        None => match properties.get("object_type") {
+            Some(ot) => hr
+                .get_path(ot)
+                .or_else(|| routes::path::get_object_type_path(ot))
+                .map(|p| p.to_string()),
+            None => None,
+        },
+    };
+    let response = match path {
+        Some(p) => match routes::service::request_route(
+            &p,
+            properties,
+            Body::from(message.body.clone()),
+            providers::Proto::Mq,
+            &dc,
+            &ac,
+            &ce,
+            &rt,
+            &hr,
+        )
+        .await
+        {
+            Ok(r) => r,
+            Err(e) => routes::service::resp_error(
+                &dc,
+                message
+                    .properties
+                    .get(routes::service::LOCALE_PARAM)
+                    .map(|l| l.as_str()),
+                errors::Error::new(errors::ErrorKind::Internal).with_source(e),
+            ),
+        },
+        None => {
+            error!("mq receiver: unknown object type");
+            routes::service::resp_error(
+                &dc,
+                message
+                    .properties
+                    .get(routes::service::LOCALE_PARAM)
+                    .map(|l| l.as_str()),
+                errors::Error::new(errors::ErrorKind::NotFound).with_field("object_type"),
+            )
+        }
+    };
+    mq_reply(&message, response, &mp).await;
+}
+
+// reply only if sender waits for it
+async fn mq_reply(
+    message: &providers::MqMessage,
+    response: hyper::Response<Body>,
+    mp: &providers::MqProvider,
+) {
+    if message.properties.contains_key(providers::MQ_REPLY_TO) {
+        let (parts, body) = response.into_parts();
+        if let Err(e) = mp.reply(message, parts.status, body).await {
+            error!("mq receiver reply: {}", e);
+        }
+    }
+}
+
 pub async fn event_publisher_worker(
     cancel_flag: Arc<AtomicBool>,
     mut receiver: mpsc::Receiver<SignalCode>,
+    ep: Arc<publishers::EventPublisher>,
 ) -> connectors::Result<()> {
     const TASK: &str = "event publisher";
     debug!("start {}", TASK);
+    let mut redeliver_interval = interval(Duration::from_secs(REDELIVER_EVENT_INTERVAL_IN_SECONDS));
     loop {
-        match receiver.recv().await {
+        let signal = tokio::select! {
+            s = receiver.recv() => s,
+            _ = redeliver_interval.tick() => {
+                if let Err(e) = ep.drain_outbox().await {
+                    error!("{} drain outbox: {}", TASK, e);
+                }
+                if let Err(e) = ep.redeliver().await {
+                    error!("{} redeliver: {}", TASK, e);
+                }
+                continue;
+            }
+        };
+        match signal {
             Some(m) => {
                 debug!("{} signal {:?}", TASK, m);
                 match m {
                     SignalCode::Exit => {
                         return Ok({});
                     }
+                    SignalCode::EventOutbox => {
+                        if let Err(e) = ep.drain_outbox().await {
+                            error!("{} drain outbox: {}", TASK, e);
+                        }
+                    }
+                    SignalCode::UndeliveredEvent => {
+                        if let Err(e) = ep.redeliver().await {
+                            error!("{} redeliver: {}", TASK, e);
+                        }
+                    }
                     _ => {}
                 };
             }
@@ -89,7 +357,7 @@ pub async fn event_publisher_worker(
                     debug!("{} cancel flag", TASK);
                     return Ok({});
                 } else {
-                    return Err(errors::ChannelError.into());
+                    return Err(errors::ErrorKind::Channel.into());
                 }
             }
         };
@@ -99,17 +367,205 @@ pub async fn event_publisher_worker(
 pub async fn command_executor_worker(
     cancel_flag: Arc<AtomicBool>,
     mut receiver: mpsc::Receiver<SignalCode>,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+    ce: Arc<executors::CommandExecutor>,
+    rt: Arc<router::Router>,
+    hr: Arc<registry::HandlerRegistry>,
+    async_reply_wait_in_hours: u16,
 ) -> connectors::Result<()> {
     const TASK: &str = "command executor";
     debug!("start {}", TASK);
+    execute_received_async_commands(&dc, &ac, &ce, &rt, &hr).await;
+    let mut expire_interval = interval(Duration::from_secs(
+        EXPIRE_ASYNC_COMMAND_INTERVAL_IN_SECONDS,
+    ));
     loop {
-        match receiver.recv().await {
+        let signal = tokio::select! {
+            s = receiver.recv() => s,
+            _ = expire_interval.tick() => {
+                expire_async_commands(&dc, async_reply_wait_in_hours).await;
+                continue;
+            }
+        };
+        match signal {
+            Some(m) => {
+                debug!("{} signal {:?}", TASK, m);
+                match m {
+                    SignalCode::Exit => {
+                        return Ok({});
+                    }
+                    SignalCode::ReceivedAsyncCommand => {
+                        execute_received_async_commands(&dc, &ac, &ce, &rt, &hr).await;
+                    }
+                    _ => {}
+                };
+            }
+            None => {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    debug!("{} cancel flag", TASK);
+                    return Ok({});
+                } else {
+                    return Err(errors::ErrorKind::Channel.into());
+                }
+            }
+        };
+    }
+}
+
+async fn expire_async_commands(dc: &connectors::DataConnector, wait_in_hours: u16) {
+    match dc.sended_async_command.expire(wait_in_hours).await {
+        Ok(errors::ErrorCode::ReplyOk) => {}
+        Ok(ec) => error!("command executor expire sended: {:?}", ec),
+        Err(e) => error!("command executor expire sended: {}", e),
+    };
+    match dc.received_async_command.expire(wait_in_hours).await {
+        Ok(errors::ErrorCode::ReplyOk) => {}
+        Ok(ec) => error!("command executor expire received: {:?}", ec),
+        Err(e) => error!("command executor expire received: {}", e),
+    };
+}
+
+async fn execute_received_async_commands(
+    dc: &connectors::DataConnector,
+    ac: &access::AccessChecker,
+    ce: &executors::CommandExecutor,
+    rt: &router::Router,
+    hr: &registry::HandlerRegistry,
+) {
+    let items = match dc
+        .received_async_command
+        .get_by_state(entities::executor::CommandSystemState::Initial.to_string())
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!("command executor get: {}", e);
+            return;
+        }
+    };
+    for mut item in items {
+        let path = match hr
+            .get_path(&item.object_type)
+            .or_else(|| routes::path::get_object_type_path(&item.object_type))
+        {
+            Some(p) => p,
+            None => {
+                error!("command executor: unknown object type {}", item.object_type);
+                continue;
+            }
+        };
+        let mut params = HashMap::<String, String>::new();
+        params.insert("correlation_id".to_string(), item.id.clone());
+        params.insert("object_type".to_string(), item.object_type.clone());
+        params.insert("service_name".to_string(), item.service_name.clone());
+        // command is executed on behalf of caller, as it would be executed synchronously
+        if let Some(u) = &item.authorized_usr_name {
+            params.insert(routes::service::AUTHORIZED_USR_PARAM.to_string(), u.clone());
+        }
+        if let Some(l) = &item.locale {
+            params.insert(routes::service::LOCALE_PARAM.to_string(), l.clone());
+        }
+        let proto = if item.proto == providers::Proto::Http.to_string() {
+            providers::Proto::Http
+        } else if item.proto == providers::Proto::Mq.to_string() {
+            providers::Proto::Mq
+        } else {
+            error!("command executor: unknown proto {}", item.proto);
+            continue;
+        };
+        let response = match routes::service::request_route(
+            path,
+            params,
+            Body::from(item.request_body.clone()),
+            proto,
+            dc,
+            ac,
+            ce,
+            rt,
+            hr,
+        )
+        .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                error!("command executor route: {}", e);
+                continue;
+            }
+        };
+        let mut reply_body = String::new();
+        match hyper::body::aggregate(response.into_body()).await {
+            Ok(b) => {
+                if let Err(e) = b.reader().read_to_string(&mut reply_body) {
+                    error!("command executor read reply: {}", e);
+                    continue;
+                }
+            }
+            Err(e) => {
+                error!("command executor read reply: {}", e);
+                continue;
+            }
+        };
+        match dc
+            .received_async_command
+            .complete(item.id.clone(), reply_body.clone())
+            .await
+        {
+            Ok(errors::ErrorCode::ReplyOk) => {}
+            Ok(ec) => {
+                error!("command executor complete: {:?}", ec);
+                continue;
+            }
+            Err(e) => {
+                error!("command executor complete: {}", e);
+                continue;
+            }
+        };
+        item.reply_body = reply_body;
+        if let Err(e) = ce.send_async_reply(&item).await {
+            warn!("command executor send reply: {}", e);
+        }
+    }
+}
+
+pub async fn access_checker_worker(
+    cancel_flag: Arc<AtomicBool>,
+    mut receiver: mpsc::Receiver<SignalCode>,
+    dc: Arc<connectors::DataConnector>,
+    ac: Arc<access::AccessChecker>,
+    reload_interval_in_seconds: Option<u16>,
+) -> connectors::Result<()> {
+    const TASK: &str = "access checker";
+    debug!("start {}", TASK);
+    // users are loaded on start, so first reload is after interval,
+    // without interval or with zero one users are reloaded by signal only
+    let mut reload_interval = reload_interval_in_seconds.filter(|s| *s > 0).map(|s| {
+        let period = Duration::from_secs(s as u64);
+        interval_at(Instant::now() + period, period)
+    });
+    loop {
+        let signal = tokio::select! {
+            s = receiver.recv() => s,
+            _ = async { reload_interval.as_mut().unwrap().tick().await },
+                if reload_interval.is_some() => {
+                if let Err(e) = ac.reload(&dc).await {
+                    error!("{} reload: {}", TASK, e);
+                }
+                continue;
+            }
+        };
+        match signal {
             Some(m) => {
                 debug!("{} signal {:?}", TASK, m);
                 match m {
                     SignalCode::Exit => {
                         return Ok({});
                     }
+                    SignalCode::AccessReload => {
+                        if let Err(e) = ac.reload(&dc).await {
+                            error!("{} reload: {}", TASK, e);
+                        }
+                    }
                     _ => {}
                 };
             }
@@ -118,9 +574,467 @@ pub async fn command_executor_worker(
                     debug!("{} cancel flag", TASK);
                     return Ok({});
                 } else {
-                    return Err(errors::ChannelError.into());
+                    return Err(errors::ErrorKind::Channel.into());
                 }
             }
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{handlers, replies, settings, tests};
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    async fn get_access_checker(
+        dc: &connectors::DataConnector,
+    ) -> (
+        Arc<access::AccessChecker>,
+        mpsc::Sender<SignalCode>,
+        mpsc::Receiver<SignalCode>,
+    ) {
+        let (sender, receiver) = mpsc::channel::<SignalCode>(5);
+        let ac = access::AccessChecker::from_data_connector(
+            dc,
+            &settings::Authentication {
+                server: HashMap::new(),
+                client: vec![],
+            },
+            sender.clone(),
+        )
+        .await
+        .unwrap();
+        (Arc::new(ac), sender, receiver)
+    }
+
+    async fn get_mq_stack() -> (
+        Arc<connectors::DataConnector>,
+        Arc<access::AccessChecker>,
+        Arc<executors::CommandExecutor>,
+        Arc<router::Router>,
+        Arc<registry::HandlerRegistry>,
+        providers::MqProvider,
+    ) {
+        let dc = Arc::new(tests::get_memory_data_connector().await);
+        let (ac, sender, _) = get_access_checker(&dc).await;
+        let hp = providers::HttpProvider::new(Duration::from_secs(1))
+            .await
+            .unwrap();
+        let mp = providers::MqProvider::new(
+            Some(Arc::new(providers::MqSession::new())),
+            Some("client".to_string()),
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+        let rt = Arc::new(
+            router::Router::new(
+                dc.clone(),
+                ac.clone(),
+                hp.clone(),
+                mp.clone(),
+                None,
+                HashMap::new(),
+                HashMap::new(),
+                "localhost",
+                "localhost",
+            )
+            .await
+            .unwrap(),
+        );
+        let ce = Arc::new(
+            executors::CommandExecutor::new(
+                dc.clone(),
+                ac.clone(),
+                rt.clone(),
+                hp.clone(),
+                mp.clone(),
+                sender.clone(),
+                1,
+                "test",
+            )
+            .await
+            .unwrap(),
+        );
+        let ep = Arc::new(
+            publishers::EventPublisher::new(
+                dc.clone(),
+                ac.clone(),
+                rt.clone(),
+                hp,
+                mp.clone(),
+                sender,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap(),
+        );
+        let mut hr = registry::HandlerRegistry::new();
+        handlers::register_service(&mut hr, dc.clone(), ac.clone(), rt.clone(), ep);
+        let mut service_hr = registry::HandlerRegistry::new();
+        handlers::register(&mut service_hr, dc.clone());
+        hr.merge(service_hr);
+        (dc, ac, ce, rt, Arc::new(hr), mp)
+    }
+
+    fn get_message(authorization: Option<String>) -> providers::MqMessage {
+        let mut properties = HashMap::<String, String>::new();
+        properties.insert(providers::MQ_MESSAGE_ID.to_string(), "1".to_string());
+        properties.insert(providers::MQ_REPLY_TO.to_string(), "client".to_string());
+        properties.insert("correlation_id".to_string(), "2".to_string());
+        properties.insert("object_type".to_string(), "GetCar".to_string());
+        if let Some(a) = authorization {
+            properties.insert(providers::MQ_AUTHORIZATION.to_string(), a);
+        }
+        providers::MqMessage {
+            properties: properties,
+            body: "{}".to_string(),
+        }
+    }
+
+    async fn dispatch(message: providers::MqMessage) -> providers::MqMessage {
+        let (dc, ac, ce, rt, hr, mp) = get_mq_stack().await;
+        let receiver = mp.get_session().unwrap().receiver("client").await.unwrap();
+        mq_dispatch(message, dc, ac, ce, rt, hr, mp).await;
+        receiver.receive().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn mq_dispatch_rejects_message_without_authorization() {
+        let reply = dispatch(get_message(None)).await;
+        assert_eq!(reply.properties.get(providers::MQ_STATUS).unwrap(), "401");
+        assert_eq!(
+            reply.properties.get(providers::MQ_IN_REPLY_TO).unwrap(),
+            "1"
+        );
+        let reply = dispatch(get_message(Some(
+            access::AccessChecker::get_basic_authorization_token(
+                &"user1".to_string(),
+                &"wrong".to_string(),
+            ),
+        )))
+        .await;
+        assert_eq!(reply.properties.get(providers::MQ_STATUS).unwrap(), "401");
+    }
+
+    #[tokio::test]
+    async fn mq_dispatch_routes_authorized_message() {
+        let reply = dispatch(get_message(get_authorization())).await;
+        assert_eq!(reply.properties.get(providers::MQ_STATUS).unwrap(), "200");
+        assert_eq!(reply.properties.get("correlation_id").unwrap(), "2");
+    }
+
+    fn get_authorization() -> Option<String> {
+        Some(access::AccessChecker::get_basic_authorization_token(
+            &"user1".to_string(),
+            &"pass1".to_string(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn mq_dispatch_routes_state_query_by_path() {
+        let (dc, ac, ce, rt, hr, mp) = get_mq_stack().await;
+        let now = chrono::Utc::now();
+        dc.received_async_command
+            .add(vec![entities::executor::ReceivedAsyncCommand {
+                id: "3".to_string(),
+                object_type: "GetCar".to_string(),
+                service_name: "test".to_string(),
+                request_body: "{}".to_string(),
+                state: "received".to_string(),
+                change_state_event: 0,
+                reply_body: String::new(),
+                proto: providers::Proto::Mq.to_string(),
+                reply_to: "client".to_string(),
+                added_at: now,
+                state_changed_at: now,
+                history: None,
+                authorized_usr_name: None,
+                locale: None,
+            }])
+            .await
+            .unwrap();
+        let receiver = mp.get_session().unwrap().receiver("client").await.unwrap();
+        let mut message = get_message(get_authorization());
+        message
+            .properties
+            .insert("async_command_id".to_string(), "3".to_string());
+        message.properties.insert(
+            providers::MQ_PATH.to_string(),
+            routes::path::STATE.to_string(),
+        );
+        mq_dispatch(message, dc, ac, ce, rt, hr, mp).await;
+        let reply = receiver.receive().await.unwrap();
+        assert_eq!(reply.properties.get(providers::MQ_STATUS).unwrap(), "200");
+        let state: entities::executor::AsyncCommandState =
+            serde_json::from_str(&reply.body).unwrap();
+        assert_eq!(state.id, "3");
+        assert_eq!(state.state, "received");
+    }
+
+    #[tokio::test]
+    async fn mq_dispatch_routes_by_object_type_without_path() {
+        let mut message = get_message(get_authorization());
+        message
+            .properties
+            .insert("async_command_id".to_string(), "3".to_string());
+        let reply = dispatch(message).await;
+        assert_eq!(reply.properties.get(providers::MQ_STATUS).unwrap(), "200");
+    }
+
+    #[tokio::test]
+    async fn mq_dispatch_replies_validation_errors_with_context() {
+        let mut message = get_message(get_authorization());
+        message
+            .properties
+            .insert("object_type".to_string(), "AddCar".to_string());
+        message.body = r#"{"items": "audi"}"#.to_string();
+        let reply = dispatch(message).await;
+        assert_eq!(reply.properties.get(providers::MQ_STATUS).unwrap(), "400");
+        let reply: replies::common::ErrorReply = serde_json::from_str(&reply.body).unwrap();
+        assert_eq!(reply.error_code, errors::ErrorCode::BadRequestError);
+        assert_eq!(reply.correlation_id.unwrap(), "2");
+        assert_eq!(reply.object_type.unwrap(), "AddCar");
+        assert_eq!(reply.errors.len(), 1);
+        assert_eq!(reply.errors[0].pointer, "/items");
+    }
+
+    #[tokio::test]
+    async fn mq_receiver_worker_serves_execute() {
+        let (dc, ac, ce, rt, hr, mp) = get_mq_stack().await;
+        let (sender, receiver) = mpsc::channel::<SignalCode>(5);
+        let worker_mp = mp.clone();
+        let worker = tokio::spawn(async move {
+            mq_receiver_worker(
+                Arc::new(AtomicBool::new(false)),
+                receiver,
+                dc,
+                ac,
+                ce,
+                rt,
+                hr,
+                worker_mp,
+                vec!["service".to_string()],
+            )
+            .await
+            .is_ok()
+        });
+        let mut prop = HashMap::<&str, &str>::new();
+        prop.insert("correlation_id", "1");
+        prop.insert("object_type", "GetCar");
+        let body = mp
+            .execute(
+                "service",
+                prop.clone(),
+                get_authorization().unwrap(),
+                Body::from("{}"),
+            )
+            .await
+            .ok();
+        assert!(body.is_some());
+        let reply = mp
+            .execute("service", prop, "Basic 1".to_string(), Body::from("{}"))
+            .await;
+        assert!(reply.is_err());
+        sender.send(SignalCode::Exit).await.unwrap();
+        assert!(worker.await.unwrap());
+    }
+
+    fn spawn_mq_receiver_worker(
+        receiver: mpsc::Receiver<SignalCode>,
+        stack: (
+            Arc<connectors::DataConnector>,
+            Arc<access::AccessChecker>,
+            Arc<executors::CommandExecutor>,
+            Arc<router::Router>,
+            Arc<registry::HandlerRegistry>,
+            providers::MqProvider,
+        ),
+    ) -> tokio::task::JoinHandle<Result<(), String>> {
+        let (dc, ac, ce, rt, hr, mp) = stack;
+        tokio::spawn(async move {
+            mq_receiver_worker(
+                Arc::new(AtomicBool::new(false)),
+                receiver,
+                dc,
+                ac,
+                ce,
+                rt,
+                hr,
+                mp,
+                vec!["service".to_string()],
+            )
+            .await
+            .map_err(|e| e.to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn mq_receiver_worker_reconnects_lost_receiver() {
+        let stack = get_mq_stack().await;
+        let mp = stack.5.clone();
+        let (sender, receiver) = mpsc::channel::<SignalCode>(5);
+        let worker = spawn_mq_receiver_worker(receiver, stack);
+        let mut prop = HashMap::<&str, &str>::new();
+        prop.insert("correlation_id", "1");
+        prop.insert("object_type", "GetCar");
+        let execute = || {
+            mp.execute(
+                "service",
+                prop.clone(),
+                get_authorization().unwrap(),
+                Body::from("{}"),
+            )
+        };
+        // first reply shows that listener is started
+        assert!(execute().await.is_ok());
+        mp.get_session().unwrap().close("service").await;
+        assert!(execute().await.is_ok());
+        sender.send(SignalCode::Exit).await.unwrap();
+        assert!(worker.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn mq_receiver_worker_fails_when_reconnect_attempts_are_over() {
+        let stack = get_mq_stack().await;
+        let session = stack.5.get_session().unwrap();
+        let (_sender, receiver) = mpsc::channel::<SignalCode>(5);
+        let worker = spawn_mq_receiver_worker(receiver, stack);
+        while !session.is_taken("service").await {
+            tokio::task::yield_now().await;
+        }
+        // queue of new address is taken by other receiver, so reconnect fails
+        session.close("service").await;
+        let _taken = session.receiver("service").await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), worker)
+            .await
+            .expect("worker is stopped")
+            .unwrap();
+        match result {
+            Err(e) => assert!(e.contains("service")),
+            Ok(_) => panic!("worker is failed"),
+        }
+    }
+
+    fn get_received_async_command(
+        id: &str,
+        request_body: &str,
+        authorized_usr_name: Option<&str>,
+        locale: Option<&str>,
+    ) -> entities::executor::ReceivedAsyncCommand {
+        let now = chrono::Utc::now();
+        entities::executor::ReceivedAsyncCommand {
+            id: id.to_string(),
+            object_type: "DisableUsr".to_string(),
+            service_name: "test".to_string(),
+            request_body: request_body.to_string(),
+            state: entities::executor::CommandSystemState::Initial.to_string(),
+            change_state_event: 0,
+            reply_body: String::new(),
+            proto: providers::Proto::Mq.to_string(),
+            reply_to: "client".to_string(),
+            added_at: now,
+            state_changed_at: now,
+            history: None,
+            authorized_usr_name: authorized_usr_name.map(|u| u.to_string()),
+            locale: locale.map(|l| l.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn received_async_command_runs_on_behalf_of_caller() {
+        let (dc, ac, ce, rt, hr, _mp) = get_mq_stack().await;
+        let body = r#"{"usr_names":["user1"]}"#;
+        dc.received_async_command
+            .add(vec![
+                get_received_async_command("1", body, None, None),
+                get_received_async_command("2", body, Some("user1"), None),
+                get_received_async_command("3", "{}", Some("user1"), Some("ru")),
+            ])
+            .await
+            .unwrap();
+        execute_received_async_commands(&dc, &ac, &ce, &rt, &hr).await;
+        let (items, _) = dc
+            .received_async_command
+            .get(None, &Default::default())
+            .await
+            .unwrap();
+        let reply = |id: &str| -> replies::common::ErrorReply {
+            let item = items.iter().find(|c| c.id == id).unwrap();
+            serde_json::from_str(&item.reply_body).unwrap()
+        };
+        assert_eq!(reply("1").error_code, errors::ErrorCode::UnauthorizedError);
+        assert_eq!(reply("2").error_code, errors::ErrorCode::ReplyOk);
+        let error_code = errors::ErrorCode::BadRequestError;
+        assert_eq!(reply("3").error_code, error_code);
+        assert_eq!(
+            reply("3").error_name,
+            Some(dc.error.get_name(&error_code, Some("ru")))
+        );
+        assert_ne!(
+            dc.error.get_name(&error_code, Some("ru")),
+            dc.error.get_name(&error_code, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn access_checker_with_zero_interval_waits_for_signal() {
+        let gets = Arc::new(AtomicUsize::new(0));
+        let mut dc = tests::get_fake_data_connector();
+        dc.usr = Box::new(tests::fakes::usr::UsrCollection::with_counter(gets.clone()));
+        let dc = Arc::new(dc);
+        let (ac, sender, receiver) = get_access_checker(&dc).await;
+        // users are loaded once on start
+        let loads = gets.load(Ordering::SeqCst);
+        let (worker_dc, worker_ac) = (dc.clone(), ac.clone());
+        let worker = tokio::spawn(async move {
+            access_checker_worker(
+                Arc::new(AtomicBool::new(false)),
+                receiver,
+                worker_dc,
+                worker_ac,
+                Some(0),
+            )
+            .await
+            .is_ok()
+        });
+        // zero interval does not reload users without signal
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(gets.load(Ordering::SeqCst) - loads, 0);
+        sender.send(SignalCode::AccessReload).await.unwrap();
+        sender.send(SignalCode::Exit).await.unwrap();
+        assert!(worker.await.unwrap());
+        assert_eq!(gets.load(Ordering::SeqCst) - loads, 1);
+    }
+
+    #[tokio::test]
+    async fn access_checker_reloads_on_signal() {
+        let dc = Arc::new(tests::get_memory_data_connector().await);
+        let (ac, sender, receiver) = get_access_checker(&dc).await;
+        let header = access::AccessChecker::get_basic_authorization_token(
+            &"user1".to_string(),
+            &"pass1".to_string(),
+        );
+        assert!(ac.is_authorized_by_header(&header).await);
+        dc.usr.disable(vec!["user1".to_string()]).await.unwrap();
+        let (worker_dc, worker_ac) = (dc.clone(), ac.clone());
+        let worker = tokio::spawn(async move {
+            access_checker_worker(
+                Arc::new(AtomicBool::new(false)),
+                receiver,
+                worker_dc,
+                worker_ac,
+                None,
+            )
+            .await
+            .is_ok()
+        });
+        sender.send(SignalCode::AccessReload).await.unwrap();
+        sender.send(SignalCode::Exit).await.unwrap();
+        assert!(worker.await.unwrap());
+        assert!(!ac.is_authorized_by_header(&header).await);
+    }
+}